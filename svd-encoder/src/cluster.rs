@@ -1,9 +1,8 @@
-use super::{
-    new_node, Config, Element, ElementMerge, Encode, EncodeChildren, EncodeError, XMLNode,
-};
+use super::{encode_commented, new_node, Config, Element, ElementMerge, Encode, EncodeError};
 
 use crate::{
-    config::{change_case, format_number},
+    config::{change_case, format_literal},
+    registerproperties::encode_properties,
     svd::{Cluster, ClusterInfo},
 };
 
@@ -51,18 +50,21 @@ impl Encode for ClusterInfo {
 
         e.children.push(new_node(
             "addressOffset",
-            format_number(self.address_offset, config.cluster_address_offset),
+            format_literal(
+                "addressOffset",
+                self.address_offset,
+                config.cluster_address_offset,
+                &self.literals,
+            ),
         ));
 
-        e.children.extend(
-            self.default_register_properties
-                .encode_with_config(config)?,
-        );
+        e.children.extend(encode_properties(
+            &self.default_register_properties,
+            &self.literals,
+            config,
+        )?);
 
-        for c in &self.children {
-            e.children
-                .push(XMLNode::Element(c.encode_with_config(config)?));
-        }
+        e.children.extend(encode_commented(&self.children, config)?);
 
         if let Some(v) = &self.derived_from {
             e.attributes.insert(
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use convert_case::{Boundary, Case, Casing};
 
-use crate::svd::BitRangeType;
+use crate::svd::{BitRangeType, Literals};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum IdentifierFormat {
@@ -130,7 +130,7 @@ where
         NumberFormat::UpperHex => format!("{:#X}", value),
         NumberFormat::UpperHex8 => format!("{:#010X}", value),
         NumberFormat::UpperHex16 => {
-            if value.into() > std::u32::MAX as u64 {
+            if value.into() > u32::MAX as u64 {
                 format!("{:#018X}", value)
             } else {
                 format!("{:#010X}", value)
@@ -139,7 +139,7 @@ where
         NumberFormat::LowerHex => format!("{:#x}", value),
         NumberFormat::LowerHex8 => format!("{:#010x}", value),
         NumberFormat::LowerHex16 => {
-            if value.into() > std::u32::MAX as u64 {
+            if value.into() > u32::MAX as u64 {
                 format!("{:#018x}", value)
             } else {
                 format!("{:#010x}", value)
@@ -150,6 +150,23 @@ where
     }
 }
 
+/// Write `value` of the child element `tag` as it was in the SVD file if it wasn't modified,
+/// otherwise format it with `format`
+pub fn format_literal<T>(tag: &str, value: T, format: NumberFormat, literals: &Literals) -> String
+where
+    T: std::fmt::UpperHex
+        + std::fmt::LowerHex
+        + std::fmt::Display
+        + std::fmt::Binary
+        + Into<u64>
+        + Copy,
+{
+    match literals.get(tag, value.into()) {
+        Some(text) => text.to_string(),
+        None => format_number(value, format),
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct FieldBitRangeFormat(pub BitRangeType);
 
@@ -253,6 +270,72 @@ impl FromStr for RcSorting {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Indentation of the SVD (XML) output
+pub enum Indent {
+    /// Everything on one line
+    None,
+    /// Indent each level with the number of spaces
+    Spaces(usize),
+    /// Indent each level with a tab
+    Tab,
+}
+
+impl FromStr for Indent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "None" => Ok(Indent::None),
+            "Tab" => Ok(Indent::Tab),
+            _ => s.parse().map(Indent::Spaces).map_err(|_| ()),
+        }
+    }
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Self::Spaces(2)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Line ending of the SVD (XML) output
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        Self::Lf
+    }
+}
+
+impl LineEnding {
+    /// Get the line separator
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+impl FromStr for LineEnding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LF" => Ok(Self::Lf),
+            "CRLF" => Ok(Self::CrLf),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[non_exhaustive]
 /// Advanced encoder options
@@ -367,6 +450,15 @@ pub struct Config {
 
     /// Format of dimArrayIndex's headerEnumName element
     pub dim_array_index_header_enum_name: Option<IdentifierFormat>,
+
+    /// Add a comment with the absolute address after each register
+    pub annotate_addresses: bool,
+
+    /// Indentation of the SVD (XML) output
+    pub indent: Indent,
+
+    /// Line ending of the SVD (XML) output
+    pub line_ending: LineEnding,
 }
 
 impl Default for Config {
@@ -395,6 +487,9 @@ impl Default for Config {
             dim_dim: NumberFormat::Dec,
             dim_increment: NumberFormat::UpperHex,
             dim_array_index_header_enum_name: None,
+            annotate_addresses: false,
+            indent: Default::default(),
+            line_ending: Default::default(),
         }
     }
 }
@@ -430,6 +525,9 @@ impl Config {
             "dim_array_index_header_enum_name" => {
                 self.dim_array_index_header_enum_name = Some(value.parse().unwrap())
             }
+            "annotate_addresses" => self.annotate_addresses = value.parse().unwrap(),
+            "indent" => self.indent = value.parse().unwrap(),
+            "line_ending" => self.line_ending = value.parse().unwrap(),
             _ => {
                 eprintln!("Unknown config key: {}", name);
             }
@@ -597,4 +695,27 @@ impl Config {
         self.dim_increment = val;
         self
     }
+
+    /// Add a comment with the absolute address after each register,
+    /// the address of the first element for arrays
+    pub fn annotate_addresses(mut self, val: bool) -> Self {
+        self.annotate_addresses = val;
+        self
+    }
+
+    /// Indentation of the SVD (XML) output
+    ///
+    /// format: None, Tab, number of spaces
+    pub fn indent(mut self, val: Indent) -> Self {
+        self.indent = val;
+        self
+    }
+
+    /// Line ending of the SVD (XML) output
+    ///
+    /// format: LF, CRLF
+    pub fn line_ending(mut self, val: LineEnding) -> Self {
+        self.line_ending = val;
+        self
+    }
 }
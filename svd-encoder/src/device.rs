@@ -1,6 +1,8 @@
 use svd_rs::Peripheral;
 
-use super::{new_node, Config, Element, Encode, EncodeChildren, EncodeError, XMLNode};
+use super::{
+    encode_commented, new_node, Config, Element, Encode, EncodeChildren, EncodeError, XMLNode,
+};
 use crate::{
     config::{DerivableSorting, Sorting},
     svd::Device,
@@ -62,18 +64,14 @@ impl Encode for Device {
                 .encode_with_config(config)?,
         );
 
-        let peripherals: Result<Vec<_>, _> =
-            if config.peripheral_sorting == DerivableSorting::Unchanged(None) {
-                self.peripherals
-                    .iter()
-                    .map(|peripheral| peripheral.encode_node_with_config(config))
-                    .collect()
-            } else {
-                sort_derived_peripherals(&self.peripherals, config.peripheral_sorting)
-                    .into_iter()
-                    .map(|peripheral| peripheral.encode_node_with_config(config))
-                    .collect()
-            };
+        let peripherals = if config.peripheral_sorting == DerivableSorting::Unchanged(None) {
+            encode_commented(&self.peripherals, config)
+        } else {
+            encode_commented(
+                sort_derived_peripherals(&self.peripherals, config.peripheral_sorting),
+                config,
+            )
+        };
 
         elem.children.push({
             let mut e = Element::new("peripherals");
@@ -81,6 +79,18 @@ impl Encode for Device {
             XMLNode::Element(e)
         });
 
+        if let Some(v) = &self.vendor_extensions {
+            match Element::parse(v.as_bytes()) {
+                Ok(e) => elem.children.push(XMLNode::Element(e)),
+                // not well-formed, keep the content as text
+                Err(_) => {
+                    let mut e = Element::new("vendorExtensions");
+                    e.children.push(XMLNode::Text(v.clone()));
+                    elem.children.push(XMLNode::Element(e));
+                }
+            }
+        }
+
         elem.attributes
             .insert(String::from("schemaVersion"), self.schema_version.clone());
         elem.attributes
@@ -9,27 +9,60 @@ impl Encode for EnumeratedValue {
     type Error = EncodeError;
 
     fn encode_with_config(&self, config: &Config) -> Result<Element, EncodeError> {
-        let mut base = Element::new("enumeratedValue");
-        base.children.push(new_node(
-            "name",
-            change_case(&self.name, config.enumerated_value_name),
-        ));
+        encode_enumerated_value(self, None, config)
+    }
+}
 
-        if let Some(d) = &self.description {
-            base.children.push(new_node("description", d.clone()));
-        };
+/// Encode the value of a field `width` bits wide, or as narrow as possible if `None`
+pub(crate) fn encode_enumerated_value(
+    ev: &EnumeratedValue,
+    width: Option<u32>,
+    config: &Config,
+) -> Result<Element, EncodeError> {
+    let mut base = Element::new("enumeratedValue");
+    base.children.push(new_node(
+        "name",
+        change_case(&ev.name, config.enumerated_value_name),
+    ));
 
-        if let Some(v) = &self.value {
+    if let Some(d) = &ev.description {
+        base.children.push(new_node("description", d.clone()));
+    };
+
+    match (ev.value, ev.mask) {
+        (Some(v), Some(mask)) => {
+            base.children
+                .push(new_node("value", format_dont_care(v, mask, width)));
+        }
+        (Some(v), None) => {
             base.children.push(new_node(
                 "value",
-                format_number(*v, config.enumerated_value_value),
+                format_number(v, config.enumerated_value_value),
             ));
-        };
+        }
+        _ => {}
+    }
 
-        if let Some(v) = &self.is_default {
-            base.children.push(new_node("isDefault", format!("{}", v)));
-        };
+    if let Some(v) = &ev.is_default {
+        base.children.push(new_node("isDefault", format!("{}", v)));
+    };
 
-        Ok(base)
-    }
+    Ok(base)
+}
+
+/// Formats value as `#` binary literal with `x` for bits not in `mask`.
+///
+/// At least `width` digits are written, so leading don't-care bits are kept.
+fn format_dont_care(value: u64, mask: u64, width: Option<u32>) -> String {
+    let width = (64 - (value | mask).leading_zeros())
+        .max(width.unwrap_or(0).min(64))
+        .max(1);
+    let digits = (0..width)
+        .rev()
+        .map(|i| match (mask >> i & 1, value >> i & 1) {
+            (0, _) => 'x',
+            (_, 0) => '0',
+            _ => '1',
+        });
+    core::iter::once('#').chain(digits).collect()
 }
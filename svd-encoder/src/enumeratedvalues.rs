@@ -1,35 +1,52 @@
-use super::{new_node, Config, Element, Encode, EncodeError};
+use super::{new_node, Config, Element, Encode, EncodeError, XMLNode};
 
-use crate::{config::change_case, svd::EnumeratedValues};
+use crate::{config::change_case, enumeratedvalue::encode_enumerated_value, svd::EnumeratedValues};
 
 impl Encode for EnumeratedValues {
     type Error = EncodeError;
 
     fn encode_with_config(&self, config: &Config) -> Result<Element, EncodeError> {
-        let mut base = Element::new("enumeratedValues");
-
-        if let Some(d) = &self.name {
-            base.children.push(new_node(
-                "name",
-                change_case(d, config.enumerated_values_name),
-            ));
-        };
-
-        if let Some(v) = &self.usage {
-            base.children.push(v.encode_node()?);
-        };
-
-        if let Some(v) = &self.derived_from {
-            base.attributes.insert(
-                String::from("derivedFrom"),
-                change_case(v, config.enumerated_values_name),
-            );
-        }
-
-        for v in &self.values {
-            base.children.push(v.encode_node_with_config(config)?);
-        }
-
-        Ok(base)
+        encode_enumerated_values(self, None, config)
     }
 }
+
+/// Encode the values of a field `width` bits wide, or without a known width if `None`
+pub(crate) fn encode_enumerated_values(
+    evs: &EnumeratedValues,
+    width: Option<u32>,
+    config: &Config,
+) -> Result<Element, EncodeError> {
+    let mut base = Element::new("enumeratedValues");
+
+    if let Some(d) = &evs.name {
+        base.children.push(new_node(
+            "name",
+            change_case(d, config.enumerated_values_name),
+        ));
+    };
+
+    if let Some(d) = &evs.header_enum_name {
+        base.children.push(new_node(
+            "headerEnumName",
+            change_case(d, config.enumerated_values_name),
+        ));
+    };
+
+    if let Some(v) = &evs.usage {
+        base.children.push(v.encode_node()?);
+    };
+
+    if let Some(v) = &evs.derived_from {
+        base.attributes.insert(
+            String::from("derivedFrom"),
+            change_case(v, config.enumerated_values_name),
+        );
+    }
+
+    for v in &evs.values {
+        base.children
+            .push(XMLNode::Element(encode_enumerated_value(v, width, config)?));
+    }
+
+    Ok(base)
+}
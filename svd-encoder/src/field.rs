@@ -1,5 +1,6 @@
 use super::{new_node, Config, Element, ElementMerge, Encode, EncodeError, XMLNode};
 use crate::bitrange::encode_bitrange;
+use crate::enumeratedvalues::encode_enumerated_values;
 
 use crate::config::change_case;
 use crate::svd::{Field, FieldInfo};
@@ -53,10 +54,17 @@ impl Encode for FieldInfo {
             elem.children.push(v.encode_node()?);
         }
 
+        if let Some(v) = &self.data_type {
+            elem.children.push(v.encode_node()?);
+        }
+
         let enumerated_values: Result<Vec<XMLNode>, EncodeError> = self
             .enumerated_values
             .iter()
-            .map(|v| v.encode_node_with_config(config))
+            .map(|v| {
+                encode_enumerated_values(v, Some(self.bit_range.width), config)
+                    .map(XMLNode::Element)
+            })
             .collect();
         elem.children.append(&mut enumerated_values?);
 
@@ -7,7 +7,8 @@ use crate::svd::Device;
 use xmltree::{Element, EmitterConfig, XMLNode};
 
 pub use crate::config::{
-    Config, DerivableSorting, IdentifierFormat, NumberFormat, RcSorting, Sorting,
+    Config, DerivableSorting, IdentifierFormat, Indent, LineEnding, NumberFormat, RcSorting,
+    Sorting,
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
@@ -54,12 +55,33 @@ pub fn encode_with_config(d: &Device, config: &Config) -> Result<String, EncodeE
     let root = d.encode_with_config(config)?;
     let mut wr = Vec::new();
     let mut cfg = EmitterConfig::new();
-    cfg.perform_indent = true;
+    match config.indent {
+        Indent::None => cfg.perform_indent = false,
+        Indent::Spaces(n) => {
+            cfg.perform_indent = true;
+            cfg.indent_string = " ".repeat(n).into();
+        }
+        Indent::Tab => {
+            cfg.perform_indent = true;
+            cfg.indent_string = "\t".into();
+        }
+    }
+    cfg.line_separator = config.line_ending.as_str().into();
     cfg.pad_self_closing = false;
     root.write_with_config(&mut wr, cfg).unwrap();
     Ok(String::from_utf8(wr).unwrap())
 }
 
+/// Validates a device object recursively and encodes it to an SVD (XML) string if it is valid
+pub fn encode_validated(
+    d: &Device,
+    lvl: svd::ValidateLevel,
+    config: &Config,
+) -> Result<String, svd::SvdError> {
+    d.validate_all(lvl)?;
+    encode_with_config(d, config).map_err(|e| match e {})
+}
+
 /// Defines extensions for implementation over xmltree::Element
 trait ElementMerge {
     fn merge(&mut self, n: &Self);
@@ -75,6 +97,48 @@ impl ElementMerge for Element {
     }
 }
 
+/// Comments which are emitted before the element
+pub(crate) trait Comments {
+    fn comments(&self) -> &[String];
+}
+
+impl Comments for svd::Peripheral {
+    fn comments(&self) -> &[String] {
+        &self.comments
+    }
+}
+
+impl Comments for svd::RegisterCluster {
+    fn comments(&self) -> &[String] {
+        match self {
+            Self::Register(r) => &r.comments,
+            Self::Cluster(c) => &c.comments,
+        }
+    }
+}
+
+impl Comments for svd::Field {
+    fn comments(&self) -> &[String] {
+        &self.comments
+    }
+}
+
+/// Encodes `items` with their comments before each of them
+pub(crate) fn encode_commented<'a, T>(
+    items: impl IntoIterator<Item = &'a T>,
+    config: &Config,
+) -> Result<Vec<XMLNode>, EncodeError>
+where
+    T: Encode<Error = EncodeError> + Comments + 'a,
+{
+    let mut nodes = Vec::new();
+    for item in items {
+        nodes.extend(item.comments().iter().cloned().map(XMLNode::Comment));
+        nodes.push(item.encode_node_with_config(config)?);
+    }
+    Ok(nodes)
+}
+
 /// Helper to create new base xml nodes
 pub(crate) fn new_node(name: &str, text: String) -> XMLNode {
     let mut e = Element::new(name);
@@ -88,6 +152,7 @@ mod bitrange;
 mod cluster;
 mod config;
 mod cpu;
+mod datatype;
 mod device;
 mod dimelement;
 mod endian;
@@ -102,5 +167,6 @@ mod readaction;
 mod register;
 mod registercluster;
 mod registerproperties;
+mod sauregion;
 mod usage;
 mod writeconstraint;
@@ -1,11 +1,12 @@
 use svd_rs::RegisterCluster;
 
 use super::{
-    new_node, Config, Element, ElementMerge, Encode, EncodeChildren, EncodeError, XMLNode,
+    encode_commented, new_node, Config, Element, ElementMerge, Encode, EncodeError, XMLNode,
 };
 
 use crate::{
-    config::{change_case, format_number, DerivableSorting, RcSorting, Sorting},
+    config::{change_case, format_literal, DerivableSorting, RcSorting, Sorting},
+    registerproperties::encode_properties,
     svd::{Peripheral, PeripheralInfo},
 };
 
@@ -81,13 +82,19 @@ impl Encode for PeripheralInfo {
 
         elem.children.push(new_node(
             "baseAddress",
-            format_number(self.base_address, config.peripheral_base_address),
+            format_literal(
+                "baseAddress",
+                self.base_address,
+                config.peripheral_base_address,
+                &self.literals,
+            ),
         ));
 
-        elem.children.extend(
-            self.default_register_properties
-                .encode_with_config(config)?,
-        );
+        elem.children.extend(encode_properties(
+            &self.default_register_properties,
+            &self.literals,
+            config,
+        )?);
 
         if let Some(v) = &self.address_block {
             for ab in v {
@@ -104,26 +111,21 @@ impl Encode for PeripheralInfo {
         elem.children.append(&mut interrupts?);
 
         if let Some(v) = &self.registers {
-            let children: Result<Vec<_>, _> = match config.register_cluster_sorting {
-                RcSorting::Unchanged(DerivableSorting::Unchanged(None)) => v
-                    .iter()
-                    .map(|e| e.encode_node_with_config(config))
-                    .collect(),
-                RcSorting::Unchanged(sorting) => sort_derived_register_cluster(v, sorting)
-                    .into_iter()
-                    .map(|e| e.encode_node_with_config(config))
-                    .collect(),
-                RcSorting::RegistersFirst(sorting) => rc_sort(v, sorting, true)
-                    .map(|e| e.encode_node_with_config(config))
-                    .collect(),
-                RcSorting::ClustersFirst(sorting) => rc_sort(v, sorting, false)
-                    .map(|e| e.encode_node_with_config(config))
-                    .collect(),
+            let refs = match config.register_cluster_sorting {
+                RcSorting::Unchanged(DerivableSorting::Unchanged(None)) => v.iter().collect(),
+                RcSorting::Unchanged(sorting) => sort_derived_register_cluster(v, sorting),
+                RcSorting::RegistersFirst(sorting) => rc_sort(v, sorting, true).collect(),
+                RcSorting::ClustersFirst(sorting) => rc_sort(v, sorting, false).collect(),
             };
 
+            let mut children = encode_commented(refs.iter().copied(), config)?;
+            if config.annotate_addresses {
+                annotate_addresses(&mut children, refs, self.base_address);
+            }
+
             elem.children.push({
                 let mut e = Element::new("registers");
-                e.children = children?;
+                e.children = children;
                 XMLNode::Element(e)
             });
         }
@@ -201,3 +203,38 @@ fn rc_sort(
         c_refs.into_iter().chain(reg_refs)
     }
 }
+
+/// Adds a comment with the absolute address after each register element,
+/// `base` is the address of the parent and arrays are annotated with their first element.
+///
+/// `rcs` are the registers and clusters in the order they were encoded into `children`.
+fn annotate_addresses<'a>(
+    children: &mut Vec<XMLNode>,
+    rcs: impl IntoIterator<Item = &'a RegisterCluster>,
+    base: u64,
+) {
+    let mut rcs = rcs.into_iter();
+    let mut annotated = Vec::with_capacity(children.len() * 2);
+    for mut node in children.drain(..) {
+        let mut comment = None;
+        if let XMLNode::Element(e) = &mut node {
+            if e.name == "register" || e.name == "cluster" {
+                match rcs.next() {
+                    Some(RegisterCluster::Register(r)) => {
+                        let address = base + r.address_offset as u64;
+                        comment = Some(XMLNode::Comment(format!(" @ {:#010x} ", address)))
+                    }
+                    Some(RegisterCluster::Cluster(c)) => annotate_addresses(
+                        &mut e.children,
+                        &c.children,
+                        base + c.address_offset as u64,
+                    ),
+                    None => {}
+                }
+            }
+        }
+        annotated.push(node);
+        annotated.extend(comment);
+    }
+    *children = annotated;
+}
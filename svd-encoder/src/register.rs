@@ -1,11 +1,12 @@
 use svd_rs::Field;
 
 use super::{
-    new_node, Config, Element, ElementMerge, Encode, EncodeChildren, EncodeError, XMLNode,
+    encode_commented, new_node, Config, Element, ElementMerge, Encode, EncodeError, XMLNode,
 };
 
 use crate::{
-    config::{change_case, format_number, DerivableSorting, Sorting},
+    config::{change_case, format_literal, DerivableSorting, Sorting},
+    registerproperties::encode_properties,
     svd::{Register, RegisterInfo},
 };
 
@@ -57,11 +58,20 @@ impl Encode for RegisterInfo {
 
         elem.children.push(new_node(
             "addressOffset",
-            format_number(self.address_offset, config.register_address_offset),
+            format_literal(
+                "addressOffset",
+                self.address_offset,
+                config.register_address_offset,
+                &self.literals,
+            ),
         ));
 
         elem.children
-            .extend(self.properties.encode_with_config(config)?);
+            .extend(encode_properties(&self.properties, &self.literals, config)?);
+
+        if let Some(v) = &self.data_type {
+            elem.children.push(v.encode_node()?);
+        }
 
         if let Some(v) = &self.modified_write_values {
             elem.children.push(v.encode_node_with_config(config)?);
@@ -76,17 +86,11 @@ impl Encode for RegisterInfo {
         }
 
         if let Some(v) = &self.fields {
-            let children: Result<Vec<_>, _> =
-                if config.field_sorting == DerivableSorting::Unchanged(None) {
-                    v.iter()
-                        .map(|field| field.encode_node_with_config(config))
-                        .collect()
-                } else {
-                    sort_derived_fields(v, config.field_sorting)
-                        .into_iter()
-                        .map(|field| field.encode_node_with_config(config))
-                        .collect()
-                };
+            let children = if config.field_sorting == DerivableSorting::Unchanged(None) {
+                encode_commented(v, config)
+            } else {
+                encode_commented(sort_derived_fields(v, config.field_sorting), config)
+            };
 
             let children = children?;
             if !children.is_empty() {
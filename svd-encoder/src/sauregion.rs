@@ -0,0 +1,53 @@
+use super::{new_node, Config, Element, Encode, EncodeError};
+
+use crate::{
+    config::format_number,
+    svd::{SauRegion, SauRegionsConfig},
+};
+
+impl Encode for SauRegionsConfig {
+    type Error = EncodeError;
+
+    fn encode_with_config(&self, config: &Config) -> Result<Element, EncodeError> {
+        let mut elem = Element::new("sauRegionsConfig");
+        elem.attributes
+            .insert(String::from("enabled"), format!("{}", self.enabled));
+        if let Some(v) = &self.protection_when_disabled {
+            elem.attributes.insert(
+                String::from("protectionWhenDisabled"),
+                v.as_str().to_string(),
+            );
+        }
+        elem.children = self
+            .regions
+            .iter()
+            .map(|r| r.encode_node_with_config(config))
+            .collect::<Result<_, _>>()?;
+        Ok(elem)
+    }
+}
+
+impl Encode for SauRegion {
+    type Error = EncodeError;
+
+    fn encode_with_config(&self, config: &Config) -> Result<Element, EncodeError> {
+        let mut elem = Element::new("region");
+        elem.attributes
+            .insert(String::from("enabled"), format!("{}", self.enabled));
+        if let Some(v) = &self.name {
+            elem.attributes.insert(String::from("name"), v.clone());
+        }
+        elem.children = vec![
+            new_node(
+                "base",
+                format_number(self.base, config.peripheral_base_address),
+            ),
+            new_node(
+                "limit",
+                format_number(self.limit, config.peripheral_base_address),
+            ),
+            new_node("access", self.access.as_str().to_string()),
+        ];
+        Ok(elem)
+    }
+}
@@ -8,8 +8,8 @@ impl Parse for AddressBlock {
 
     fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
         Self::builder()
-            .offset(tree.get_child_u32("offset")?)
-            .size(tree.get_child_u32("size")?)
+            .offset(tree.get_child_u32_with_config("offset", config)?)
+            .size(tree.get_child_u32_with_config("size", config)?)
             .usage(AddressBlockUsage::parse(
                 &tree.get_child_elem("usage")?,
                 config,
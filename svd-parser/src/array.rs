@@ -10,10 +10,22 @@ where
     }
 
     let info = T::parse(tree, config)?;
+    make_array(tag, tree, config, info)
+}
 
+/// Wraps already parsed `info` in an array if `tree` has `dim` elements
+pub(crate) fn make_array<T: Name>(
+    tag: &str,
+    tree: &Node,
+    config: &Config,
+    info: T,
+) -> Result<MaybeArray<T>, SVDErrorAt> {
+    tree.check_text(config)?;
     if tree.get_child("dimIncrement").is_some() {
         let array_info = DimElement::parse(tree, config)?;
-        check_has_placeholder(info.name(), tag).map_err(|e| e.at(tree.id()))?;
+        if array_info.dim > 1 {
+            check_has_placeholder(info.name(), tag).map_err(|e| e.at(tree.id()))?;
+        }
         if let Some(indexes) = &array_info.dim_index {
             if array_info.dim as usize != indexes.len() {
                 return Err(SVDError::IncorrectDimIndexesCount(
@@ -1,5 +1,6 @@
 use super::*;
 use crate::svd::{BitRange, BitRangeType};
+use crate::types::NumberParse;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InvalidBitRange {
@@ -15,7 +16,7 @@ impl Parse for BitRange {
     type Error = SVDErrorAt;
     type Config = Config;
 
-    fn parse(tree: &Node, _config: &Self::Config) -> Result<Self, Self::Error> {
+    fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
         let (end, start, range_type): (u32, u32, BitRangeType) =
             if let Some(range) = tree.get_child("bitRange") {
                 let text = range.text().ok_or_else(|| {
@@ -56,10 +57,10 @@ impl Parse for BitRange {
             } else if let (Some(lsb), Some(msb)) = (tree.get_child("lsb"), tree.get_child("msb")) {
                 (
                     // TODO: `u32::parse` should not hide it's errors
-                    u32::parse(&msb, &()).map_err(|_| {
+                    NumberParse::<u32>::parse(&msb, config).map_err(|_| {
                         SVDError::InvalidBitRange(InvalidBitRange::MsbLsb).at(tree.id())
                     })?,
-                    u32::parse(&lsb, &()).map_err(|_| {
+                    NumberParse::<u32>::parse(&lsb, config).map_err(|_| {
                         SVDError::InvalidBitRange(InvalidBitRange::MsbLsb).at(tree.id())
                     })?,
                     BitRangeType::MsbLsb,
@@ -72,10 +73,10 @@ impl Parse for BitRange {
                 return Ok(BitRange {
                     // TODO: capture that error comes from offset/width tag
                     // TODO: `u32::parse` should not hide it's errors
-                    offset: u32::parse(&offset, &()).map_err(|_| {
+                    offset: NumberParse::<u32>::parse(&offset, config).map_err(|_| {
                         SVDError::InvalidBitRange(InvalidBitRange::ParseError).at(tree.id())
                     })?,
-                    width: u32::parse(&width, &()).map_err(|_| {
+                    width: NumberParse::<u32>::parse(&width, config).map_err(|_| {
                         SVDError::InvalidBitRange(InvalidBitRange::ParseError).at(tree.id())
                     })?,
                     range_type: BitRangeType::OffsetWidth,
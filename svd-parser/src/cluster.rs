@@ -1,5 +1,5 @@
 use super::*;
-use crate::svd::{Cluster, ClusterInfo, RegisterCluster, RegisterProperties};
+use crate::svd::{Cluster, ClusterInfo, RegisterProperties};
 
 impl Parse for Cluster {
     type Object = Self;
@@ -17,24 +17,28 @@ impl Parse for ClusterInfo {
     type Config = Config;
 
     fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
+        let properties = RegisterProperties::parse(tree, config)?;
         ClusterInfo::builder()
             .name(tree.get_child_text("name")?)
             .description(tree.get_child_text_opt("description")?)
+            .comments(tree.preceding_comments())
             .alternate_cluster(tree.get_child_text_opt("alternateCluster")?)
             .header_struct_name(tree.get_child_text_opt("headerStructName")?)
-            .address_offset(tree.get_child_u32("addressOffset")?)
-            .default_register_properties(RegisterProperties::parse(tree, config)?)
-            .children({
-                let children: Result<Vec<_>, _> = tree
-                    .children()
-                    .filter(|t| {
-                        t.is_element() && (t.has_tag_name("register") || t.has_tag_name("cluster"))
-                    })
-                    .map(|t| RegisterCluster::parse(&t, config))
-                    .collect();
-                children?
-            })
+            .address_offset(tree.get_child_u32_with_config("addressOffset", config)?)
+            .default_register_properties(properties)
+            .children(registercluster::parse_children(
+                tree.children().filter(|t| {
+                    t.is_element() && (t.has_tag_name("register") || t.has_tag_name("cluster"))
+                }),
+                &properties.inherit_from(&config.inherited),
+                config,
+            )?)
             .derived_from(tree.attribute("derivedFrom").map(|s| s.to_owned()))
+            .literals(parse_literals(
+                tree,
+                &["addressOffset", "size", "resetValue", "resetMask"],
+                config,
+            )?)
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
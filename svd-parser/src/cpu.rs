@@ -1,6 +1,6 @@
 use super::*;
-use crate::svd::{Cpu, Endian};
-use crate::types::BoolParse;
+use crate::svd::{cpu::normalize_cpu_name, Cpu, Endian, SauRegionsConfig};
+use crate::types::{BoolParse, NumberParse};
 
 impl Parse for Cpu {
     type Object = Self;
@@ -11,9 +11,14 @@ impl Parse for Cpu {
         if !tree.has_tag_name("cpu") {
             return Err(SVDError::NotExpectedTag("cpu".to_string()).at(tree.id()));
         }
+        tree.check_text(config)?;
 
+        let name = tree.get_child_text("name")?;
         Cpu::builder()
-            .name(tree.get_child_text("name")?)
+            .name(match normalize_cpu_name(&name) {
+                Some(known) => known.to_string(),
+                None => name,
+            })
             .revision(tree.get_child_text("revision")?)
             .endian(Endian::parse(&tree.get_child_elem("endian")?, config)?)
             .mpu_present(tree.get_child_bool("mpuPresent")?)
@@ -25,10 +30,19 @@ impl Parse for Cpu {
             .itcm_present(optional::<BoolParse>("itcmPresent", tree, &())?)
             .dtcm_present(optional::<BoolParse>("dtcmPresent", tree, &())?)
             .vtor_present(optional::<BoolParse>("vtorPresent", tree, &())?)
-            .nvic_priority_bits(tree.get_child_u32("nvicPrioBits")?)
+            .nvic_priority_bits(tree.get_child_u32_with_config("nvicPrioBits", config)?)
             .has_vendor_systick(tree.get_child_bool("vendorSystickConfig")?)
-            .device_num_interrupts(optional::<u32>("deviceNumInterrupts", tree, &())?)
-            .sau_num_regions(optional::<u32>("sauNumRegions", tree, &())?)
+            .device_num_interrupts(optional::<NumberParse<u32>>(
+                "deviceNumInterrupts",
+                tree,
+                config,
+            )?)
+            .sau_num_regions(optional::<NumberParse<u32>>("sauNumRegions", tree, config)?)
+            .sau_regions_config(optional::<SauRegionsConfig>(
+                "sauRegionsConfig",
+                tree,
+                config,
+            )?)
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
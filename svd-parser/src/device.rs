@@ -2,6 +2,7 @@ use super::*;
 use crate::svd::{
     cpu::Cpu, peripheral::Peripheral, registerproperties::RegisterProperties, Device,
 };
+use crate::types::NumberParse;
 
 /// Parses a SVD file
 impl Parse for Device {
@@ -13,7 +14,18 @@ impl Parse for Device {
         if !tree.has_tag_name("device") {
             return Err(SVDError::NotExpectedTag("device".to_string()).at(tree.id()));
         }
+        tree.check_text(config)?;
 
+        let address_unit_bits = optional::<NumberParse<u32>>("addressUnitBits", tree, config)?;
+        let config = &Config {
+            address_unit_bits,
+            ..*config
+        };
+        let properties = RegisterProperties::parse(tree, config)?;
+        let peripheral_config = Config {
+            inherited: properties,
+            ..*config
+        };
         let mut device = Device::builder()
             .vendor(tree.get_child_text_opt("vendor")?)
             .vendor_id(tree.get_child_text_opt("vendorID")?)
@@ -23,26 +35,28 @@ impl Parse for Device {
             .cpu(optional::<Cpu>("cpu", tree, config)?)
             .header_system_filename(tree.get_child_text_opt("headerSystemFilename")?)
             .header_definitions_prefix(tree.get_child_text_opt("headerDefinitionsPrefix")?)
-            .default_register_properties(RegisterProperties::parse(tree, config)?)
+            .default_register_properties(properties)
             .peripherals({
-                let ps: Result<Vec<_>, _> = tree
-                    .get_child_elem("peripherals")?
+                let peripherals = tree.get_child_elem("peripherals")?;
+                peripherals.check_text(config)?;
+                let ps: Result<Vec<_>, _> = peripherals
                     .children()
                     .filter(Node::is_element)
-                    .map(|t| Peripheral::parse(&t, config))
+                    .map(|t| Peripheral::parse(&t, &peripheral_config))
                     .collect();
                 ps?
-            });
+            })
+            .vendor_extensions(tree.get_child("vendorExtensions").map(raw_xml));
         if let Some(version) = tree.get_child_text_opt("version")? {
             device = device.version(version)
         }
         if let Some(description) = tree.get_child_text_opt("description")? {
             device = device.description(description)
         }
-        if let Some(bits) = optional::<u32>("addressUnitBits", tree, &())? {
+        if let Some(bits) = address_unit_bits {
             device = device.address_unit_bits(bits)
         }
-        if let Some(width) = optional::<u32>("width", tree, &())? {
+        if let Some(width) = optional::<NumberParse<u32>>("width", tree, config)? {
             device = device.width(width)
         }
         // TODO: accept namespace other than `xs`
@@ -61,3 +75,25 @@ impl Parse for Device {
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
 }
+
+/// Source text of `node` with the namespaces used inside of it declared on the element
+fn raw_xml(node: Node) -> String {
+    let text = &node.document().input_text()[node.range()];
+    let name_end = text
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(text.len());
+    let start_tag = &text[..text.find('>').unwrap_or(text.len())];
+    let mut declarations = String::new();
+    for ns in node.namespaces() {
+        if let Some(prefix) = ns.name() {
+            if prefix != "xml"
+                && text.contains(&format!("{prefix}:"))
+                && !start_tag.contains(&format!("xmlns:{prefix}="))
+            {
+                let uri = ns.uri().replace('&', "&amp;").replace('"', "&quot;");
+                declarations.push_str(&format!(" xmlns:{prefix}=\"{uri}\""));
+            }
+        }
+    }
+    format!("{}{}{}", &text[..name_end], declarations, &text[name_end..])
+}
@@ -8,8 +8,8 @@ impl Parse for DimElement {
 
     fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
         DimElement::builder()
-            .dim(tree.get_child_u32("dim")?)
-            .dim_increment(tree.get_child_u32("dimIncrement")?)
+            .dim(tree.get_child_u32_with_config("dim", config)?)
+            .dim_increment(tree.get_child_u32_with_config("dimIncrement", config)?)
             .dim_index(optional::<DimIndex>("dimIndex", tree, config)?)
             .dim_name(tree.get_child_text_opt("dimName")?)
             .dim_array_index(optional::<DimArrayIndex>("dimArrayIndex", tree, config)?)
@@ -52,7 +52,8 @@ impl Parse for DimIndex {
     type Config = Config;
 
     fn parse(tree: &Node, _config: &Self::Config) -> Result<Vec<String>, Self::Error> {
-        DimElement::parse_indexes(tree.get_text()?)
-            .ok_or_else(|| SVDError::DimIndexParse.at(tree.id()))
+        let text = tree.get_text()?;
+        DimElement::parse_indexes(text)
+            .ok_or_else(|| SVDError::DimIndexParse(text.into()).at(tree.id()))
     }
 }
@@ -3,12 +3,12 @@
 
 use roxmltree::Node;
 
-use super::types::BoolParse;
-use super::{Parse, SVDError, SVDErrorAt};
+use super::types::{BoolParse, NumberParse};
+use super::{Config, Parse, SVDError, SVDErrorAt};
 
 /// Defines extensions for implementation over roxmltree::Node
 pub trait ElementExt {
-    fn get_child<K>(&self, k: K) -> Option<Node>
+    fn get_child<K>(&self, k: K) -> Option<Node<'_, '_>>
     where
         K: AsRef<str>;
     fn get_child_text_opt<K>(&self, k: K) -> Result<Option<String>, SVDErrorAt>
@@ -20,17 +20,23 @@ pub trait ElementExt {
 
     fn get_text(&self) -> Result<&str, SVDErrorAt>;
 
-    fn get_child_elem(&self, n: &str) -> Result<Node, SVDErrorAt>;
+    fn get_child_elem(&self, n: &str) -> Result<Node<'_, '_>, SVDErrorAt>;
     fn get_child_u32(&self, n: &str) -> Result<u32, SVDErrorAt>;
     fn get_child_u64(&self, n: &str) -> Result<u64, SVDErrorAt>;
+    fn get_child_u32_with_config(&self, n: &str, config: &Config) -> Result<u32, SVDErrorAt>;
+    fn get_child_u64_with_config(&self, n: &str, config: &Config) -> Result<u64, SVDErrorAt>;
     fn get_child_bool(&self, n: &str) -> Result<bool, SVDErrorAt>;
 
+    fn check_text(&self, config: &Config) -> Result<(), SVDErrorAt>;
+
+    fn preceding_comments(&self) -> Vec<String>;
+
     fn debug(&self);
 }
 
 /// Implements extensions for roxmltree::Node
 impl<'a, 'input> ElementExt for Node<'a, 'input> {
-    fn get_child<K>(&self, k: K) -> Option<Node>
+    fn get_child<K>(&self, k: K) -> Option<Node<'_, '_>>
     where
         K: AsRef<str>,
     {
@@ -80,7 +86,7 @@ impl<'a, 'input> ElementExt for Node<'a, 'input> {
     }
 
     /// Get a named child element from an XML Element
-    fn get_child_elem(&self, n: &str) -> Result<Node, SVDErrorAt> {
+    fn get_child_elem(&self, n: &str) -> Result<Node<'_, '_>, SVDErrorAt> {
         self.get_child(n)
             .ok_or_else(|| SVDError::MissingTag(n.to_string()).at(self.id()))
     }
@@ -97,12 +103,68 @@ impl<'a, 'input> ElementExt for Node<'a, 'input> {
         u64::parse(&s, &())
     }
 
+    /// Get a u32 value from a named child element, tolerating annotated values
+    /// if validation is not strict
+    fn get_child_u32_with_config(&self, n: &str, config: &Config) -> Result<u32, SVDErrorAt> {
+        let s = self.get_child_elem(n)?;
+        NumberParse::<u32>::parse(&s, config)
+    }
+
+    /// Get a u64 value from a named child element, tolerating annotated values
+    /// if validation is not strict
+    fn get_child_u64_with_config(&self, n: &str, config: &Config) -> Result<u64, SVDErrorAt> {
+        let s = self.get_child_elem(n)?;
+        NumberParse::<u64>::parse(&s, config)
+    }
+
     /// Get a bool value from a named child element
     fn get_child_bool(&self, n: &str) -> Result<bool, SVDErrorAt> {
         let s = self.get_child_elem(n)?;
         BoolParse::parse(&s, &())
     }
 
+    /// Check that an XML Element containing other elements has no text between them.
+    ///
+    /// Only done on [`ValidateLevel::Strict`](crate::ValidateLevel::Strict),
+    /// otherwise the text is ignored.
+    fn check_text(&self, config: &Config) -> Result<(), SVDErrorAt> {
+        if !config.validate_level.is_strict() {
+            return Ok(());
+        }
+        for n in self.children() {
+            if let Some(text) = n.text().filter(|t| n.is_text() && !t.trim().is_empty()) {
+                return Err(SVDError::UnexpectedText(
+                    self.tag_name().name().to_string(),
+                    text.trim().to_string(),
+                )
+                .at(n.id()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the comments directly before an XML Element, separated only by whitespace
+    ///
+    /// Address annotations of the encoder (`<!-- @ 0x40000004 -->`) belong
+    /// to the previous element and are skipped.
+    fn preceding_comments(&self) -> Vec<String> {
+        let mut comments = Vec::new();
+        let mut node = self.prev_sibling();
+        while let Some(n) = node {
+            if n.is_comment() {
+                let text = n.text().unwrap_or_default();
+                if !text.trim_start().starts_with("@ 0x") {
+                    comments.push(text.to_string());
+                }
+            } else if !(n.is_text() && n.text().map_or(true, |t| t.trim().is_empty())) {
+                break;
+            }
+            node = n.prev_sibling();
+        }
+        comments.reverse();
+        comments
+    }
+
     fn debug(&self) {
         let name = self.tag_name().name();
         println!("<{}>", name);
@@ -1,5 +1,6 @@
 use super::*;
 use crate::svd::EnumeratedValue;
+use crate::types::NumberParse;
 
 impl Parse for EnumeratedValue {
     type Object = Self;
@@ -11,12 +12,57 @@ impl Parse for EnumeratedValue {
             return Err(SVDError::NotExpectedTag("enumeratedValue".to_string()).at(tree.id()));
         }
 
+        let (value, mask) = match tree
+            .get_child("value")
+            .map(|v| v.text().unwrap_or_default())
+        {
+            Some(text) if text.contains(['x', 'X']) => match parse_dont_care(text.trim()) {
+                Some((value, mask)) => (Some(value), Some(mask)),
+                None => (optional::<NumberParse<u64>>("value", tree, config)?, None),
+            },
+            _ => (optional::<NumberParse<u64>>("value", tree, config)?, None),
+        };
+
         EnumeratedValue::builder()
             .name(tree.get_child_text("name")?)
             .description(tree.get_child_text_opt("description")?)
-            .value(optional::<u64>("value", tree, &())?)
+            .value(value)
+            .mask(mask)
             .is_default(tree.get_child_bool("isDefault").ok())
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
 }
+
+/// Parses binary literal with don't care digits like `#10xx` into value and mask of significant bits
+fn parse_dont_care(text: &str) -> Option<(u64, u64)> {
+    let digits = text
+        .strip_prefix('#')
+        .or_else(|| text.strip_prefix("0b"))?
+        .chars()
+        .filter(|&c| c != '_');
+    let (mut value, mut mask) = (0u64, 0u64);
+    let mut width = 0;
+    for c in digits {
+        width += 1;
+        if width > 64 {
+            return None;
+        }
+        value <<= 1;
+        mask <<= 1;
+        match c {
+            '0' => mask |= 1,
+            '1' => {
+                value |= 1;
+                mask |= 1;
+            }
+            'x' | 'X' => {}
+            _ => return None,
+        }
+    }
+    if width > 0 {
+        Some((value, mask))
+    } else {
+        None
+    }
+}
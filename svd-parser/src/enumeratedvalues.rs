@@ -12,6 +12,7 @@ impl Parse for EnumeratedValues {
         }
         EnumeratedValues::builder()
             .name(tree.get_child_text_opt("name")?)
+            .header_enum_name(tree.get_child_text_opt("headerEnumName")?)
             .usage(optional::<Usage>("usage", tree, config)?)
             .derived_from(tree.attribute("derivedFrom").map(|s| s.to_owned()))
             .values({
@@ -35,3 +36,20 @@ impl Parse for EnumeratedValues {
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
 }
+
+/// Parse `enumeratedValues`, dropping empty blocks with a warning
+pub(crate) fn parse_non_empty(
+    tree: &Node,
+    config: &Config,
+) -> Result<Option<EnumeratedValues>, SVDErrorAt> {
+    let evs = EnumeratedValues::parse(tree, config)?;
+    if evs.values.is_empty() && evs.derived_from.is_none() {
+        match evs.name.as_deref() {
+            Some(name) => log::warn!("Skipping empty `enumeratedValues` `{}`", name),
+            None => log::warn!("Skipping empty `enumeratedValues`"),
+        }
+        Ok(None)
+    } else {
+        Ok(Some(evs))
+    }
+}
@@ -615,7 +615,8 @@ pub fn expand(indevice: &Device) -> Result<Device> {
 }
 
 /// Takes register `size`, `access`, `reset_value` and `reset_mask`
-/// from peripheral or device properties if absent in register
+/// from peripheral or device properties if absent in register.
+/// Fields without `access` get it from their register
 pub fn expand_properties(device: &mut Device) {
     let default = device.default_register_properties;
     for p in &mut device.peripherals {
@@ -644,6 +645,13 @@ fn expand_properties_registers(regs: &mut [RegisterCluster], default: &RegisterP
                     continue;
                 }
                 r.properties = r.properties.derive_from(default);
+                if let Some(access) = r.properties.access {
+                    for f in r.fields_mut() {
+                        if f.derived_from.is_none() {
+                            f.access = f.access.or(Some(access));
+                        }
+                    }
+                }
             }
         }
     }
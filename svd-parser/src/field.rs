@@ -1,7 +1,6 @@
 use super::*;
 use crate::svd::{
-    Access, BitRange, EnumeratedValues, Field, FieldInfo, ModifiedWriteValues, ReadAction,
-    WriteConstraint,
+    Access, BitRange, DataType, Field, FieldInfo, ModifiedWriteValues, ReadAction, WriteConstraint,
 };
 
 impl Parse for Field {
@@ -28,6 +27,7 @@ impl Parse for FieldInfo {
         FieldInfo::builder()
             .name(tree.get_child_text("name")?)
             .description(tree.get_child_text_opt("description")?)
+            .comments(tree.preceding_comments())
             .bit_range(bit_range)
             .access(optional::<Access>("access", tree, config)?)
             .modified_write_values(optional::<ModifiedWriteValues>(
@@ -41,13 +41,14 @@ impl Parse for FieldInfo {
                 None
             })
             .read_action(optional::<ReadAction>("readAction", tree, config)?)
+            .data_type(optional::<DataType>("dataType", tree, config)?)
             .enumerated_values(if !config.ignore_enums {
                 let values: Result<Vec<_>, _> = tree
                     .children()
                     .filter(|t| t.is_element() && t.has_tag_name("enumeratedValues"))
-                    .map(|t| EnumeratedValues::parse(&t, config))
+                    .map(|t| enumeratedvalues::parse_non_empty(&t, config))
                     .collect();
-                values?
+                values?.into_iter().flatten().collect()
             } else {
                 Vec::new()
             })
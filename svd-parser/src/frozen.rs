@@ -0,0 +1,71 @@
+//! Provides [FrozenDevice], an immutable resolved device for sharing between threads
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use svd_rs::{Device, Peripheral, PeripheralInfo, RegisterInfo};
+
+/// Read-only [Device] with resolved inheritance and derivation.
+///
+/// Register properties are inherited and all `derivedFrom` references and arrays
+/// are expanded once on creation. The device can't be modified afterwards,
+/// cloning is cheap, and it can be queried from several threads at once.
+#[derive(Clone, Debug)]
+pub struct FrozenDevice {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    device: Device,
+    index: HashMap<String, usize>,
+}
+
+impl FrozenDevice {
+    /// Resolves `device` and freezes the result
+    pub fn new(device: &Device) -> Result<Self> {
+        let mut device = device.clone();
+        super::expand_properties(&mut device);
+        let device = super::expand(&device)?;
+        let mut index = HashMap::with_capacity(device.peripherals.len());
+        for (i, p) in device.peripherals.iter().enumerate() {
+            if index.insert(p.name.clone(), i).is_some() {
+                return Err(anyhow!("peripheral {} is defined more than once", p.name));
+            }
+        }
+        Ok(Self {
+            inner: Arc::new(Inner { device, index }),
+        })
+    }
+
+    /// Resolved device, with expanded arrays of peripherals, clusters, registers and fields
+    pub fn device(&self) -> &Device {
+        &self.inner.device
+    }
+
+    /// Iterates over all peripherals
+    pub fn peripherals(&self) -> impl Iterator<Item = &PeripheralInfo> {
+        self.inner.device.peripherals.iter().map(|p| match p {
+            Peripheral::Single(info) | Peripheral::Array(info, _) => info,
+        })
+    }
+
+    /// Get peripheral by name
+    pub fn get_peripheral(&self, name: &str) -> Option<&PeripheralInfo> {
+        let i = *self.inner.index.get(name)?;
+        match &self.inner.device.peripherals[i] {
+            Peripheral::Single(info) | Peripheral::Array(info, _) => Some(info),
+        }
+    }
+
+    /// Get register by `PERIPH.REG` path.
+    ///
+    /// Clusters are flattened by [expand](super::expand), so registers inside of them
+    /// are named like `CLUSTER_REG`.
+    pub fn get_register(&self, path: &str) -> Option<&RegisterInfo> {
+        let (peripheral, name) = path.split_once('.')?;
+        self.get_peripheral(peripheral)?
+            .get_register(name)
+            .map(|r| &**r)
+    }
+}
@@ -0,0 +1,85 @@
+//! Resolving of XInclude references for SVD files split into several files
+
+use anyhow::{anyhow, bail, Context, Result};
+use roxmltree::Document;
+use std::path::{Path, PathBuf};
+
+const XINCLUDE_NS: &str = "http://www.w3.org/2001/XInclude";
+
+/// Replaces `<xi:include href="..."/>` elements with the contents of the referenced files.
+///
+/// Relative `href`s are resolved against `base`, includes of included files
+/// against the directory of that file.
+pub fn resolve_includes(xml: &str, base: &Path) -> Result<String> {
+    resolve(xml, base, &mut Vec::new())
+}
+
+/// Reads an SVD file, resolves its XIncludes and parses it
+pub fn parse_file_with_includes(path: &Path, config: &super::Config) -> Result<svd_rs::Device> {
+    let xml = read(path, &mut Vec::new())?;
+    super::parse_with_config(&xml, config)
+}
+
+fn read(path: &Path, stack: &mut Vec<PathBuf>) -> Result<String> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Included file `{}` not found", path.display()))?;
+    if stack.contains(&canonical) {
+        let chain: Vec<_> = stack
+            .iter()
+            .chain([&canonical])
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("Include cycle: {}", chain.join(" -> "));
+    }
+    let xml = std::fs::read_to_string(&canonical)
+        .with_context(|| format!("Can't read included file `{}`", path.display()))?;
+    let base = canonical
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_owned();
+    stack.push(canonical);
+    let xml = resolve(super::trim_utf8_bom(&xml), &base, stack)?;
+    stack.pop();
+    Ok(xml)
+}
+
+fn resolve(xml: &str, base: &Path, stack: &mut Vec<PathBuf>) -> Result<String> {
+    let doc = Document::parse(xml)?;
+    let mut out = String::with_capacity(xml.len());
+    let mut last = 0;
+    let includes = doc
+        .descendants()
+        .filter(|n| n.tag_name().namespace() == Some(XINCLUDE_NS) && n.has_tag_name("include"));
+    for node in includes {
+        let range = node.range();
+        if range.start < last {
+            // nested in an already replaced include
+            continue;
+        }
+        let href = node.attribute("href").ok_or_else(|| {
+            anyhow!(
+                "Missing `href` in include at {}",
+                doc.text_pos_at(range.start)
+            )
+        })?;
+        let included = read(&base.join(href), stack)
+            .with_context(|| format!("In include at {}", doc.text_pos_at(range.start)))?;
+        out.push_str(&xml[last..range.start]);
+        out.push_str(strip_declaration(&included));
+        last = range.end;
+    }
+    out.push_str(&xml[last..]);
+    Ok(out)
+}
+
+/// Removes `<?xml ...?>` declaration of the included document
+fn strip_declaration(xml: &str) -> &str {
+    let xml = xml.trim_start();
+    if xml.starts_with("<?xml") {
+        if let Some(end) = xml.find("?>") {
+            return xml[end + 2..].trim_start();
+        }
+    }
+    xml
+}
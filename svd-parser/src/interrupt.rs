@@ -1,5 +1,5 @@
 use super::*;
-use crate::svd::Interrupt;
+use crate::svd::{interrupt::MAX_NVIC_INTERRUPT, Interrupt};
 
 impl Parse for Interrupt {
     type Object = Self;
@@ -11,11 +11,19 @@ impl Parse for Interrupt {
             return Err(SVDError::NotExpectedTag("interrupt".to_string()).at(tree.id()));
         }
         let name = tree.get_child_text("name")?;
+        let value = tree.get_child_u32_with_config("value", config)?;
+        if value > MAX_NVIC_INTERRUPT && !config.validate_level.is_strict() {
+            log::warn!(
+                "Interrupt `{}` has suspiciously large value {}",
+                name,
+                value
+            );
+        }
 
         Interrupt::builder()
             .name(name)
             .description(tree.get_child_text_opt("description")?)
-            .value(tree.get_child_u32("value")?)
+            .value(value)
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
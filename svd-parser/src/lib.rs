@@ -33,6 +33,9 @@ pub mod elementext;
 use crate::elementext::ElementExt;
 // Types defines simple types and parse/encode implementations
 pub mod types;
+// Include resolves XInclude references of split SVD files
+pub mod include;
+pub use include::{parse_file_with_includes, resolve_includes};
 
 #[derive(Clone, Copy, Debug, Default)]
 #[non_exhaustive]
@@ -49,6 +52,18 @@ pub struct Config {
     pub expand_properties: bool,
     /// Skip parsing and emitting `enumeratedValues` and `writeConstraint` in `Field`
     pub ignore_enums: bool,
+    /// Attach `enumeratedValues` placed directly in `register` to the field they belong to
+    /// if it can be determined unambiguously, otherwise skip them with a warning
+    pub attach_register_enums: bool,
+    /// Treat `size` smaller than 8 as a number of bytes if validation is not strict
+    pub fix_byte_sizes: bool,
+    /// Keep numbers as they are written in the SVD file, so that unmodified values
+    /// are encoded back in the same format
+    pub keep_literals: bool,
+    /// Register properties of the elements enclosing the one being parsed
+    pub(crate) inherited: svd::RegisterProperties,
+    /// `addressUnitBits` of the device being parsed
+    pub(crate) address_unit_bits: Option<u32>,
 }
 
 impl Config {
@@ -78,6 +93,25 @@ impl Config {
         self.ignore_enums = val;
         self
     }
+
+    /// Attach `enumeratedValues` placed directly in `register` to the corresponding field
+    pub fn attach_register_enums(mut self, val: bool) -> Self {
+        self.attach_register_enums = val;
+        self
+    }
+
+    /// Treat suspiciously small `size` as a number of bytes,
+    /// or of `addressUnitBits` if the device specifies it
+    pub fn fix_byte_sizes(mut self, val: bool) -> Self {
+        self.fix_byte_sizes = val;
+        self
+    }
+
+    /// Keep numbers as they are written in the SVD file
+    pub fn keep_literals(mut self, val: bool) -> Self {
+        self.keep_literals = val;
+        self
+    }
 }
 
 /// Parse trait allows SVD objects to be parsed from XML elements.
@@ -117,13 +151,25 @@ pub fn parse(xml: &str) -> anyhow::Result<Device> {
 }
 /// Parses the contents of an SVD (XML) string
 pub fn parse_with_config(xml: &str, config: &Config) -> anyhow::Result<Device> {
+    parse_bytes_with_config(xml.as_bytes(), config)
+}
+/// Parses the contents of an SVD (XML) file read as bytes.
+///
+/// A leading UTF-8 BOM is skipped. The bytes are checked to be UTF-8 and borrowed,
+/// so no `String` copy of the file is needed.
+pub fn parse_bytes(xml: &[u8]) -> anyhow::Result<Device> {
+    parse_bytes_with_config(xml, &Config::default())
+}
+/// Parses the contents of an SVD (XML) file read as bytes
+pub fn parse_bytes_with_config(xml: &[u8], config: &Config) -> anyhow::Result<Device> {
     fn get_name<'a>(node: &'a Node) -> Option<&'a str> {
         node.children()
             .find(|t| t.has_tag_name("name"))
             .and_then(|t| t.text())
     }
 
-    let xml = trim_utf8_bom(xml);
+    let xml = xml.strip_prefix(b"\xef\xbb\xbf").unwrap_or(xml);
+    let xml = std::str::from_utf8(xml)?;
     let tree = Document::parse(xml)?;
     let root = tree.root();
     let xmldevice = root
@@ -133,18 +179,19 @@ pub fn parse_with_config(xml: &str, config: &Config) -> anyhow::Result<Device> {
     #[allow(unused_mut)]
     let mut device = match Device::parse(&xmldevice, config) {
         Ok(o) => Ok(o),
-        Err(e) => {
+        Err(mut e) => {
+            e.resolve_pos(&tree);
             let id = e.id;
             let node = tree.get_node(id).unwrap();
-            let pos = tree.text_pos_at(node.range().start);
             let tagname = node.tag_name().name();
             let mut res = Err(e.into());
-            if tagname.is_empty() {
-                res = res.with_context(|| format!("at {}", pos))
-            } else if let Some(name) = get_name(&node) {
-                res = res.with_context(|| format!("Parsing {} `{}` at {}", tagname, name, pos))
-            } else {
-                res = res.with_context(|| format!("Parsing unknown {} at {}", tagname, pos))
+            // position is already in the error, text nodes have no name
+            if !tagname.is_empty() {
+                if let Some(name) = get_name(&node) {
+                    res = res.with_context(|| format!("Parsing {} `{}`", tagname, name))
+                } else {
+                    res = res.with_context(|| format!("Parsing unknown {}", tagname))
+                }
             }
             for parent in node.ancestors().skip(1) {
                 if parent.id() == NodeId::new(0) {
@@ -179,6 +226,80 @@ pub fn parse_with_config(xml: &str, config: &Config) -> anyhow::Result<Device> {
     Ok(device)
 }
 
+/// Parses the contents of an SVD (XML) string, collecting errors instead of stopping at the first one
+pub fn parse_collect(xml: &str) -> (Option<Device>, Vec<SVDErrorAt>) {
+    parse_collect_with_config(xml, &Config::default())
+}
+/// Parses the contents of an SVD (XML) string, collecting errors instead of stopping at the first one.
+///
+/// Elements with errors are skipped and parsing continues, e.g. a register with a bad
+/// `<size>` is read without the size. If the skipped element was required, its parent
+/// is skipped too. The device is `None` if the error can't be recovered from,
+/// like malformed XML or an error in the `<device>` element itself.
+///
+/// Error positions refer to `xml`. The `expand` options of `config` are not applied.
+pub fn parse_collect_with_config(xml: &str, config: &Config) -> (Option<Device>, Vec<SVDErrorAt>) {
+    let source = trim_utf8_bom(xml);
+    let original = match Document::parse(source) {
+        Ok(tree) => tree,
+        Err(e) => {
+            // the message of the XML error contains the position
+            return (None, vec![SVDError::Xml(e).at(NodeId::new(0))]);
+        }
+    };
+    let mut xml = std::borrow::Cow::Borrowed(source);
+    let mut errors = Vec::new();
+    loop {
+        let blanked;
+        let tree = match &xml {
+            std::borrow::Cow::Borrowed(_) => &original,
+            std::borrow::Cow::Owned(text) => match Document::parse(text) {
+                Ok(tree) => {
+                    blanked = tree;
+                    &blanked
+                }
+                Err(e) => {
+                    errors.push(SVDError::Xml(e).at(NodeId::new(0)));
+                    return (None, errors);
+                }
+            },
+        };
+        let root = tree.root();
+        let result = root
+            .get_child("device")
+            .ok_or_else(|| SVDError::MissingTag("device".to_string()).at(root.id()))
+            .and_then(|device| Device::parse(&device, config));
+        let mut e = match result {
+            Ok(device) => return (Some(device), errors),
+            Err(e) => e,
+        };
+        let node = tree.get_node(e.id);
+        // blanked elements are missing in `tree`, the path is taken from the original
+        // document, where the node has the same range
+        match node.and_then(|n| original.descendants().find(|o| o.range() == n.range())) {
+            Some(n) => {
+                e.id = n.id();
+                e.resolve_pos(&original);
+            }
+            None => e.resolve_pos(tree),
+        }
+        let element = node
+            .and_then(|n| n.ancestors().find(|a| a.is_element()))
+            .filter(|n| !n.has_tag_name("device"));
+        errors.push(e);
+        let range = match element {
+            Some(element) => element.range(),
+            None => return (None, errors),
+        };
+        // blank out the element, so positions of the remaining nodes don't change
+        let blank: String = xml[range.clone()]
+            .chars()
+            .map(|c| if c == '\n' { c } else { ' ' })
+            .collect();
+        xml.to_mut().replace_range(range, &blank);
+    }
+}
+
 /// Return the &str trimmed UTF-8 BOM if the input &str contains the BOM.
 fn trim_utf8_bom(s: &str) -> &str {
     if s.len() > 2 && s.as_bytes().starts_with(b"\xef\xbb\xbf") {
@@ -196,6 +317,7 @@ mod addressblock;
 mod bitrange;
 mod cluster;
 mod cpu;
+mod datatype;
 mod device;
 mod dimelement;
 mod endian;
@@ -203,6 +325,8 @@ mod enumeratedvalue;
 mod enumeratedvalues;
 mod field;
 mod interrupt;
+mod literals;
+use literals::parse_literals;
 mod modifiedwritevalues;
 mod peripheral;
 mod protection;
@@ -210,6 +334,7 @@ mod readaction;
 mod register;
 mod registercluster;
 mod registerproperties;
+mod sauregion;
 mod usage;
 mod writeconstraint;
 
@@ -218,6 +343,12 @@ pub mod expand;
 
 #[cfg(feature = "expand")]
 pub use expand::{expand, expand_properties};
+
+#[cfg(feature = "expand")]
+pub mod frozen;
+
+#[cfg(feature = "expand")]
+pub use frozen::FrozenDevice;
 /// SVD parse Errors.
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
 pub enum SVDError {
@@ -251,27 +382,157 @@ pub enum SVDError {
     InvalidModifiedWriteValues(String),
     #[error("Invalid readAction variant, found {0}")]
     InvalidReadAction(String),
+    #[error("Unexpected text `{1}` in <{0}>")]
+    UnexpectedText(String, String),
+    #[error("Invalid dataType variant, found {0}")]
+    InvalidDataType(String),
     #[error("Invalid protection variant, found {0}")]
     InvalidProtection(String),
     #[error("The content of the element could not be parsed to a boolean value {0}: {1}")]
     InvalidBooleanValue(String, core::str::ParseBoolError),
     #[error("dimIndex tag must contain {0} indexes, found {1}")]
     IncorrectDimIndexesCount(usize, usize),
-    #[error("Failed to parse dimIndex")]
-    DimIndexParse,
+    #[error("Failed to parse dimIndex `{0}`, expected a range like `0-3` or a list like `A,B,C`")]
+    DimIndexParse(String),
     #[error("Name `{0}` in tag `{1}` is missing a %s placeholder")]
     MissingPlaceholder(String, String),
+    #[error("Invalid SAU region access variant, found {0}")]
+    InvalidSauAccess(String),
+    #[error("{0}")]
+    Xml(roxmltree::Error),
+}
+
+impl SVDError {
+    /// Name of the error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Svd(e) => e.kind(),
+            Self::MissingTag(_) => "MissingTag",
+            Self::EmptyTag(_) => "EmptyTag",
+            Self::ParseInt(_) => "ParseInt",
+            Self::UnknownEndian(_) => "UnknownEndian",
+            Self::UnknownAccessType(_) => "UnknownAccessType",
+            Self::InvalidBitRange(_) => "InvalidBitRange",
+            Self::UnknownWriteConstraint => "UnknownWriteConstraint",
+            Self::MoreThanOneWriteConstraint => "MoreThanOneWriteConstraint",
+            Self::UnknownUsageVariant => "UnknownUsageVariant",
+            Self::UnknownAddressBlockUsageVariant => "UnknownAddressBlockUsageVariant",
+            Self::NotExpectedTag(_) => "NotExpectedTag",
+            Self::InvalidRegisterCluster(_) => "InvalidRegisterCluster",
+            Self::InvalidModifiedWriteValues(_) => "InvalidModifiedWriteValues",
+            Self::InvalidReadAction(_) => "InvalidReadAction",
+            Self::InvalidDataType(_) => "InvalidDataType",
+            Self::UnexpectedText(..) => "UnexpectedText",
+            Self::InvalidProtection(_) => "InvalidProtection",
+            Self::InvalidBooleanValue(..) => "InvalidBooleanValue",
+            Self::IncorrectDimIndexesCount(..) => "IncorrectDimIndexesCount",
+            Self::DimIndexParse(_) => "DimIndexParse",
+            Self::MissingPlaceholder(..) => "MissingPlaceholder",
+            Self::InvalidSauAccess(_) => "InvalidSauAccess",
+            Self::Xml(_) => "Xml",
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SVDErrorAt {
     error: SVDError,
     id: NodeId,
+    pos: Option<roxmltree::TextPos>,
+    path: Option<Box<str>>,
+}
+
+impl SVDErrorAt {
+    /// The error
+    pub fn error(&self) -> &SVDError {
+        &self.error
+    }
+
+    /// Id of the XML node the error occurred at
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Position of the node in the source text, if resolved
+    pub fn text_pos(&self) -> Option<roxmltree::TextPos> {
+        self.pos
+    }
+
+    /// Path of the element in the document like `device/peripherals/peripheral[3]/name`,
+    /// if resolved.
+    ///
+    /// Elements are numbered from 1 when their parent has several children with the same tag.
+    pub fn xml_path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Resolve the position and the path of the node in the source text
+    pub fn resolve_pos(&mut self, doc: &Document) {
+        if let Some(node) = doc.get_node(self.id) {
+            self.pos = Some(doc.text_pos_at(node.range().start));
+            self.path = Some(xml_path(node).into());
+        }
+    }
+}
+
+fn xml_path(node: Node) -> String {
+    let mut segments: Vec<_> = node
+        .ancestors()
+        .filter(Node::is_element)
+        .map(|n| {
+            let tag = n.tag_name().name();
+            let same_tag = |s: &Node| s.is_element() && s.tag_name().name() == tag;
+            let siblings = n
+                .parent()
+                .map_or(1, |p| p.children().filter(same_tag).count());
+            if siblings > 1 {
+                let index = n.prev_siblings().filter(same_tag).count();
+                format!("{tag}[{index}]")
+            } else {
+                tag.to_string()
+            }
+        })
+        .collect();
+    segments.reverse();
+    segments.join("/")
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SVDErrorAt {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        #[derive(serde::Serialize)]
+        struct Position {
+            line: u32,
+            column: u32,
+        }
+
+        let mut s = serializer.serialize_struct("SVDErrorAt", 4)?;
+        s.serialize_field("kind", self.error.kind())?;
+        s.serialize_field("message", &self.error.to_string())?;
+        s.serialize_field(
+            "position",
+            &self.pos.map(|pos| Position {
+                line: pos.row,
+                column: pos.col,
+            }),
+        )?;
+        s.serialize_field("path", &self.path)?;
+        s.end()
+    }
 }
 
 impl std::fmt::Display for SVDErrorAt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.error.fmt(f)
+        self.error.fmt(f)?;
+        if let Some(pos) = self.pos {
+            write!(f, " at {}:{}", pos.row, pos.col)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, " ({path})")?;
+        }
+        Ok(())
     }
 }
 
@@ -279,7 +540,12 @@ impl std::error::Error for SVDErrorAt {}
 
 impl SVDError {
     pub fn at(self, id: NodeId) -> SVDErrorAt {
-        SVDErrorAt { error: self, id }
+        SVDErrorAt {
+            error: self,
+            id,
+            pos: None,
+            path: None,
+        }
     }
 }
 
@@ -0,0 +1,23 @@
+use super::*;
+use crate::svd::Literals;
+use crate::types::NumberParse;
+
+/// Record the text of the numeric child elements `tags` of `tree`
+/// if [`Config::keep_literals`] is set
+pub(crate) fn parse_literals(
+    tree: &Node,
+    tags: &[&str],
+    config: &Config,
+) -> Result<Literals, SVDErrorAt> {
+    let mut literals = Literals::default();
+    if !config.keep_literals {
+        return Ok(literals);
+    }
+    for &tag in tags {
+        if let Some(child) = tree.get_child(tag) {
+            let value = NumberParse::<u64>::parse(&child, config)?;
+            literals.insert(tag, value, child.get_text()?.to_string());
+        }
+    }
+    Ok(literals)
+}
@@ -1,7 +1,5 @@
 use super::*;
-use crate::svd::{
-    AddressBlock, Interrupt, Peripheral, PeripheralInfo, RegisterCluster, RegisterProperties,
-};
+use crate::svd::{AddressBlock, Interrupt, Peripheral, PeripheralInfo, RegisterProperties};
 
 impl Parse for Peripheral {
     type Object = Self;
@@ -23,18 +21,20 @@ impl Parse for PeripheralInfo {
             return Err(SVDError::NotExpectedTag("peripheral".to_string()).at(tree.id()));
         }
 
+        let properties = RegisterProperties::parse(tree, config)?;
         PeripheralInfo::builder()
             .name(tree.get_child_text("name")?)
             .display_name(tree.get_child_text_opt("displayName")?)
             .version(tree.get_child_text_opt("version")?)
+            .comments(tree.preceding_comments())
             .description(tree.get_child_text_opt("description")?)
             .alternate_peripheral(tree.get_child_text_opt("alternatePeripheral")?)
             .group_name(tree.get_child_text_opt("groupName")?)
             .prepend_to_name(tree.get_child_text_opt("prependToName")?)
             .append_to_name(tree.get_child_text_opt("appendToName")?)
             .header_struct_name(tree.get_child_text_opt("headerStructName")?)
-            .base_address(tree.get_child_u64("baseAddress")?)
-            .default_register_properties(RegisterProperties::parse(tree, config)?)
+            .base_address(tree.get_child_u64_with_config("baseAddress", config)?)
+            .default_register_properties(properties)
             .address_block({
                 let ab: Result<Vec<_>, _> = tree
                     .children()
@@ -57,16 +57,21 @@ impl Parse for PeripheralInfo {
                 Some(interrupt?)
             })
             .registers(if let Some(registers) = tree.get_child("registers") {
-                let rs: Result<Vec<_>, _> = registers
-                    .children()
-                    .filter(Node::is_element)
-                    .map(|t| RegisterCluster::parse(&t, config))
-                    .collect();
-                Some(rs?)
+                registers.check_text(config)?;
+                Some(registercluster::parse_children(
+                    registers.children().filter(Node::is_element),
+                    &properties.inherit_from(&config.inherited),
+                    config,
+                )?)
             } else {
                 None
             })
             .derived_from(tree.attribute("derivedFrom").map(|s| s.to_owned()))
+            .literals(parse_literals(
+                tree,
+                &["baseAddress", "size", "resetValue", "resetMask"],
+                config,
+            )?)
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
@@ -1,6 +1,6 @@
 use super::*;
 use crate::svd::{
-    Field, ModifiedWriteValues, ReadAction, Register, RegisterInfo, RegisterProperties,
+    DataType, Field, ModifiedWriteValues, ReadAction, Register, RegisterInfo, RegisterProperties,
     WriteConstraint,
 };
 
@@ -20,39 +20,127 @@ impl Parse for RegisterInfo {
     type Config = Config;
 
     fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
-        RegisterInfo::builder()
-            .name(tree.get_child_text("name")?)
-            .display_name(tree.get_child_text_opt("displayName")?)
-            .description(tree.get_child_text_opt("description")?)
-            .alternate_group(tree.get_child_text_opt("alternateGroup")?)
-            .alternate_register(tree.get_child_text_opt("alternateRegister")?)
-            .address_offset(tree.get_child_u32("addressOffset")?)
-            .properties(RegisterProperties::parse(tree, config)?)
-            .modified_write_values(optional::<ModifiedWriteValues>(
-                "modifiedWriteValues",
-                tree,
-                config,
-            )?)
-            .write_constraint(optional::<WriteConstraint>(
-                "writeConstraint",
-                tree,
-                config,
-            )?)
-            .read_action(optional::<ReadAction>("readAction", tree, config)?)
-            .fields({
-                if let Some(fields) = tree.get_child("fields") {
-                    let fs: Result<Vec<_>, _> = fields
-                        .children()
-                        .filter(Node::is_element)
-                        .map(|t| Field::parse(&t, config))
-                        .collect();
-                    Some(fs?)
-                } else {
-                    None
-                }
-            })
-            .derived_from(tree.attribute("derivedFrom").map(|s| s.to_owned()))
-            .build(config.validate_level)
-            .map_err(|e| SVDError::from(e).at(tree.id()))
+        parse_info(tree, config, None)
+    }
+}
+
+/// Parse a register, placing it at `next_offset` if it has no `addressOffset`
+/// and validation is not strict
+pub(crate) fn parse_register_at(
+    tree: &Node,
+    config: &Config,
+    next_offset: u32,
+) -> Result<Register, SVDErrorAt> {
+    if !tree.has_tag_name("register") {
+        return Err(SVDError::NotExpectedTag("register".into()).at(tree.id()));
+    }
+    let info = parse_info(tree, config, Some(next_offset))?;
+    array::make_array("register", tree, config, info)
+}
+
+fn parse_info(
+    tree: &Node,
+    config: &Config,
+    next_offset: Option<u32>,
+) -> Result<RegisterInfo, SVDErrorAt> {
+    let name = tree.get_child_text("name")?;
+    let mut fields = if let Some(fields) = tree.get_child("fields") {
+        fields.check_text(config)?;
+        let fs: Result<Vec<_>, _> = fields
+            .children()
+            .filter(Node::is_element)
+            .map(|t| Field::parse(&t, config))
+            .collect();
+        Some(fs?)
+    } else {
+        None
+    };
+    if config.attach_register_enums && !config.ignore_enums {
+        for t in tree
+            .children()
+            .filter(|t| t.is_element() && t.has_tag_name("enumeratedValues"))
+        {
+            attach_enumerated_values(&name, fields.as_deref_mut(), &t, config)?;
+        }
+    }
+    let address_offset = match next_offset {
+        Some(offset)
+            if tree.get_child("addressOffset").is_none() && !config.validate_level.is_strict() =>
+        {
+            log::warn!(
+                "Register `{}` has no `addressOffset`, placing it at 0x{:x}",
+                name,
+                offset
+            );
+            offset
+        }
+        _ => tree.get_child_u32_with_config("addressOffset", config)?,
+    };
+    RegisterInfo::builder()
+        .name(name)
+        .display_name(tree.get_child_text_opt("displayName")?)
+        .description(tree.get_child_text_opt("description")?)
+        .comments(tree.preceding_comments())
+        .alternate_group(tree.get_child_text_opt("alternateGroup")?)
+        .alternate_register(tree.get_child_text_opt("alternateRegister")?)
+        .address_offset(address_offset)
+        .properties(RegisterProperties::parse(tree, config)?)
+        .modified_write_values(optional::<ModifiedWriteValues>(
+            "modifiedWriteValues",
+            tree,
+            config,
+        )?)
+        .write_constraint(optional::<WriteConstraint>(
+            "writeConstraint",
+            tree,
+            config,
+        )?)
+        .read_action(optional::<ReadAction>("readAction", tree, config)?)
+        .data_type(optional::<DataType>("dataType", tree, config)?)
+        .fields(fields)
+        .derived_from(tree.attribute("derivedFrom").map(|s| s.to_owned()))
+        .literals(parse_literals(
+            tree,
+            &["addressOffset", "size", "resetValue", "resetMask"],
+            config,
+        )?)
+        .build(config.validate_level)
+        .map_err(|e| SVDError::from(e).at(tree.id()))
+}
+
+/// Attaches `enumeratedValues` found in a register to the field with the same name
+/// or to the only field of the register
+fn attach_enumerated_values(
+    register: &str,
+    fields: Option<&mut [Field]>,
+    tree: &Node,
+    config: &Config,
+) -> Result<(), SVDErrorAt> {
+    let evs = match enumeratedvalues::parse_non_empty(tree, config)? {
+        Some(evs) => evs,
+        None => return Ok(()),
+    };
+    let fields = fields.unwrap_or_default();
+    let field = match evs.name.as_deref() {
+        Some(name) if fields.iter().any(|f| f.name == name) => {
+            fields.iter_mut().find(|f| f.name == name)
+        }
+        _ if fields.len() == 1 => fields.first_mut(),
+        _ => None,
+    };
+    match field {
+        Some(field) if field.enumerated_values.is_empty() => {
+            field.enumerated_values.push(evs);
+            field
+                .validate(config.validate_level)
+                .map_err(|e| SVDError::from(e).at(tree.id()))
+        }
+        _ => {
+            log::warn!(
+                "Can't determine the field for `enumeratedValues` of register `{}`, skipping",
+                register
+            );
+            Ok(())
+        }
     }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::svd::{Cluster, Register};
+use crate::svd::{Cluster, Register, RegisterProperties};
 
 use crate::svd::RegisterCluster;
 impl Parse for RegisterCluster {
@@ -17,3 +17,51 @@ impl Parse for RegisterCluster {
         }
     }
 }
+
+/// Parse registers and clusters.
+///
+/// If validation is not strict, a register without `addressOffset` is placed
+/// right after the previous register. Registers without `size` take it from
+/// `inherited`, the properties of their parents.
+pub(crate) fn parse_children<'a, 'input: 'a>(
+    nodes: impl Iterator<Item = Node<'a, 'input>>,
+    inherited: &RegisterProperties,
+    config: &Config,
+) -> Result<Vec<RegisterCluster>, SVDErrorAt> {
+    let config = &Config {
+        inherited: *inherited,
+        ..*config
+    };
+    let mut children = Vec::new();
+    for t in nodes {
+        let needs_offset = t.has_tag_name("register")
+            && t.get_child("addressOffset").is_none()
+            && !config.validate_level.is_strict();
+        let next_offset = match children.last() {
+            _ if !needs_offset => None,
+            None => Some(0),
+            Some(RegisterCluster::Register(r)) => register_end(r, inherited.size),
+            Some(RegisterCluster::Cluster(_)) => None,
+        };
+        let rc = match next_offset {
+            Some(offset) => register::parse_register_at(&t, config, offset)?.into(),
+            None => RegisterCluster::parse(&t, config)?,
+        };
+        children.push(rc);
+    }
+    Ok(children)
+}
+
+/// Offset right after the register, `None` if it's beyond the 32-bit address space
+fn register_end(r: &Register, inherited_size: Option<u32>) -> Option<u32> {
+    let size = (r.properties.size.or(inherited_size).unwrap_or(32) as u64 + 7) / 8;
+    let end = match r {
+        Register::Single(info) => info.address_offset as u64 + size,
+        Register::Array(info, dim) => {
+            info.address_offset as u64
+                + dim.dim.saturating_sub(1) as u64 * dim.dim_increment as u64
+                + size
+        }
+    };
+    u32::try_from(end).ok()
+}
@@ -1,5 +1,6 @@
 use super::*;
 use crate::svd::{Access, Protection, RegisterProperties};
+use crate::types::NumberParse;
 
 impl Parse for RegisterProperties {
     type Object = Self;
@@ -7,12 +8,31 @@ impl Parse for RegisterProperties {
     type Config = Config;
 
     fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
+        let mut size = optional::<NumberParse<u32>>("size", tree, config)?;
+        match size {
+            Some(bytes @ 1..=7) if !config.validate_level.is_strict() => {
+                let name = tree.get_child_text_opt("name")?.unwrap_or_default();
+                if config.fix_byte_sizes {
+                    let bits = bytes * config.address_unit_bits.unwrap_or(8);
+                    log::warn!(
+                        "Size {} of `{}` looks like a number of bytes, using {} bits",
+                        bytes,
+                        name,
+                        bits
+                    );
+                    size = Some(bits);
+                } else {
+                    log::warn!("Size {} of `{}` looks like a number of bytes", bytes, name);
+                }
+            }
+            _ => {}
+        }
         RegisterProperties::new()
-            .size(optional::<u32>("size", tree, &())?)
+            .size(size)
             .access(optional::<Access>("access", tree, config)?)
             .protection(optional::<Protection>("protection", tree, config)?)
-            .reset_value(optional::<u64>("resetValue", tree, &())?)
-            .reset_mask(optional::<u64>("resetMask", tree, &())?)
+            .reset_value(optional::<NumberParse<u64>>("resetValue", tree, config)?)
+            .reset_mask(optional::<NumberParse<u64>>("resetMask", tree, config)?)
             .build(config.validate_level)
             .map_err(|e| SVDError::from(e).at(tree.id()))
     }
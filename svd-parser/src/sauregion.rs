@@ -0,0 +1,69 @@
+use super::*;
+use crate::svd::{Protection, SauAccess, SauRegion, SauRegionsConfig};
+use crate::types::BoolParse;
+
+impl Parse for SauRegionsConfig {
+    type Object = Self;
+    type Error = SVDErrorAt;
+    type Config = Config;
+
+    fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
+        if !tree.has_tag_name("sauRegionsConfig") {
+            return Err(SVDError::NotExpectedTag("sauRegionsConfig".to_string()).at(tree.id()));
+        }
+        let protection_when_disabled = tree
+            .attribute("protectionWhenDisabled")
+            .map(|p| {
+                Protection::parse_str(p)
+                    .ok_or_else(|| SVDError::InvalidProtection(p.into()).at(tree.id()))
+            })
+            .transpose()?;
+        let mut builder = SauRegionsConfig::builder()
+            .protection_when_disabled(protection_when_disabled)
+            .regions(
+                tree.children()
+                    .filter(|t| t.is_element() && t.has_tag_name("region"))
+                    .map(|t| SauRegion::parse(&t, config))
+                    .collect::<Result<_, _>>()?,
+            );
+        if let Some(enabled) = tree
+            .attribute("enabled")
+            .map(|text| BoolParse::parse_text(text).map_err(|e| e.at(tree.id())))
+            .transpose()?
+        {
+            builder = builder.enabled(enabled);
+        }
+        builder
+            .build(config.validate_level)
+            .map_err(|e| SVDError::from(e).at(tree.id()))
+    }
+}
+
+impl Parse for SauRegion {
+    type Object = Self;
+    type Error = SVDErrorAt;
+    type Config = Config;
+
+    fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
+        let access = tree.get_child_elem("access")?;
+        let text = access.get_text()?;
+        let mut builder = SauRegion::builder()
+            .name(tree.attribute("name").map(|s| s.to_owned()))
+            .base(tree.get_child_u64_with_config("base", config)?)
+            .limit(tree.get_child_u64_with_config("limit", config)?)
+            .access(
+                SauAccess::parse_str(text)
+                    .ok_or_else(|| SVDError::InvalidSauAccess(text.into()).at(access.id()))?,
+            );
+        if let Some(enabled) = tree
+            .attribute("enabled")
+            .map(|text| BoolParse::parse_text(text).map_err(|e| e.at(tree.id())))
+            .transpose()?
+        {
+            builder = builder.enabled(enabled);
+        }
+        builder
+            .build(config.validate_level)
+            .map_err(|e| SVDError::from(e).at(tree.id()))
+    }
+}
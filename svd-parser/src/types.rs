@@ -2,8 +2,11 @@
 #![allow(clippy::manual_strip)]
 
 use roxmltree::Node;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::num::ParseIntError;
 
-use super::{ElementExt, Parse, SVDError, SVDErrorAt};
+use super::{Config, ElementExt, Parse, SVDError, SVDErrorAt};
 
 impl Parse for u32 {
     type Object = u32;
@@ -11,27 +14,7 @@ impl Parse for u32 {
     type Config = ();
 
     fn parse(tree: &Node, _config: &Self::Config) -> Result<u32, Self::Error> {
-        let text = tree.get_text()?;
-
-        (if text.starts_with("0x") || text.starts_with("0X") {
-            u32::from_str_radix(&text["0x".len()..], 16)
-        } else if text.starts_with('#') {
-            // Handle strings in the binary form of:
-            // #01101x1
-            // along with don't care character x (replaced with 0)
-            u32::from_str_radix(
-                &str::replace(&text.to_lowercase()["#".len()..], "x", "0"),
-                2,
-            )
-        } else if text.starts_with("0b") {
-            // Handle strings in the binary form of:
-            // 0b01101x1
-            // along with don't care character x (replaced with 0)
-            u32::from_str_radix(&str::replace(&text["0b".len()..], "x", "0"), 2)
-        } else {
-            text.parse::<u32>()
-        })
-        .map_err(|e| SVDError::from(e).at(tree.id()))
+        parse_number(tree, false, parse_u32)
     }
 }
 
@@ -41,27 +24,157 @@ impl Parse for u64 {
     type Config = ();
 
     fn parse(tree: &Node, _config: &Self::Config) -> Result<u64, Self::Error> {
-        let text = tree.get_text()?;
+        parse_number(tree, false, parse_u64)
+    }
+}
+
+/// Parses a number like `u32` and `u64` do, but tolerates annotated values
+/// (see [`parse_number`]) if validation is not strict.
+pub struct NumberParse<T>(PhantomData<T>);
+
+impl Parse for NumberParse<u32> {
+    type Object = u32;
+    type Error = SVDErrorAt;
+    type Config = Config;
 
-        (if text.starts_with("0x") || text.starts_with("0X") {
-            u64::from_str_radix(&text["0x".len()..], 16)
-        } else if text.starts_with('#') {
-            // Handle strings in the binary form of:
-            // #01101x1
-            // along with don't care character x (replaced with 0)
-            u64::from_str_radix(
-                &str::replace(&text.to_lowercase()["#".len()..], "x", "0"),
-                2,
-            )
-        } else if text.starts_with("0b") {
-            // Handle strings in the binary form of:
-            // 0b01101x1
-            // along with don't care character x (replaced with 0)
-            u64::from_str_radix(&str::replace(&text["0b".len()..], "x", "0"), 2)
-        } else {
-            text.parse::<u64>()
-        })
-        .map_err(|e| SVDError::from(e).at(tree.id()))
+    fn parse(tree: &Node, config: &Self::Config) -> Result<u32, Self::Error> {
+        parse_number(tree, !config.validate_level.is_strict(), parse_u32)
+    }
+}
+
+impl Parse for NumberParse<u64> {
+    type Object = u64;
+    type Error = SVDErrorAt;
+    type Config = Config;
+
+    fn parse(tree: &Node, config: &Self::Config) -> Result<u64, Self::Error> {
+        parse_number(tree, !config.validate_level.is_strict(), parse_u64)
+    }
+}
+
+/// Parses the text of the element as a number.
+///
+/// `_` separators between digits are ignored. If it can't be parsed and `lenient`
+/// is set, spaces between groups of digits (of 3 digits, 4 for hexadecimal and binary
+/// numbers) and trailing content separated from the number
+/// by a space or `;` are ignored with a warning. The trailing content must be
+/// a comment, starting with `;` or `<!--`, or an annotation without digits, like units.
+fn parse_number<T>(
+    tree: &Node,
+    lenient: bool,
+    parse: fn(&str) -> Result<T, ParseIntError>,
+) -> Result<T, SVDErrorAt> {
+    let text = tree.get_text()?;
+    let parse = |text: &str| parse(&strip_underscores(text));
+    parse(text).or_else(|e| {
+        if lenient {
+            if let Some(compact) = join_digit_groups(text) {
+                if let Ok(value) = parse(&compact) {
+                    log::warn!(
+                        "Ignoring spaces in number `{}` in <{}>",
+                        text,
+                        tree.tag_name().name()
+                    );
+                    return Ok(value);
+                }
+            }
+            if let Some(end) = text.find(|c: char| c.is_whitespace() || c == ';') {
+                let (number, rest) = text.split_at(end);
+                let rest = rest.trim();
+                let annotation = rest.starts_with(';')
+                    || rest.starts_with("<!--")
+                    || !rest.contains(|c: char| c.is_ascii_digit());
+                if annotation {
+                    if let Ok(value) = parse(number) {
+                        log::warn!(
+                            "Ignoring `{}` after number `{}` in <{}>",
+                            rest,
+                            number,
+                            tree.tag_name().name()
+                        );
+                        return Ok(value);
+                    }
+                }
+            }
+        }
+        Err(SVDError::from(e).at(tree.id()))
+    })
+}
+
+/// Joins `text` split by spaces in groups of digits, like `0x4000 0000`
+fn join_digit_groups(text: &str) -> Option<String> {
+    let mut groups = text.split_whitespace();
+    let mut compact = groups.next()?.to_string();
+    let width = if ["0x", "0X", "0b", "#"]
+        .iter()
+        .any(|p| compact.starts_with(p))
+    {
+        4
+    } else {
+        3
+    };
+    let mut joined = false;
+    for group in groups {
+        if group.len() != width {
+            return None;
+        }
+        compact.push_str(group);
+        joined = true;
+    }
+    if joined {
+        Some(compact)
+    } else {
+        None
+    }
+}
+
+fn strip_underscores(text: &str) -> Cow<'_, str> {
+    if text.contains('_') {
+        text.replace('_', "").into()
+    } else {
+        text.into()
+    }
+}
+
+fn parse_u32(text: &str) -> Result<u32, ParseIntError> {
+    if text.starts_with("0x") || text.starts_with("0X") {
+        u32::from_str_radix(&text["0x".len()..], 16)
+    } else if text.starts_with('#') {
+        // Handle strings in the binary form of:
+        // #01101x1
+        // along with don't care character x (replaced with 0)
+        u32::from_str_radix(
+            &str::replace(&text.to_lowercase()["#".len()..], "x", "0"),
+            2,
+        )
+    } else if text.starts_with("0b") {
+        // Handle strings in the binary form of:
+        // 0b01101x1
+        // along with don't care character x (replaced with 0)
+        u32::from_str_radix(&str::replace(&text["0b".len()..], "x", "0"), 2)
+    } else {
+        text.parse::<u32>()
+    }
+}
+
+fn parse_u64(text: &str) -> Result<u64, ParseIntError> {
+    if text.starts_with("0x") || text.starts_with("0X") {
+        u64::from_str_radix(&text["0x".len()..], 16)
+    } else if text.starts_with('#') {
+        // Handle strings in the binary form of:
+        // #01101x1
+        // along with don't care character x (replaced with 0)
+        u64::from_str_radix(
+            &str::replace(&text.to_lowercase()["#".len()..], "x", "0"),
+            2,
+        )
+    } else if text.starts_with("0b") {
+        // Handle strings in the binary form of:
+        // 0b01101x1
+        // along with don't care character x (replaced with 0)
+        u64::from_str_radix(&str::replace(&text["0b".len()..], "x", "0"), 2)
+    } else {
+        text.parse::<u64>()
     }
 }
 
@@ -74,13 +187,19 @@ impl Parse for BoolParse {
 
     fn parse(tree: &Node, _config: &Self::Config) -> Result<bool, Self::Error> {
         let text = tree.get_text()?;
+        Self::parse_text(text).map_err(|e| e.at(tree.id()))
+    }
+}
+
+impl BoolParse {
+    /// Parses a boolean written as `0`, `1`, `true` or `false`, e.g. in an attribute
+    pub(crate) fn parse_text(text: &str) -> Result<bool, SVDError> {
         match text {
             "0" => Ok(false),
             "1" => Ok(true),
-            _ => match text.parse() {
-                Ok(b) => Ok(b),
-                Err(e) => Err(SVDError::InvalidBooleanValue(text.into(), e).at(tree.id())),
-            },
+            _ => text
+                .parse()
+                .map_err(|e| SVDError::InvalidBooleanValue(text.into(), e)),
         }
     }
 }
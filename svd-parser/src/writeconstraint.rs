@@ -6,7 +6,7 @@ impl Parse for WriteConstraint {
     type Error = SVDErrorAt;
     type Config = Config;
 
-    fn parse(tree: &Node, _config: &Self::Config) -> Result<Self, Self::Error> {
+    fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
         let child = tree.first_element_child().unwrap();
         if child.next_sibling_element().is_some() {
             return Err(SVDError::MoreThanOneWriteConstraint.at(tree.id()));
@@ -18,7 +18,7 @@ impl Parse for WriteConstraint {
             "useEnumeratedValues" => tree
                 .get_child_bool(field)
                 .map(WriteConstraint::UseEnumeratedValues),
-            "range" => WriteConstraintRange::parse(&tree.get_child_elem(field)?, &())
+            "range" => WriteConstraintRange::parse(&tree.get_child_elem(field)?, config)
                 .map(WriteConstraint::Range),
             _ => Err(SVDError::UnknownWriteConstraint.at(tree.id())),
         }
@@ -28,12 +28,12 @@ impl Parse for WriteConstraint {
 impl Parse for WriteConstraintRange {
     type Object = Self;
     type Error = SVDErrorAt;
-    type Config = ();
+    type Config = Config;
 
-    fn parse(tree: &Node, _config: &Self::Config) -> Result<Self, Self::Error> {
+    fn parse(tree: &Node, config: &Self::Config) -> Result<Self, Self::Error> {
         Ok(Self {
-            min: tree.get_child_u64("minimum")?,
-            max: tree.get_child_u64("maximum")?,
+            min: tree.get_child_u64_with_config("minimum", config)?,
+            max: tree.get_child_u64_with_config("maximum", config)?,
         })
     }
 }
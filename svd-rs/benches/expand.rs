@@ -0,0 +1,63 @@
+//! Compares eager and lazy expansion of a large register array.
+//!
+//! Run with `cargo bench -p svd-rs --bench expand`.
+
+use std::time::{Duration, Instant};
+use svd_rs::{BitRange, DimElement, Field, FieldInfo, Register, RegisterInfo, ValidateLevel};
+
+const ITERATIONS: u32 = 100;
+
+fn array() -> Register {
+    let fields = (0..16)
+        .map(|i| {
+            Field::Single(
+                FieldInfo::builder()
+                    .name(format!("F{i}"))
+                    .description(Some(format!("Bit {i} of %s")))
+                    .bit_range(BitRange::from_offset_width(i * 2, 2))
+                    .build(ValidateLevel::Strict)
+                    .unwrap(),
+            )
+        })
+        .collect();
+    let info = RegisterInfo::builder()
+        .name("CCR%s".to_string())
+        .description(Some("Capture/compare %s".to_string()))
+        .address_offset(0)
+        .fields(Some(fields))
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let dim = DimElement::builder()
+        .dim(1024)
+        .dim_increment(4)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    info.array(dim)
+}
+
+/// Average time of `f`, and the sum of its results so that the work isn't optimized out
+fn time(f: impl Fn() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let sum = (0..ITERATIONS).map(|_| f()).sum();
+    (start.elapsed() / ITERATIONS, sum)
+}
+
+fn main() {
+    let array = array();
+    let (eager, eager_sum) = time(|| {
+        array
+            .expanded()
+            .iter()
+            .map(|r| r.address_offset as usize + r.name.len())
+            .sum()
+    });
+    let (lazy, lazy_sum) = time(|| {
+        array
+            .expanded_lazy()
+            .map(|r| r.address_offset() as usize + r.name().len())
+            .sum()
+    });
+    assert_eq!(eager_sum, lazy_sum);
+    println!("expanded:      {eager:?} per array of 1024 registers");
+    println!("expanded_lazy: {lazy:?} per array of 1024 registers");
+}
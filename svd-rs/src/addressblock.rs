@@ -1,3 +1,4 @@
+use super::prelude::*;
 use super::{BuildError, Protection, SvdError, ValidateLevel};
 
 ///  An uniquely mapped address block to a peripheral
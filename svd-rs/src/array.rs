@@ -1,4 +1,8 @@
-use super::{Description, DimElement, Name};
+use super::prelude::*;
+use super::{
+    CmsisMangler, Description, DimElement, EnumeratedValue, Name, NameMangler, SvdError,
+    ValidateLevel,
+};
 use core::ops::{Deref, DerefMut};
 
 /// A single SVD instance or array of instances
@@ -59,20 +63,62 @@ where
     }
 }
 
+/// Whether `name` is the name of `item` or of one of its array elements.
+///
+/// Elements of `DR[%s]` match both as `DR3` and as `DR[3]`.
+pub(crate) fn matches_name<T: Name>(item: &MaybeArray<T>, name: &str) -> bool {
+    match item {
+        MaybeArray::Single(info) => info.name() == name,
+        MaybeArray::Array(info, dim) => {
+            info.name() == name
+                || dim.indexes().any(|i| {
+                    element_name(info, dim, &i, &CmsisMangler) == name
+                        || info.name().replace("%s", &i) == name
+                })
+        }
+    }
+}
+
+/// Applies the array dimension of `other` to `item`, single `other`s keep the dimension of `item`
+pub(crate) fn merge_dim<T: Clone>(
+    item: &mut MaybeArray<T>,
+    other: &MaybeArray<T>,
+    lvl: ValidateLevel,
+) -> Result<(), SvdError> {
+    if let MaybeArray::Array(_, other_dim) = other {
+        match item {
+            MaybeArray::Array(_, dim) => dim.modify_from(other_dim.clone().into(), lvl)?,
+            MaybeArray::Single(info) => *item = MaybeArray::Array(info.clone(), other_dim.clone()),
+        }
+    }
+    Ok(())
+}
+
 /// Return list of names of instances in array
 pub fn names<'a, T: Name>(info: &'a T, dim: &'a DimElement) -> impl Iterator<Item = String> + 'a {
-    let name = info.name();
-    dim.indexes().map(move |i| {
-        dim.dim_array_index
-            .as_ref()
-            .and_then(|dai| {
-                dai.values
-                    .iter()
-                    .find(|e| e.value.map(|v| v.to_string().as_str() == i.deref()) == Some(true))
-            })
-            .map(|n| n.name.clone())
-            .unwrap_or_else(|| name.replace("[%s]", &i).replace("%s", &i))
-    })
+    names_with(info, dim, &CmsisMangler)
+}
+
+/// Return list of names of instances in array built with `mangler`
+pub fn names_with<'a, T: Name, M: NameMangler + ?Sized>(
+    info: &'a T,
+    dim: &'a DimElement,
+    mangler: &'a M,
+) -> impl Iterator<Item = String> + 'a {
+    dim.indexes()
+        .map(move |i| element_name(info, dim, &i, mangler))
+}
+
+/// Name of the array element with index `i`
+pub(crate) fn element_name<T: Name, M: NameMangler + ?Sized>(
+    info: &T,
+    dim: &DimElement,
+    i: &str,
+    mangler: &M,
+) -> String {
+    dim_array_value(dim, i)
+        .map(|n| n.name.clone())
+        .unwrap_or_else(|| mangler.array_element(info.name(), i))
 }
 
 /// Return list of descriptions of instances in array
@@ -80,17 +126,29 @@ pub fn descriptions<'a, T: Description>(
     info: &'a T,
     dim: &'a DimElement,
 ) -> impl Iterator<Item = Option<String>> + 'a {
-    let description = info.description();
-    dim.indexes().map(move |i| {
-        dim.dim_array_index
-            .as_ref()
-            .and_then(|dai| {
-                dai.values
-                    .iter()
-                    .find(|e| e.value.map(|v| v.to_string().as_str() == i.deref()) == Some(true))
-            })
-            .and_then(|n| n.description.clone())
-            .or_else(|| description.map(|d| d.replace("[%s]", &i).replace("%s", &i)))
+    dim.indexes()
+        .map(move |i| element_description(info, dim, &i))
+}
+
+/// Description of the array element with index `i`
+pub(crate) fn element_description<T: Description>(
+    info: &T,
+    dim: &DimElement,
+    i: &str,
+) -> Option<String> {
+    dim_array_value(dim, i)
+        .and_then(|n| n.description.clone())
+        .or_else(|| {
+            info.description()
+                .map(|d| DimElement::replace_placeholder(d, i))
+        })
+}
+
+fn dim_array_value<'a>(dim: &'a DimElement, i: &str) -> Option<&'a EnumeratedValue> {
+    dim.dim_array_index.as_ref().and_then(|dai| {
+        dai.values
+            .iter()
+            .find(|e| e.value.map(|v| v.to_string() == i) == Some(true))
     })
 }
 
@@ -1,3 +1,4 @@
+use super::prelude::*;
 /// Errors for bit ranges
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
@@ -1,13 +1,14 @@
+use super::prelude::*;
 use super::{
-    array::{descriptions, names},
+    array::{descriptions, merge_dim, names},
     registercluster::{
-        AllRegistersIter, AllRegistersIterMut, ClusterIter, ClusterIterMut, RegisterIter,
+        self, AllRegistersIter, AllRegistersIterMut, ClusterIter, ClusterIterMut, RegisterIter,
         RegisterIterMut,
     },
-    BuildError, Description, DimElement, EmptyToNone, MaybeArray, Name, Register, RegisterCluster,
-    RegisterProperties, SvdError, ValidateLevel,
+    BuildError, Description, DimElement, EmptyToNone, Literals, MaybeArray, Name, Register,
+    RegisterCluster, RegisterProperties, SvdError, ValidateLevel,
 };
-use std::ops::Deref;
+use core::ops::Deref;
 
 /// Cluster describes a sequence of neighboring registers within a peripheral.
 pub type Cluster = MaybeArray<ClusterInfo>;
@@ -18,6 +19,9 @@ pub enum Error {
     /// The cluster can not be empty
     #[error("Cluster must contain at least one Register or Cluster")]
     EmptyCluster,
+    /// Several registers or clusters of the cluster have the same name
+    #[error("Register or cluster `{0}` is defined more than once")]
+    DuplicateName(String),
 }
 
 /// Description of a cluster
@@ -71,14 +75,28 @@ pub struct ClusterInfo {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub derived_from: Option<String>,
+
+    /// Comments preceding the element in the SVD file
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub comments: Vec<String>,
+
+    /// Numbers as written in the SVD file
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub literals: Literals,
 }
 
-/// Return iterator over address offsets of each cluster in array
+/// Return iterator over address offsets of each cluster in array, wrapping around `u32::MAX`
 pub fn address_offsets<'a>(
     info: &'a ClusterInfo,
     dim: &'a DimElement,
 ) -> impl Iterator<Item = u32> + 'a {
-    (0..dim.dim).map(move |i| info.address_offset + i * dim.dim_increment)
+    (0..dim.dim).map(move |i| {
+        info.address_offset
+            .wrapping_add(i.wrapping_mul(dim.dim_increment))
+    })
 }
 
 /// Extract `ClusterInfo` items from array
@@ -109,6 +127,8 @@ pub struct ClusterInfoBuilder {
     default_register_properties: RegisterProperties,
     children: Option<Vec<RegisterCluster>>,
     derived_from: Option<String>,
+    comments: Option<Vec<String>>,
+    literals: Option<Literals>,
 }
 
 impl From<ClusterInfo> for ClusterInfoBuilder {
@@ -122,6 +142,8 @@ impl From<ClusterInfo> for ClusterInfoBuilder {
             default_register_properties: c.default_register_properties,
             children: Some(c.children),
             derived_from: c.derived_from,
+            comments: Some(c.comments),
+            literals: Some(c.literals),
         }
     }
 }
@@ -167,6 +189,16 @@ impl ClusterInfoBuilder {
         self.derived_from = value;
         self
     }
+    /// Set the comments preceding the element
+    pub fn comments(mut self, value: Vec<String>) -> Self {
+        self.comments = Some(value);
+        self
+    }
+    /// Set the numbers as written in the SVD file
+    pub fn literals(mut self, value: Literals) -> Self {
+        self.literals = Some(value);
+        self
+    }
     /// Validate and build a [`ClusterInfo`].
     pub fn build(self, lvl: ValidateLevel) -> Result<ClusterInfo, SvdError> {
         let cluster = ClusterInfo {
@@ -184,6 +216,8 @@ impl ClusterInfoBuilder {
                 .children
                 .ok_or_else(|| BuildError::Uninitialized("children".to_string()))?,
             derived_from: self.derived_from,
+            comments: self.comments.unwrap_or_default(),
+            literals: self.literals.unwrap_or_default(),
         };
         cluster.validate(lvl)?;
         Ok(cluster)
@@ -224,6 +258,12 @@ impl ClusterInfo {
         if let Some(address_offset) = builder.address_offset {
             self.address_offset = address_offset;
         }
+        if let Some(comments) = builder.comments {
+            self.comments = comments;
+        }
+        if let Some(literals) = builder.literals {
+            self.literals = literals;
+        }
         if builder.derived_from.is_some() {
             self.derived_from = builder.derived_from;
             self.children = Vec::new();
@@ -240,9 +280,19 @@ impl ClusterInfo {
 
     /// Validate the [`ClusterInfo`]
     pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_element(lvl)?;
+        match self.check_children(lvl).into_iter().next() {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+    fn validate_element(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
         if !lvl.is_disabled() {
             if lvl.is_strict() {
                 super::check_dimable_name(&self.name, "name")?;
+                if let Some(name) = self.header_struct_name.as_ref() {
+                    super::check_name(name, "headerStructName")?;
+                }
             }
             if let Some(name) = self.derived_from.as_ref() {
                 if lvl.is_strict() {
@@ -256,24 +306,40 @@ impl ClusterInfo {
     }
     /// Validate the [`ClusterInfo`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        self.default_register_properties.validate(lvl)?;
-        for r in self.registers() {
-            r.validate_all(lvl)?;
-        }
-        for c in self.clusters() {
-            c.validate_all(lvl)?;
+        super::first_error(self.validate_collect(lvl))
+    }
+    /// Validate the [`ClusterInfo`] recursively, collecting all errors instead of stopping at the first one
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors: Vec<_> = self
+            .default_register_properties
+            .validate(lvl)
+            .err()
+            .into_iter()
+            .collect();
+        errors.extend(registercluster::validate_collect(&self.children, lvl));
+        errors.extend(self.validate_element(lvl).err());
+        errors.extend(self.check_children(lvl).into_iter().map(SvdError::from));
+        errors
+    }
+    /// Check that registers and clusters have unique names, returning all errors
+    fn check_children(&self, lvl: ValidateLevel) -> Vec<Error> {
+        if !lvl.is_strict() || self.derived_from.is_some() {
+            return Vec::new();
         }
-        self.validate(lvl)
+        registercluster::duplicate_names(&self.children)
+            .into_iter()
+            .map(|name| Error::DuplicateName(name.into()))
+            .collect()
     }
 
     /// Returns iterator over all descendant registers
     #[deprecated(since = "0.12.1", note = "Please use `all_registers` instead")]
-    pub fn reg_iter(&self) -> AllRegistersIter {
+    pub fn reg_iter(&self) -> AllRegistersIter<'_> {
         self.all_registers()
     }
 
     /// Returns iterator over all descendant registers
-    pub fn all_registers(&self) -> AllRegistersIter {
+    pub fn all_registers(&self) -> AllRegistersIter<'_> {
         AllRegistersIter {
             rem: self.children.iter().rev().collect(),
         }
@@ -281,73 +347,124 @@ impl ClusterInfo {
 
     /// Returns mutable iterator over all descendant registers
     #[deprecated(since = "0.12.1", note = "Please use `all_registers_mut` instead")]
-    pub fn reg_iter_mut(&mut self) -> AllRegistersIterMut {
+    pub fn reg_iter_mut(&mut self) -> AllRegistersIterMut<'_> {
         self.all_registers_mut()
     }
 
     /// Returns mutable iterator over all descendant registers
-    pub fn all_registers_mut(&mut self) -> AllRegistersIterMut {
+    pub fn all_registers_mut(&mut self) -> AllRegistersIterMut<'_> {
         AllRegistersIterMut {
             rem: self.children.iter_mut().rev().collect(),
         }
     }
 
     /// Returns iterator over child registers
-    pub fn registers(&self) -> RegisterIter {
+    pub fn registers(&self) -> RegisterIter<'_> {
         RegisterIter {
             all: self.children.iter(),
         }
     }
 
     /// Returns mutable iterator over child registers
-    pub fn registers_mut(&mut self) -> RegisterIterMut {
+    pub fn registers_mut(&mut self) -> RegisterIterMut<'_> {
         RegisterIterMut {
             all: self.children.iter_mut(),
         }
     }
 
     /// Returns iterator over child clusters
-    pub fn clusters(&self) -> ClusterIter {
+    pub fn clusters(&self) -> ClusterIter<'_> {
         ClusterIter {
             all: self.children.iter(),
         }
     }
 
     /// Returns mutable iterator over child clusters
-    pub fn clusters_mut(&mut self) -> ClusterIterMut {
+    pub fn clusters_mut(&mut self) -> ClusterIterMut<'_> {
         ClusterIterMut {
             all: self.children.iter_mut(),
         }
     }
 
-    /// Get register by name
+    /// Get register by name.
+    ///
+    /// Arrays are also found by element names like `DR3` or `DR[3]`,
+    /// registers inside of clusters by `CLUSTER.REG` paths.
     pub fn get_register(&self, name: &str) -> Option<&Register> {
-        self.registers().find(|f| f.name == name)
+        registercluster::get_register(&self.children, name)
     }
 
     /// Get mutable register by name
     pub fn get_mut_register(&mut self, name: &str) -> Option<&mut Register> {
-        self.registers_mut().find(|f| f.name == name)
+        registercluster::get_mut_register(&mut self.children, name)
     }
 
-    /// Get cluster by name
+    /// Get cluster by name.
+    ///
+    /// Arrays are also found by element names,
+    /// nested clusters by `CLUSTER.NESTED` paths.
     pub fn get_cluster(&self, name: &str) -> Option<&Cluster> {
-        self.clusters().find(|f| f.name == name)
+        registercluster::get_cluster(&self.children, name)
     }
 
     /// Get mutable cluster by name
     pub fn get_mut_cluster(&mut self, name: &str) -> Option<&mut Cluster> {
-        self.clusters_mut().find(|f| f.name == name)
+        registercluster::get_mut_cluster(&mut self.children, name)
     }
 }
 
 impl Cluster {
+    /// Merges a partial cluster, e.g. of a patch file, into this cluster.
+    ///
+    /// Children are merged like the registers of [`Peripheral::merge`](crate::Peripheral::merge).
+    /// Comments and literals are kept if `other` has none.
+    /// `name` and `addressOffset` are always present in `other` and are kept.
+    pub(crate) fn merge(&mut self, other: &Cluster, lvl: ValidateLevel) -> Result<(), SvdError> {
+        let mut builder = ClusterInfoBuilder::from((**other).clone());
+        builder.name = None;
+        builder.address_offset = None;
+        builder.derived_from = None;
+        builder.children = None;
+        if other.comments.is_empty() {
+            builder.comments = None;
+        }
+        if other.literals.is_empty() {
+            builder.literals = None;
+        }
+        let info: &mut ClusterInfo = self;
+        info.modify_from(builder, lvl)?;
+        if other.derived_from.is_some() {
+            self.derived_from = other.derived_from.clone();
+        }
+        registercluster::merge_children(&mut self.children, &other.children, lvl)?;
+        (**self).validate(lvl)?;
+        merge_dim(self, other, lvl)
+    }
+
+    /// Expand array into single clusters with substituted names and descriptions
+    /// and the address offsets of the elements.
+    ///
+    /// Single clusters are returned as is.
+    pub fn expanded(&self) -> Vec<ClusterInfo> {
+        match self {
+            Self::Single(info) => vec![info.clone()],
+            Self::Array(info, dim) => expand(info, dim).collect(),
+        }
+    }
+
     /// Validate the [`Cluster`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        if let Self::Array(_, dim) = self {
-            dim.validate(lvl)?;
+        super::first_error(self.validate_collect(lvl))
+    }
+
+    /// Validate the [`Cluster`] recursively, collecting all errors instead of stopping at the first one
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
+        if let Self::Array(info, dim) = self {
+            errors.extend(super::validate_array(&info.name, dim, lvl));
         }
-        self.deref().validate_all(lvl)
+        errors.extend(self.deref().validate_collect(lvl));
+        errors
     }
 }
 
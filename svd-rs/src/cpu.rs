@@ -1,4 +1,14 @@
-use super::{BuildError, Endian, SvdError, ValidateLevel};
+use super::prelude::*;
+use super::{BuildError, Endian, SauRegionsConfig, SvdError, ValidateLevel};
+
+/// Errors for [`Cpu::validate`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The SAU configuration has more regions than the SAU supports
+    #[error("sauRegionsConfig declares {0} regions, but sauNumRegions is {1}")]
+    TooManySauRegions(usize, u32),
+}
+
 /// CPU describes the processor included in the microcontroller device.
 #[cfg_attr(
     feature = "serde",
@@ -103,7 +113,58 @@ pub struct Cpu {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub sau_num_regions: Option<u32>,
-    // sauRegionsConfig
+
+    /// Indicate the default configuration of the Security Attribution Unit (SAU)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub sau_regions_config: Option<SauRegionsConfig>,
+}
+
+/// Standard processor names from the `cpuNameType` of the SVD schema
+pub const KNOWN_CPU_NAMES: &[&str] = &[
+    "CM0",
+    "CM0PLUS",
+    "CM1",
+    "CM3",
+    "CM4",
+    "CM7",
+    "CM23",
+    "CM33",
+    "CM35P",
+    "CM55",
+    "CM85",
+    "SC000",
+    "SC300",
+    "ARMV8MML",
+    "ARMV8MBL",
+    "ARMV81MML",
+    "CA5",
+    "CA7",
+    "CA8",
+    "CA9",
+    "CA15",
+    "CA17",
+    "CA53",
+    "CA57",
+    "CA72",
+    "SMC1",
+];
+
+/// Get the standard spelling of a processor name.
+///
+/// Matching ignores case, `Cortex-M4`-like spellings are accepted and `CM0+` is recognized as `CM0PLUS`.
+/// Returns `None` for custom cores.
+pub fn normalize_cpu_name(name: &str) -> Option<&'static str> {
+    let mut name = name.trim().to_ascii_uppercase().replace('+', "PLUS");
+    for prefix in ["ARM CORTEX-", "CORTEX-", "CORTEX"] {
+        if let Some(core) = name.strip_prefix(prefix) {
+            name = format!("C{}", core.trim_start());
+            break;
+        }
+    }
+    KNOWN_CPU_NAMES.iter().copied().find(|&n| n == name)
 }
 
 /// Builder for [`Cpu`]
@@ -125,6 +186,7 @@ pub struct CpuBuilder {
     has_vendor_systick: Option<bool>,
     device_num_interrupts: Option<u32>,
     sau_num_regions: Option<u32>,
+    sau_regions_config: Option<SauRegionsConfig>,
 }
 
 impl From<Cpu> for CpuBuilder {
@@ -146,6 +208,7 @@ impl From<Cpu> for CpuBuilder {
             has_vendor_systick: Some(c.has_vendor_systick),
             device_num_interrupts: c.device_num_interrupts,
             sau_num_regions: c.sau_num_regions,
+            sau_regions_config: c.sau_regions_config,
         }
     }
 }
@@ -231,6 +294,11 @@ impl CpuBuilder {
         self.sau_num_regions = value;
         self
     }
+    /// Set the sau_regions_config of the cpu.
+    pub fn sau_regions_config(mut self, value: Option<SauRegionsConfig>) -> Self {
+        self.sau_regions_config = value;
+        self
+    }
     /// Validate and build a [`Cpu`].
     pub fn build(self, lvl: ValidateLevel) -> Result<Cpu, SvdError> {
         let cpu = Cpu {
@@ -262,6 +330,7 @@ impl CpuBuilder {
                 .ok_or_else(|| BuildError::Uninitialized("has_vendor_systick".to_string()))?,
             device_num_interrupts: self.device_num_interrupts,
             sau_num_regions: self.sau_num_regions,
+            sau_regions_config: self.sau_regions_config,
         };
         cpu.validate(lvl)?;
         Ok(cpu)
@@ -323,13 +392,26 @@ impl Cpu {
         if builder.sau_num_regions.is_some() {
             self.sau_num_regions = builder.sau_num_regions;
         }
+        if builder.sau_regions_config.is_some() {
+            self.sau_regions_config = builder.sau_regions_config;
+        }
         self.validate(lvl)
     }
     /// Validate the [`Cpu`]
-    pub fn validate(&self, _lvl: ValidateLevel) -> Result<(), SvdError> {
-        // TODO
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if let Some(sau) = &self.sau_regions_config {
+            sau.validate(lvl)?;
+            let max = self.sau_num_regions.unwrap_or(0);
+            if lvl.is_strict() && sau.regions.len() > max as usize {
+                return Err(Error::TooManySauRegions(sau.regions.len(), max).into());
+            }
+        }
         Ok(())
     }
+    /// Get the standard spelling of the processor name, `None` for custom cores
+    pub fn known_name(&self) -> Option<&'static str> {
+        normalize_cpu_name(&self.name)
+    }
     /// Check if the [`Cpu`] is a Cortex-M
     pub fn is_cortex_m(&self) -> bool {
         self.name.starts_with("CM")
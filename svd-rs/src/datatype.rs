@@ -0,0 +1,101 @@
+/// C data type of a register or field, used in the generated device header
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataType {
+    /// `uint8_t`
+    #[cfg_attr(feature = "serde", serde(rename = "uint8_t"))]
+    U8,
+    /// `uint16_t`
+    #[cfg_attr(feature = "serde", serde(rename = "uint16_t"))]
+    U16,
+    /// `uint32_t`
+    #[cfg_attr(feature = "serde", serde(rename = "uint32_t"))]
+    U32,
+    /// `uint64_t`
+    #[cfg_attr(feature = "serde", serde(rename = "uint64_t"))]
+    U64,
+    /// `int8_t`
+    #[cfg_attr(feature = "serde", serde(rename = "int8_t"))]
+    I8,
+    /// `int16_t`
+    #[cfg_attr(feature = "serde", serde(rename = "int16_t"))]
+    I16,
+    /// `int32_t`
+    #[cfg_attr(feature = "serde", serde(rename = "int32_t"))]
+    I32,
+    /// `int64_t`
+    #[cfg_attr(feature = "serde", serde(rename = "int64_t"))]
+    I64,
+    /// `uint8_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "uint8_t *"))]
+    U8Ptr,
+    /// `uint16_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "uint16_t *"))]
+    U16Ptr,
+    /// `uint32_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "uint32_t *"))]
+    U32Ptr,
+    /// `uint64_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "uint64_t *"))]
+    U64Ptr,
+    /// `int8_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "int8_t *"))]
+    I8Ptr,
+    /// `int16_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "int16_t *"))]
+    I16Ptr,
+    /// `int32_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "int32_t *"))]
+    I32Ptr,
+    /// `int64_t *`
+    #[cfg_attr(feature = "serde", serde(rename = "int64_t *"))]
+    I64Ptr,
+}
+
+impl DataType {
+    /// Parse a string into an [`DataType`] value, returning [`Option::None`] if the string is not valid.
+    pub fn parse_str(s: &str) -> Option<Self> {
+        use self::DataType::*;
+        match s {
+            "uint8_t" => Some(U8),
+            "uint16_t" => Some(U16),
+            "uint32_t" => Some(U32),
+            "uint64_t" => Some(U64),
+            "int8_t" => Some(I8),
+            "int16_t" => Some(I16),
+            "int32_t" => Some(I32),
+            "int64_t" => Some(I64),
+            "uint8_t *" => Some(U8Ptr),
+            "uint16_t *" => Some(U16Ptr),
+            "uint32_t *" => Some(U32Ptr),
+            "uint64_t *" => Some(U64Ptr),
+            "int8_t *" => Some(I8Ptr),
+            "int16_t *" => Some(I16Ptr),
+            "int32_t *" => Some(I32Ptr),
+            "int64_t *" => Some(I64Ptr),
+            _ => None,
+        }
+    }
+
+    /// Convert this [`DataType`] into a static string.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::U8 => "uint8_t",
+            Self::U16 => "uint16_t",
+            Self::U32 => "uint32_t",
+            Self::U64 => "uint64_t",
+            Self::I8 => "int8_t",
+            Self::I16 => "int16_t",
+            Self::I32 => "int32_t",
+            Self::I64 => "int64_t",
+            Self::U8Ptr => "uint8_t *",
+            Self::U16Ptr => "uint16_t *",
+            Self::U32Ptr => "uint32_t *",
+            Self::U64Ptr => "uint64_t *",
+            Self::I8Ptr => "int8_t *",
+            Self::I16Ptr => "int16_t *",
+            Self::I32Ptr => "int32_t *",
+            Self::I64Ptr => "int64_t *",
+        }
+    }
+}
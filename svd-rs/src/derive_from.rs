@@ -80,13 +80,7 @@ impl DeriveFrom for RegisterInfo {
 
 impl DeriveFrom for RegisterProperties {
     fn derive_from(&self, other: &Self) -> Self {
-        let mut derived = *self;
-        derived.size = derived.size.or(other.size);
-        derived.access = derived.access.or(other.access);
-        derived.protection = derived.protection.or(other.protection);
-        derived.reset_value = derived.reset_value.or(other.reset_value);
-        derived.reset_mask = derived.reset_mask.or(other.reset_mask);
-        derived
+        self.inherit_from(other)
     }
 }
 
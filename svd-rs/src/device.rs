@@ -1,7 +1,15 @@
+use super::prelude::*;
 use super::{
-    BuildError, Cpu, Description, EmptyToNone, Name, Peripheral, RegisterProperties, SvdError,
-    ValidateLevel,
+    array::matches_name,
+    peripheral::{self, base_addresses},
+    registercluster::{
+        count_registers, get_register_properties, join_path, sort_children, walk_registers,
+    },
+    Access, BitRangeType, BuildError, Cpu, Description, EmptyToNone, EnumeratedValues, Field, Name,
+    Peripheral, PeripheralInfo, Register, RegisterCluster, RegisterInfo, RegisterProperties,
+    SvdError, Usage, ValidateLevel,
 };
+use alloc::collections::BTreeMap;
 
 /// Errors for [`Device::validate`]
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
@@ -9,6 +17,30 @@ pub enum Error {
     /// Device has no peripherals
     #[error("Device must contain at least one peripheral")]
     EmptyDevice,
+    /// Peripheral is not in the device
+    #[error("Peripheral `{0}` not found")]
+    PeripheralNotFound(String),
+    /// Peripheral is the base of other peripherals
+    #[error("Peripheral `{0}` is derived by {1:?}")]
+    DerivedPeripheral(String, Vec<String>),
+    /// Derived peripheral redefines a register of the base peripheral incompatibly
+    #[error("Peripheral `{0}` redefines register `{1}` of `{2}` with different {3}")]
+    IncompatibleOverride(String, String, String, &'static str),
+    /// Enumerated values with the same `headerEnumName` and usage have different values
+    #[error("Header enum `{0}` has different values in `{1}` and `{2}`")]
+    ConflictingHeaderEnum(String, String, String),
+    /// Peripherals derive from each other in a loop
+    #[error("Peripherals derive from each other in a cycle: {}", .0.join(" -> "))]
+    DeriveCycle(Vec<String>),
+    /// Differently named interrupts have the same number
+    #[error("Interrupts `{0}` and `{1}` have the same value {2}")]
+    DuplicateInterruptValue(String, String, u32),
+    /// Address blocks of two peripherals overlap
+    #[error("Peripheral `{0}` at 0x{1:x} overlaps peripheral `{2}` at 0x{3:x}")]
+    OverlappingPeripherals(String, u64, String, u64),
+    /// Several peripherals have the same name
+    #[error("Peripheral `{0}` is defined more than once")]
+    DuplicatePeripheral(String),
 }
 
 /// The top element in a SVD file. Describes information specific to a device.
@@ -91,6 +123,13 @@ pub struct Device {
     /// Group to define peripherals
     pub peripherals: Vec<Peripheral>,
 
+    /// Raw XML of the `<vendorExtensions>` element, kept as is
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub vendor_extensions: Option<String>,
+
     /// Specify the underlying XML schema to which the CMSIS-SVD schema is compliant.
     #[cfg_attr(feature = "serde", serde(skip, default = "default_xmlns_xs"))]
     pub xmlns_xs: String,
@@ -120,6 +159,39 @@ fn default_schema_version() -> String {
     "1.1".into()
 }
 
+/// A register of the flattened register map of a [`Device`], see [`Device::register_table`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RegisterRow {
+    /// Absolute address of the register
+    pub address: u64,
+    /// Name of the peripheral
+    pub peripheral: String,
+    /// Name of the register, prefixed with the names of the enclosing clusters separated by `.`
+    pub name: String,
+    /// Bit-width of the register
+    pub size: Option<u32>,
+    /// Access rights of the register
+    pub access: Option<Access>,
+    /// Register value at reset
+    pub reset_value: Option<u64>,
+    /// Description of the register
+    pub description: Option<String>,
+}
+
+/// Iterator over all registers of a [`Device`], see [`Device::reg_iter`]
+pub struct RegIter<'a> {
+    registers: alloc::vec::IntoIter<(u64, String, &'a RegisterInfo)>,
+}
+
+impl<'a> Iterator for RegIter<'a> {
+    type Item = (u64, String, &'a RegisterInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.registers.next()
+    }
+}
+
 /// Builder for [`Device`]
 #[derive(Clone, Debug, Default)]
 pub struct DeviceBuilder {
@@ -137,6 +209,7 @@ pub struct DeviceBuilder {
     width: Option<u32>,
     default_register_properties: RegisterProperties,
     peripherals: Option<Vec<Peripheral>>,
+    vendor_extensions: Option<String>,
     xmlns_xs: Option<String>,
     no_namespace_schema_location: Option<String>,
     schema_version: Option<String>,
@@ -159,6 +232,7 @@ impl From<Device> for DeviceBuilder {
             width: Some(d.width),
             default_register_properties: d.default_register_properties,
             peripherals: Some(d.peripherals),
+            vendor_extensions: d.vendor_extensions,
             xmlns_xs: Some(d.xmlns_xs),
             no_namespace_schema_location: Some(d.no_namespace_schema_location),
             schema_version: Some(d.schema_version),
@@ -237,6 +311,11 @@ impl DeviceBuilder {
         self.peripherals = Some(value);
         self
     }
+    /// Set the raw XML of the `<vendorExtensions>` element of the device.
+    pub fn vendor_extensions(mut self, value: Option<String>) -> Self {
+        self.vendor_extensions = value;
+        self
+    }
     /// Set the xmlns_xs version of the device.
     pub fn xmlns_xs(mut self, value: String) -> Self {
         self.xmlns_xs = Some(value);
@@ -298,6 +377,7 @@ impl DeviceBuilder {
             peripherals: self
                 .peripherals
                 .ok_or_else(|| BuildError::Uninitialized("peripherals".to_string()))?,
+            vendor_extensions: self.vendor_extensions,
             xmlns_xs: self.xmlns_xs.unwrap_or_else(default_xmlns_xs),
             no_namespace_schema_location: self
                 .no_namespace_schema_location
@@ -361,6 +441,9 @@ impl Device {
         if let Some(peripherals) = builder.peripherals {
             self.peripherals = peripherals;
         }
+        if builder.vendor_extensions.is_some() {
+            self.vendor_extensions = builder.vendor_extensions.empty_to_none();
+        }
         if let Some(xmlns_xs) = builder.xmlns_xs {
             self.xmlns_xs = xmlns_xs;
         }
@@ -375,6 +458,13 @@ impl Device {
 
     /// Validate the [`Device`]
     pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_element(lvl)?;
+        match self.check_peripherals(lvl).into_iter().next() {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+    fn validate_element(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
         if !lvl.is_disabled() {
             // TODO
             if self.peripherals.is_empty() {
@@ -383,16 +473,281 @@ impl Device {
         }
         Ok(())
     }
-    /// Validate the [`Device`] recursively
+    /// Check peripherals against each other, returning all errors
+    fn check_peripherals(&self, lvl: ValidateLevel) -> Vec<Error> {
+        let mut errors = Vec::new();
+        if lvl.is_strict() {
+            self.check_overrides(&mut errors);
+            self.check_header_enums(&mut errors);
+            self.check_interrupts(&mut errors);
+            self.check_peripheral_overlaps(&mut errors);
+            for name in super::duplicate_names(self.peripherals.iter().map(|p| p.name.as_str())) {
+                errors.push(Error::DuplicatePeripheral(name.into()));
+            }
+        }
+        errors
+    }
+    /// Check that registers of derived peripherals with the same name as in the base peripheral
+    /// have the same offset and size
+    fn check_overrides(&self, errors: &mut Vec<Error>) {
+        for p in &self.peripherals {
+            let base = match p
+                .derived_from
+                .as_deref()
+                .and_then(|d| self.get_peripheral(d))
+            {
+                Some(base) => base,
+                None => continue,
+            };
+            for r in p.registers() {
+                let br = match base.get_register(&r.name) {
+                    Some(br) => br,
+                    None => continue,
+                };
+                let mismatch = if r.address_offset != br.address_offset {
+                    "addressOffset"
+                } else if r.properties.size.is_some()
+                    && br.properties.size.is_some()
+                    && r.properties.size != br.properties.size
+                {
+                    "size"
+                } else {
+                    continue;
+                };
+                errors.push(Error::IncompatibleOverride(
+                    p.name.clone(),
+                    r.name.clone(),
+                    base.name.clone(),
+                    mismatch,
+                ));
+            }
+        }
+    }
+    /// Check that enumerated values sharing a `headerEnumName` for the same usage are equal
+    fn check_header_enums(&self, errors: &mut Vec<Error>) {
+        let mut seen: Vec<(&str, Usage, &EnumeratedValues, String)> = Vec::new();
+        for p in &self.peripherals {
+            for r in p.all_registers() {
+                for f in r.fields() {
+                    for evs in &f.enumerated_values {
+                        let (name, usage) = match (&evs.header_enum_name, evs.usage()) {
+                            (Some(name), Some(usage)) => (name.as_str(), usage),
+                            _ => continue,
+                        };
+                        let path = format!("{}.{}.{}", p.name, r.name, f.name);
+                        match seen.iter().find(|s| s.0 == name && s.1 == usage) {
+                            Some(s) if !s.2.semantically_eq(evs) => {
+                                errors.push(Error::ConflictingHeaderEnum(
+                                    name.into(),
+                                    s.3.clone(),
+                                    path,
+                                ));
+                            }
+                            Some(_) => {}
+                            None => seen.push((name, usage, evs, path)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// Check that interrupts with the same value have the same name.
+    ///
+    /// Interrupts declared again with the same name and value,
+    /// e.g. in derived peripherals, are the same interrupt.
+    fn check_interrupts(&self, errors: &mut Vec<Error>) {
+        let mut seen: BTreeMap<u32, &str> = BTreeMap::new();
+        for i in self.peripherals.iter().flat_map(|p| p.interrupt.iter()) {
+            match seen.get(&i.value) {
+                Some(&name) if name != i.name.as_str() => {
+                    errors.push(Error::DuplicateInterruptValue(
+                        name.into(),
+                        i.name.clone(),
+                        i.value,
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(i.value, i.name.as_str());
+                }
+            }
+        }
+    }
+    /// Check that address blocks of different peripherals don't overlap,
+    /// unless one is the `alternatePeripheral` of the other.
+    ///
+    /// Derived peripherals without address blocks use the ones of their base.
+    fn check_peripheral_overlaps(&self, errors: &mut Vec<Error>) {
+        let mut ranges = Vec::new();
+        for p in &self.peripherals {
+            let blocks = p.address_block.as_ref().or_else(|| {
+                p.derived_from
+                    .as_deref()
+                    .and_then(|d| self.get_peripheral(d))
+                    .and_then(|base| base.address_block.as_ref())
+            });
+            let bases = match p {
+                Peripheral::Single(info) => vec![info.base_address],
+                Peripheral::Array(info, dim) => base_addresses(info, dim).collect(),
+            };
+            for base in bases {
+                for ab in blocks.into_iter().flatten().filter(|ab| ab.size > 0) {
+                    let start = base + ab.offset as u64;
+                    ranges.push((start, start + ab.size as u64, base, &**p));
+                }
+            }
+        }
+        ranges.sort_by_key(|r| r.0);
+        for (i, &(_, end, base, p)) in ranges.iter().enumerate() {
+            for &(_, _, other_base, other) in ranges[i + 1..].iter().take_while(|r| r.0 < end) {
+                let alternate = p.alternate_peripheral.as_deref() == Some(other.name.as_str())
+                    || other.alternate_peripheral.as_deref() == Some(p.name.as_str());
+                if p.name != other.name && !alternate {
+                    errors.push(Error::OverlappingPeripherals(
+                        p.name.clone(),
+                        base,
+                        other.name.clone(),
+                        other_base,
+                    ));
+                }
+            }
+        }
+    }
+    /// Validate the [`Device`] recursively, returning the first error of [`Device::validate_collect`]
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        super::first_error(self.validate_collect(lvl))
+    }
+
+    /// Validate the [`Device`] recursively, collecting all errors instead of stopping at the first one.
+    ///
+    /// On [`ValidateLevel::Strict`] every overlap of registers and peripherals, register outside
+    /// of address blocks, overlap of fields, duplicate name and interrupt sharing a value is
+    /// reported. Errors of nested elements have their dotted path, see [`SvdError::path`].
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
         if let Some(cpu) = self.cpu.as_ref() {
-            cpu.validate(lvl)?;
+            errors.extend(cpu.validate(lvl).err());
         }
-        self.default_register_properties.validate(lvl)?;
+        errors.extend(self.default_register_properties.validate(lvl).err());
         for p in &self.peripherals {
-            p.validate_all(lvl)?;
+            errors.extend(p.validate_collect(lvl).into_iter().map(|e| e.at(&p.name)));
         }
-        self.validate(lvl)
+        if let Some(num) = self.cpu.as_ref().and_then(|cpu| cpu.device_num_interrupts) {
+            for i in self.peripherals.iter().flat_map(|p| p.interrupt.iter()) {
+                errors.extend(i.validate_max(num.saturating_sub(1), lvl).err());
+            }
+        }
+        errors.extend(self.validate_element(lvl).err());
+        errors.extend(self.check_peripherals(lvl).into_iter().map(SvdError::from));
+        errors
+    }
+
+    /// Validate the whole [`Device`] again, e.g. strictly after parsing it leniently.
+    ///
+    /// Same as [`Device::validate_all`].
+    pub fn revalidate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_all(lvl)
+    }
+
+    /// Check if the device describes TrustZone security: SAU regions or `protection` of
+    /// registers, clusters, peripherals, address blocks or the device itself
+    pub fn has_trustzone(&self) -> bool {
+        fn in_children(children: &[RegisterCluster]) -> bool {
+            children.iter().any(|rc| match rc {
+                RegisterCluster::Register(r) => r.properties.protection.is_some(),
+                RegisterCluster::Cluster(c) => {
+                    c.default_register_properties.protection.is_some() || in_children(&c.children)
+                }
+            })
+        }
+        self.cpu.as_ref().map_or(false, |cpu| {
+            cpu.sau_num_regions.unwrap_or(0) > 0
+                || cpu
+                    .sau_regions_config
+                    .as_ref()
+                    .map_or(false, |sau| !sau.regions.is_empty())
+        }) || self.default_register_properties.protection.is_some()
+            || self.peripherals.iter().any(|p| {
+                p.default_register_properties.protection.is_some()
+                    || p.address_block
+                        .iter()
+                        .flatten()
+                        .any(|ab| ab.protection.is_some())
+                    || p.registers.as_deref().map_or(false, in_children)
+            })
+    }
+
+    /// Number of peripherals, arrays count once
+    pub fn peripheral_count(&self) -> usize {
+        self.peripherals.len()
+    }
+
+    /// Number of registers, arrays of peripherals, clusters and registers
+    /// count as their number of elements if `expand_arrays`
+    pub fn register_count(&self, expand_arrays: bool) -> usize {
+        self.count(expand_arrays, &|_| 1)
+    }
+
+    /// Number of fields, arrays of peripherals, clusters, registers and fields
+    /// count as their number of elements if `expand_arrays`
+    pub fn field_count(&self, expand_arrays: bool) -> usize {
+        self.count(expand_arrays, &|r| {
+            r.fields()
+                .map(|f| match f {
+                    Field::Array(_, dim) if expand_arrays => dim.dim as usize,
+                    _ => 1,
+                })
+                .sum()
+        })
+    }
+
+    fn count(&self, expand_arrays: bool, count: &impl Fn(&Register) -> usize) -> usize {
+        self.peripherals
+            .iter()
+            .map(|p| {
+                let times = match p {
+                    Peripheral::Array(_, dim) if expand_arrays => dim.dim as usize,
+                    _ => 1,
+                };
+                times
+                    * p.registers
+                        .as_deref()
+                        .map_or(0, |regs| count_registers(regs, expand_arrays, count))
+            })
+            .sum()
+    }
+
+    /// Use `bitOffset`/`bitWidth` as bit range format of all fields
+    pub fn normalize_field_ranges(&mut self) {
+        for p in &mut self.peripherals {
+            for r in p.all_registers_mut() {
+                for f in r.fields_mut() {
+                    f.bit_range.range_type = BitRangeType::OffsetWidth;
+                }
+            }
+        }
+    }
+
+    /// Replace arrays of peripherals, clusters, registers and fields with their single elements.
+    ///
+    /// Names are substituted from `dimIndex` and address and bit offsets computed from `dimIncrement`.
+    /// `derivedFrom` is not resolved, use `svd_parser::expand` for this.
+    pub fn expand_arrays(&mut self) {
+        for p in core::mem::take(&mut self.peripherals) {
+            let mut infos = p.expanded();
+            for info in &mut infos {
+                info.expand_arrays();
+            }
+            self.peripherals
+                .extend(infos.into_iter().map(PeripheralInfo::single));
+        }
+    }
+
+    /// Get peripherals sorted by `baseAddress`, peripherals at the same address keep their order
+    pub fn peripherals_by_address(&self) -> Vec<&Peripheral> {
+        let mut peripherals: Vec<_> = self.peripherals.iter().collect();
+        peripherals.sort_by_key(|p| p.base_address);
+        peripherals
     }
 
     /// Get peripheral by name
@@ -400,10 +755,427 @@ impl Device {
         self.peripherals.iter().find(|f| f.name == name)
     }
 
+    /// Get register by `PERIPH.REG` path.
+    ///
+    /// Registers inside of clusters are found by `PERIPH.CLUSTER.REG` paths,
+    /// arrays of peripherals, clusters and registers also by element names.
+    /// `derivedFrom` is not followed.
+    pub fn find_register(&self, path: &str) -> Option<&Register> {
+        let (peripheral, register) = path.split_once('.')?;
+        self.find_peripheral(peripheral)?.get_register(register)
+    }
+
+    /// Get the properties of the register found by `PERIPH.REG` path, like
+    /// [`find_register`](Self::find_register), with `size`, `access`, `protection`,
+    /// `resetValue` and `resetMask` inherited from the enclosing clusters, the peripheral
+    /// and the device if the register doesn't specify them.
+    ///
+    /// Unlike [`find_register`](Self::find_register), registers of derived peripherals
+    /// are taken from the base peripheral if the derived one has none.
+    pub fn effective_properties(&self, path: &str) -> Option<RegisterProperties> {
+        let (peripheral, register) = path.split_once('.')?;
+        let (registers, props) = self.peripheral_registers(self.find_peripheral(peripheral)?);
+        get_register_properties(registers?, register, &props)
+    }
+
+    /// Get peripheral by name, arrays also by element names
+    fn find_peripheral(&self, name: &str) -> Option<&Peripheral> {
+        self.get_peripheral(name)
+            .or_else(|| self.peripherals.iter().find(|p| matches_name(p, name)))
+    }
+
+    /// Get field by `PERIPH.REG.FIELD` path, see [`find_register`](Self::find_register)
+    pub fn find_field(&self, path: &str) -> Option<&Field> {
+        let (register, field) = path.rsplit_once('.')?;
+        self.find_register(register)?.get_field(field)
+    }
+
     /// Get mutable peripheral by name
     pub fn get_mut_peripheral(&mut self, name: &str) -> Option<&mut Peripheral> {
         self.peripherals.iter_mut().find(|f| f.name == name)
     }
+
+    /// Merges the peripherals of `other` into this device.
+    ///
+    /// Peripherals with the same name are merged with [`Peripheral::merge`],
+    /// new peripherals are appended. Other values of `other` are ignored.
+    pub fn merge(&mut self, other: &Device, lvl: ValidateLevel) -> Result<(), SvdError> {
+        for p in &other.peripherals {
+            match self.get_mut_peripheral(&p.name) {
+                Some(existing) => existing.merge(p, lvl)?,
+                None => self.peripherals.push(p.clone()),
+            }
+        }
+        self.validate(lvl)
+    }
+
+    /// Remove peripheral by name.
+    ///
+    /// Fails if other peripherals are derived from it.
+    pub fn remove_peripheral(&mut self, name: &str) -> Result<Peripheral, SvdError> {
+        let derivers = self.derivers_of(name);
+        if !derivers.is_empty() {
+            return Err(Error::DerivedPeripheral(name.to_string(), derivers).into());
+        }
+        self.force_remove_peripheral(name).map(|(p, _)| p)
+    }
+
+    /// Remove peripheral by name even if other peripherals are derived from it.
+    ///
+    /// Also returns names of peripherals whose `derivedFrom` is now dangling.
+    pub fn force_remove_peripheral(
+        &mut self,
+        name: &str,
+    ) -> Result<(Peripheral, Vec<String>), SvdError> {
+        let idx = self
+            .peripherals
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| Error::PeripheralNotFound(name.to_string()))?;
+        let derivers = self.derivers_of(name);
+        Ok((self.peripherals.remove(idx), derivers))
+    }
+
+    fn derivers_of(&self, name: &str) -> Vec<String> {
+        self.peripherals
+            .iter()
+            .filter(|p| p.derived_from.as_deref() == Some(name))
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    /// Returns iterator over peripherals with `derivedFrom` applied.
+    ///
+    /// Derive chains are followed, each base peripheral is resolved only once.
+    /// Peripherals deriving from missing peripherals are returned unchanged.
+    #[cfg(feature = "derive-from")]
+    pub fn resolved_peripherals(&self) -> impl Iterator<Item = Peripheral> + '_ {
+        let mut cache = BTreeMap::new();
+        self.peripherals
+            .iter()
+            .map(move |p| self.resolve_peripheral(p, &mut cache, 0))
+    }
+
+    #[cfg(feature = "derive-from")]
+    fn resolve_peripheral<'a>(
+        &'a self,
+        p: &'a Peripheral,
+        cache: &mut BTreeMap<&'a str, Peripheral>,
+        depth: usize,
+    ) -> Peripheral {
+        use super::DeriveFrom;
+
+        let name = match p.derived_from.as_deref() {
+            // derive cycles can't be resolved
+            Some(name) if depth < self.peripherals.len() => name,
+            _ => return p.clone(),
+        };
+        let base = match cache.get(name) {
+            Some(base) => base.clone(),
+            None => match self.get_peripheral(name) {
+                Some(base) => {
+                    let base = self.resolve_peripheral(base, cache, depth + 1);
+                    cache.insert(name, base.clone());
+                    base
+                }
+                None => return p.clone(),
+            },
+        };
+        let mut derived = p.derive_from(&base);
+        derived.derived_from = None;
+        derived
+    }
+
+    /// Returns peripheral by name with `derivedFrom` fully applied.
+    ///
+    /// Registers, interrupts and address blocks are copied from the base peripherals,
+    /// registers of the derived peripheral replace inherited registers with the same name.
+    /// `name`, `baseAddress` and explicitly set values of the peripheral are kept.
+    #[cfg(feature = "derive-from")]
+    pub fn expand_peripheral(&self, name: &str) -> Result<Peripheral, SvdError> {
+        use super::DeriveFrom;
+
+        let mut derived = self
+            .get_peripheral(name)
+            .ok_or_else(|| Error::PeripheralNotFound(name.to_string()))?
+            .clone();
+        let mut chain = vec![derived.name.clone()];
+        while let Some(base_name) = derived.derived_from.take() {
+            if chain.contains(&base_name) {
+                chain.push(base_name);
+                return Err(Error::DeriveCycle(chain).into());
+            }
+            let base = self
+                .get_peripheral(&base_name)
+                .ok_or_else(|| Error::PeripheralNotFound(base_name.clone()))?;
+            let registers = match (derived.registers.take(), &base.registers) {
+                (Some(own), Some(inherited)) => Some(override_registers(inherited, own)),
+                (own, inherited) => own.or_else(|| inherited.clone()),
+            };
+            let address_block = derived
+                .address_block
+                .take()
+                .or_else(|| base.address_block.clone());
+            derived = derived.derive_from(base);
+            derived.registers = registers;
+            derived.address_block = address_block;
+            derived.derived_from = base.derived_from.clone();
+            chain.push(base_name);
+        }
+        Ok(derived)
+    }
+
+    /// Returns the registers of all peripherals with arrays expanded and
+    /// properties inherited from parent elements, sorted by address
+    pub fn register_table(&self) -> Vec<RegisterRow> {
+        let mut rows = Vec::new();
+        self.walk_registers(&mut |p, path, address, r, _, props| {
+            rows.push(RegisterRow {
+                address,
+                peripheral: p.name.clone(),
+                name: join_path(path, &r.name),
+                size: props.size,
+                access: props.access,
+                reset_value: props.reset_value,
+                description: r.description.clone(),
+            });
+        });
+        rows.sort_by_key(|r| r.address);
+        rows
+    }
+
+    /// Counts fields of all registers with each effective access.
+    ///
+    /// Access is inherited from registers and their parents,
+    /// fields without access anywhere count as `read-write`.
+    /// Arrays are expanded.
+    pub fn access_summary(&self) -> BTreeMap<Access, usize> {
+        let mut summary = BTreeMap::new();
+        self.walk_registers(&mut |_, _, _, r, _, props| {
+            for f in r.fields() {
+                let access = f.effective_access(props);
+                let count = match f {
+                    Field::Single(_) => 1,
+                    Field::Array(_, dim) => dim.dim as usize,
+                };
+                *summary.entry(access).or_insert(0) += count;
+            }
+        });
+        summary
+    }
+
+    /// Calls `f` for each register of each peripheral with arrays expanded,
+    /// with the path of parent clusters, absolute address, the register as declared
+    /// and inherited properties, see [`registercluster::walk_registers`]
+    fn walk_registers<'a>(
+        &'a self,
+        f: &mut impl FnMut(
+            &PeripheralInfo,
+            &[String],
+            u64,
+            &RegisterInfo,
+            &'a RegisterInfo,
+            &RegisterProperties,
+        ),
+    ) {
+        for p in &self.peripherals {
+            let (regs, props) = self.peripheral_registers(p);
+            let regs = match regs {
+                Some(regs) => regs,
+                None => continue,
+            };
+            let mut walk = |p: &PeripheralInfo| {
+                walk_registers(
+                    regs,
+                    p.base_address,
+                    &props,
+                    &mut Vec::new(),
+                    &mut |path, address, r, declared, props| {
+                        f(p, path, address, r, declared, props)
+                    },
+                )
+            };
+            match p {
+                Peripheral::Single(info) => walk(info),
+                Peripheral::Array(info, dim) => {
+                    for info in peripheral::expand(info, dim) {
+                        walk(&info);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Iterates over all registers with their absolute address and
+    /// `PERIPHERAL.CLUSTER.REGISTER` path, in the order of the device.
+    ///
+    /// Arrays of peripherals, clusters and registers are expanded and the index is
+    /// substituted in the path. Elements of register arrays refer to the [`RegisterInfo`]
+    /// of the whole array. Derived peripherals without own registers use the registers
+    /// of their base.
+    pub fn reg_iter(&self) -> RegIter<'_> {
+        let mut registers = Vec::new();
+        self.walk_registers(&mut |p, path, address, r, declared, _| {
+            let name = format!("{}.{}", p.name, join_path(path, &r.name));
+            registers.push((address, name, declared));
+        });
+        RegIter {
+            registers: registers.into_iter(),
+        }
+    }
+
+    /// Hash of the device which doesn't depend on the order of peripherals, registers, clusters,
+    /// fields, enumerated values and interrupts, on whitespace in descriptions, on comments
+    /// or on the [literals](crate::Literals) of numbers.
+    ///
+    /// The hash is computed from the `Debug` output of the device, so it is only stable within
+    /// one build: don't persist it or compare it between other versions of this crate or Rust.
+    pub fn fingerprint(&self) -> u64 {
+        let mut device = self.clone();
+        squash_whitespace(&mut device.description);
+        device.peripherals.sort_by(|a, b| a.name.cmp(&b.name));
+        for p in &mut device.peripherals {
+            p.description.iter_mut().for_each(squash_whitespace);
+            p.literals.clear();
+            p.comments.clear();
+            p.interrupt
+                .sort_by(|a, b| (a.value, &a.name).cmp(&(b.value, &b.name)));
+            for i in &mut p.interrupt {
+                i.description.iter_mut().for_each(squash_whitespace);
+            }
+            if let Some(regs) = p.registers.as_mut() {
+                sort_children(regs, &|rc| (rc.address_offset(), rc.name().clone()));
+                canonicalize_children(regs);
+            }
+        }
+        // FNV-1a
+        format!("{device:?}")
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+                (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Returns `PERIPHERAL.CLUSTER.REGISTER` paths of all registers with arrays expanded
+    /// and their size in bits, sorted by address.
+    ///
+    /// Registers without size in the inheritance chain get the device bus `width`.
+    pub fn registers_with_size(&self) -> impl Iterator<Item = (String, u32)> {
+        let width = self.width;
+        self.register_table().into_iter().map(move |r| {
+            (
+                format!("{}.{}", r.peripheral, r.name),
+                r.size.unwrap_or(width),
+            )
+        })
+    }
+
+    /// Returns the registers of the peripheral, following `derivedFrom` if needed,
+    /// and the register properties the peripheral provides for them
+    pub(crate) fn peripheral_registers<'a>(
+        &'a self,
+        p: &'a PeripheralInfo,
+    ) -> (Option<&'a [RegisterCluster]>, RegisterProperties) {
+        let mut props = p.default_register_properties;
+        let mut current = p;
+        // bounded by the number of peripherals to not get stuck on derive cycles
+        for _ in 0..=self.peripherals.len() {
+            if current.registers.is_some() {
+                break;
+            }
+            match current
+                .derived_from
+                .as_ref()
+                .and_then(|d| self.get_peripheral(d))
+            {
+                Some(parent) => {
+                    props = props.inherit_from(&parent.default_register_properties);
+                    current = parent;
+                }
+                None => break,
+            }
+        }
+        (
+            current.registers.as_deref(),
+            props.inherit_from(&self.default_register_properties),
+        )
+    }
+
+    /// Deserialize a [`Device`] from JSON and validate it
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, SvdError> {
+        let device: Self = serde_json::from_str(json).map_err(|e| SvdError::Json(e.to_string()))?;
+        device.validate_all(ValidateLevel::default())?;
+        Ok(device)
+    }
+
+    /// Serialize the [`Device`] to pretty-printed JSON
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Device is always serializable")
+    }
+
+    /// Get the root register properties, inherited by all peripherals of the device
+    pub fn default_register_properties(&self) -> &RegisterProperties {
+        &self.default_register_properties
+    }
+}
+
+fn squash_whitespace(description: &mut String) {
+    *description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+}
+
+/// Sorts fields and enumerated values and squashes whitespace in descriptions of `children`
+fn canonicalize_children(children: &mut [RegisterCluster]) {
+    for rc in children {
+        match rc {
+            RegisterCluster::Cluster(c) => {
+                c.description.iter_mut().for_each(squash_whitespace);
+                c.literals.clear();
+                c.comments.clear();
+                canonicalize_children(&mut c.children);
+            }
+            RegisterCluster::Register(r) => {
+                r.description.iter_mut().for_each(squash_whitespace);
+                r.literals.clear();
+                r.comments.clear();
+                let fields = match r.fields.as_mut() {
+                    Some(fields) => fields,
+                    None => continue,
+                };
+                fields.sort_by(|a, b| (a.lsb(), &a.name).cmp(&(b.lsb(), &b.name)));
+                for f in fields {
+                    f.description.iter_mut().for_each(squash_whitespace);
+                    f.comments.clear();
+                    for evs in &mut f.enumerated_values {
+                        evs.values
+                            .sort_by(|a, b| (a.value, &a.name).cmp(&(b.value, &b.name)));
+                        for ev in &mut evs.values {
+                            ev.description.iter_mut().for_each(squash_whitespace);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces registers and clusters of `inherited` with the ones of `own` with the same name,
+/// the rest of `own` is appended
+#[cfg(feature = "derive-from")]
+fn override_registers(
+    inherited: &[RegisterCluster],
+    mut own: Vec<RegisterCluster>,
+) -> Vec<RegisterCluster> {
+    let mut registers: Vec<_> = inherited
+        .iter()
+        .map(|rc| match own.iter().position(|o| o.name() == rc.name()) {
+            Some(i) => own.remove(i),
+            None => rc.clone(),
+        })
+        .collect();
+    registers.extend(own);
+    registers
 }
 
 impl Name for Device {
@@ -1,6 +1,7 @@
+use super::prelude::*;
 use super::{BuildError, EmptyToNone, EnumeratedValue, SvdError, ValidateLevel};
-use std::borrow::Cow;
-use std::ops::RangeInclusive;
+use alloc::borrow::Cow;
+use core::ops::RangeInclusive;
 
 /// Defines arrays and lists.
 #[cfg_attr(
@@ -68,6 +69,17 @@ pub struct DimArrayIndex {
     pub values: Vec<EnumeratedValue>,
 }
 
+/// Errors for [`DimElement::validate`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// `dimArrayIndex` has more values than there are array elements
+    #[error("dimArrayIndex has {0} values, but array has only {1} elements")]
+    TooManyArrayIndexValues(usize, u32),
+    /// Number of `dimIndex` entries differs from `dim`
+    #[error("dimIndex has {0} indexes, but dim is {1}")]
+    IncorrectIndexCount(usize, u32),
+}
+
 /// Builder for [`DimElement`]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DimElementBuilder {
@@ -140,7 +152,11 @@ impl DimElement {
         DimElementBuilder::default()
     }
 
-    /// Get array of indexes from string
+    /// Get array of indexes from string.
+    ///
+    /// Accepts inclusive ranges of numbers or letters like `3-6` or `A-D`
+    /// and comma separated lists like `A,B,C`. Returns `None` for reversed ranges
+    /// and empty indexes.
     pub fn parse_indexes(text: &str) -> Option<Vec<String>> {
         (if text.contains('-') {
             let (start, end) = text.split_once('-')?;
@@ -162,7 +178,7 @@ impl DimElement {
         } else {
             Some(text.split(',').map(|s| s.to_string()).collect())
         })
-        .filter(|v| !v.is_empty())
+        .filter(|v| !v.is_empty() && v.iter().all(|i| !i.is_empty()))
     }
     /// Try to represent [`DimElement`] as range of integer indexes
     pub fn indexes_as_range(&self) -> Option<RangeInclusive<u32>> {
@@ -213,21 +229,45 @@ impl DimElement {
     }
     /// Validate the [`DimElement`].
     ///
-    /// # Notes
-    ///
-    /// This doesn't do anything.
-    pub fn validate(&self, _lvl: ValidateLevel) -> Result<(), SvdError> {
-        // TODO
+    /// Unless validation is disabled, checks that `dimIndex` has exactly `dim` indexes
+    /// and that `dimArrayIndex` has no more enumerated values than `dim`.
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if !lvl.is_disabled() {
+            // `indexes` relies on this
+            if let Some(index) = self.dim_index.as_ref() {
+                if index.len() != self.dim as usize {
+                    return Err(Error::IncorrectIndexCount(index.len(), self.dim).into());
+                }
+            }
+            if let Some(dai) = self.dim_array_index.as_ref() {
+                if dai.values.len() > self.dim as usize {
+                    return Err(Error::TooManyArrayIndexValues(dai.values.len(), self.dim).into());
+                }
+            }
+        }
         Ok(())
     }
     /// Get the indexes of the array or list.
-    pub fn indexes(&self) -> Indexes {
+    pub fn indexes(&self) -> Indexes<'_> {
         Indexes {
             i: 0,
             dim: self.dim,
             dim_index: &self.dim_index,
         }
     }
+    /// Substitute the placeholder in `text` with `index`.
+    ///
+    /// `[%s]` is replaced as a whole, so `DR[%s]` becomes `DR3`,
+    /// `%s` is replaced wherever it appears, like in `GPIO%s_CTRL`.
+    pub fn replace_placeholder(text: &str, index: &str) -> String {
+        text.replace("[%s]", index).replace("%s", index)
+    }
+    /// Get the names of the elements by substituting the placeholder in `base` with each index,
+    /// see [`replace_placeholder`](Self::replace_placeholder)
+    pub fn element_names<'a>(&'a self, base: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.indexes()
+            .map(move |i| Self::replace_placeholder(base, &i))
+    }
 }
 
 /// Indexes into a [DimElement]
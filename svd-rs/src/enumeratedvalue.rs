@@ -1,3 +1,4 @@
+use super::prelude::*;
 use super::{BuildError, Description, EmptyToNone, Name, SvdError, ValidateLevel};
 
 /// Describes a single entry in the enumeration.
@@ -22,6 +23,15 @@ pub struct EnumeratedValue {
     )]
     pub value: Option<u64>,
 
+    /// Significant bits of `value`, the rest are don't-care bits.
+    ///
+    /// Set for binary literals with `x` digits like `#10xx`, `None` if all bits matter
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub mask: Option<u64>,
+
     /// Defines the name and description for all other values that are not listed explicitly
     #[cfg_attr(
         feature = "serde",
@@ -40,8 +50,11 @@ pub enum Error {
     #[error("EnumeratedValue with `value` (passed {0:?}) should not have `is_default(True)`")]
     ValueAndDefault(Option<u64>),
     /// The value is not in range.
-    #[error("Value {0} out of range [{} - {}]", .1.start, .1.end - 1)]
+    #[error("Value {0} out of range [{start} - {end}]", start = .1.start, end = .1.end - 1)]
     OutOfRange(u64, core::ops::Range<u64>),
+    /// Don't-care bits of the value are set
+    #[error("Value {0:#b} has bits set outside of mask {1:#b}")]
+    MaskedValue(u64, u64),
 }
 
 /// Builder for [`EnumeratedValue`]
@@ -50,6 +63,7 @@ pub struct EnumeratedValueBuilder {
     name: Option<String>,
     description: Option<String>,
     value: Option<u64>,
+    mask: Option<u64>,
     is_default: Option<bool>,
 }
 
@@ -59,6 +73,7 @@ impl From<EnumeratedValue> for EnumeratedValueBuilder {
             name: Some(e.name),
             description: e.description,
             value: e.value,
+            mask: e.mask,
             is_default: e.is_default,
         }
     }
@@ -80,6 +95,11 @@ impl EnumeratedValueBuilder {
         self.value = value;
         self
     }
+    /// Set the significant bits of the value.
+    pub fn mask(mut self, value: Option<u64>) -> Self {
+        self.mask = value;
+        self
+    }
     #[allow(clippy::wrong_self_convention)]
     /// Set if the enumerated value is defaulted for non-explicit values.
     pub fn is_default(mut self, value: Option<bool>) -> Self {
@@ -94,6 +114,7 @@ impl EnumeratedValueBuilder {
                 .ok_or_else(|| BuildError::Uninitialized("name".to_string()))?,
             description: self.description.empty_to_none(),
             value: self.value,
+            mask: self.mask,
             is_default: self.is_default,
         };
         ev.validate(lvl)?;
@@ -125,6 +146,9 @@ impl EnumeratedValue {
         if builder.value.is_some() {
             self.value = builder.value;
         }
+        if builder.mask.is_some() {
+            self.mask = builder.mask;
+        }
         if builder.is_default.is_some() {
             self.is_default = builder.is_default;
         }
@@ -136,6 +160,11 @@ impl EnumeratedValue {
             if lvl.is_strict() {
                 super::check_name(&self.name, "name")?;
             }
+            if let (Some(value), Some(mask)) = (self.value, self.mask) {
+                if value & !mask != 0 {
+                    return Err(Error::MaskedValue(value, mask).into());
+                }
+            }
             match (self.value.is_some(), self.is_default()) {
                 (false, false) => Err(Error::AbsentValue.into()),
                 (true, true) if lvl.is_strict() => Err(Error::ValueAndDefault(self.value).into()),
@@ -145,7 +174,13 @@ impl EnumeratedValue {
             Ok(())
         }
     }
+    /// Checks that the value and its significant bits fit in `range`
     pub(crate) fn check_range(&self, range: &core::ops::Range<u64>) -> Result<(), SvdError> {
+        for x in self.value.iter().chain(&self.mask) {
+            if !range.contains(x) {
+                return Err(Error::OutOfRange(*x, range.clone()).into());
+            }
+        }
         Ok(())
     }
 }
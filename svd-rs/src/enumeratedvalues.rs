@@ -1,3 +1,4 @@
+use super::prelude::*;
 use super::{EmptyToNone, EnumeratedValue, SvdError, Usage, ValidateLevel};
 
 /// A map describing unsigned integers and their description and name.
@@ -12,6 +13,13 @@ pub struct EnumeratedValues {
     )]
     pub name: Option<String>,
 
+    /// Identifier for the enumeration in C headers
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub header_enum_name: Option<String>,
+
     /// Usage of the values
     #[cfg_attr(
         feature = "serde",
@@ -41,12 +49,19 @@ pub enum Error {
     /// Enum is empty
     #[error("EnumeratedValues is empty")]
     Empty,
+    /// Several values have the same name
+    #[error("Name `{0}` is used by several enumerated values")]
+    DuplicateName(String),
+    /// Several values have the same value
+    #[error("Value {0} is used by several enumerated values")]
+    DuplicateValue(u64),
 }
 
 /// Builder for [`EnumeratedValues`]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct EnumeratedValuesBuilder {
     name: Option<String>,
+    header_enum_name: Option<String>,
     usage: Option<Usage>,
     derived_from: Option<String>,
     values: Option<Vec<EnumeratedValue>>,
@@ -56,6 +71,7 @@ impl From<EnumeratedValues> for EnumeratedValuesBuilder {
     fn from(e: EnumeratedValues) -> Self {
         Self {
             name: e.name,
+            header_enum_name: e.header_enum_name,
             usage: e.usage,
             derived_from: e.derived_from,
             values: Some(e.values),
@@ -69,6 +85,11 @@ impl EnumeratedValuesBuilder {
         self.name = value;
         self
     }
+    /// Set the header_enum_name of the enumerated values
+    pub fn header_enum_name(mut self, value: Option<String>) -> Self {
+        self.header_enum_name = value;
+        self
+    }
     /// Set the usage of the enumerated values
     pub fn usage(mut self, value: Option<Usage>) -> Self {
         self.usage = value;
@@ -79,6 +100,11 @@ impl EnumeratedValuesBuilder {
         self.derived_from = value;
         self
     }
+    /// Add a value
+    pub fn value(mut self, value: EnumeratedValue) -> Self {
+        self.values.get_or_insert_with(Vec::new).push(value);
+        self
+    }
     /// Set the values
     pub fn values(mut self, value: Vec<EnumeratedValue>) -> Self {
         self.values = Some(value);
@@ -88,6 +114,7 @@ impl EnumeratedValuesBuilder {
     pub fn build(self, lvl: ValidateLevel) -> Result<EnumeratedValues, SvdError> {
         let evs = EnumeratedValues {
             name: self.name.empty_to_none(),
+            header_enum_name: self.header_enum_name.empty_to_none(),
             usage: self.usage,
             derived_from: self.derived_from,
             values: self.values.unwrap_or_default(),
@@ -114,13 +141,18 @@ impl EnumeratedValues {
         lvl: ValidateLevel,
     ) -> Result<(), SvdError> {
         if builder.derived_from.is_some() {
+            self.derived_from = builder.derived_from;
             self.name = None;
+            self.header_enum_name = None;
             self.usage = None;
             self.values = Vec::new();
         } else {
             if builder.name.is_some() {
                 self.name = builder.name.empty_to_none();
             }
+            if builder.header_enum_name.is_some() {
+                self.header_enum_name = builder.header_enum_name.empty_to_none();
+            }
             if builder.usage.is_some() {
                 self.usage = builder.usage;
             }
@@ -137,13 +169,17 @@ impl EnumeratedValues {
                 if let Some(name) = self.name.as_ref() {
                     super::check_name(name, "name")?;
                 }
+                if let Some(name) = self.header_enum_name.as_ref() {
+                    super::check_name(name, "headerEnumName")?;
+                }
+                self.check_unique()?;
             }
             if let Some(_dname) = self.derived_from.as_ref() {
                 if lvl.is_strict() {
                     super::check_derived_name(_dname, "derivedFrom")?;
                 }
                 Ok(())
-            } else if self.values.is_empty() {
+            } else if self.values.is_empty() && lvl.is_strict() {
                 Err(Error::Empty.into())
             } else {
                 Ok(())
@@ -152,6 +188,21 @@ impl EnumeratedValues {
             Ok(())
         }
     }
+    fn check_unique(&self) -> Result<(), Error> {
+        for (i, v) in self.values.iter().enumerate() {
+            for other in &self.values[..i] {
+                if other.name == v.name {
+                    return Err(Error::DuplicateName(v.name.clone()));
+                }
+                if let (Some(a), Some(b)) = (other.value, v.value) {
+                    if a == b {
+                        return Err(Error::DuplicateValue(a));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
     /// Validate the [`EnumeratedValues`] recursively.
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
         for ev in &self.values {
@@ -174,6 +225,21 @@ impl EnumeratedValues {
         }
     }
 
+    /// Check whether both blocks map the same values to the same names,
+    /// ignoring order of values and descriptions
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        fn mapping(evs: &EnumeratedValues) -> Vec<(Option<u64>, bool, &str)> {
+            let mut m: Vec<_> = evs
+                .values
+                .iter()
+                .map(|v| (v.value, v.is_default(), v.name.as_str()))
+                .collect();
+            m.sort_unstable();
+            m
+        }
+        self.values.len() == other.values.len() && mapping(self) == mapping(other)
+    }
+
     /// Get `enumeratedValue` by name
     pub fn get_value(&self, name: &str) -> Option<&EnumeratedValue> {
         self.values.iter().find(|e| e.name == name)
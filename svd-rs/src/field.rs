@@ -1,10 +1,12 @@
+use super::prelude::*;
 use super::{
-    array::{descriptions, names},
-    bitrange, Access, BitRange, BuildError, Description, DimElement, EmptyToNone, EnumeratedValues,
-    MaybeArray, ModifiedWriteValues, Name, ReadAction, SvdError, Usage, ValidateLevel,
+    array::{descriptions, merge_dim, names},
+    bitrange, Access, BitRange, BuildError, CmsisMangler, DataType, Description, Device,
+    DimElement, EmptyToNone, EnumeratedValues, MaybeArray, ModifiedWriteValues, Name, NameMangler,
+    PeripheralInfo, ReadAction, RegisterInfo, RegisterProperties, SvdError, Usage, ValidateLevel,
     WriteConstraint,
 };
-use std::ops::Deref;
+use core::ops::Deref;
 
 /// Describes a field or fields of a [register](crate::RegisterInfo).
 pub type Field = MaybeArray<FieldInfo>;
@@ -15,6 +17,18 @@ pub enum Error {
     /// The enumerated value is not recognized by svd-rs.
     #[error("You can have 0, 1 or 2 enumeratedValues with different usage")]
     IncompatibleEnumeratedValues,
+
+    /// The write-once access is in conflict with modifiedWriteValues
+    #[error("Field `{0}` with `{access}` access can't have `{mwv}` modifiedWriteValues", access = .1.as_str(), mwv = .2.as_str())]
+    WriteOnceModifiedWriteValues(String, Access, ModifiedWriteValues),
+
+    /// Merged enumerated values map the same value or name differently
+    #[error("Field `{0}` has conflicting enumerated values `{1}` and `{2}`")]
+    ConflictingEnumeratedValues(String, String, String),
+
+    /// The bits of the field are beyond the size of its register
+    #[error("Field `{0}` ends at bit {1}, beyond the register size of {2} bits")]
+    ExceedsRegisterSize(String, u32, u32),
 }
 
 /// A partition of a [register](crate::RegisterInfo)
@@ -68,6 +82,13 @@ pub struct FieldInfo {
     )]
     pub read_action: Option<ReadAction>,
 
+    /// C data type of the field in the device header
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub data_type: Option<DataType>,
+
     /// Describes the field
     #[cfg_attr(
         feature = "serde",
@@ -82,6 +103,13 @@ pub struct FieldInfo {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub derived_from: Option<String>,
+
+    /// Comments preceding the element in the SVD file
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub comments: Vec<String>,
 }
 
 /// Return iterator over bit offsets of each field in array
@@ -119,8 +147,10 @@ pub struct FieldInfoBuilder {
     modified_write_values: Option<ModifiedWriteValues>,
     write_constraint: Option<WriteConstraint>,
     read_action: Option<ReadAction>,
+    data_type: Option<DataType>,
     enumerated_values: Option<Vec<EnumeratedValues>>,
     derived_from: Option<String>,
+    comments: Option<Vec<String>>,
 }
 
 impl From<FieldInfo> for FieldInfoBuilder {
@@ -135,8 +165,10 @@ impl From<FieldInfo> for FieldInfoBuilder {
             modified_write_values: f.modified_write_values,
             write_constraint: f.write_constraint,
             read_action: f.read_action,
+            data_type: f.data_type,
             enumerated_values: Some(f.enumerated_values),
             derived_from: f.derived_from,
+            comments: Some(f.comments),
         }
     }
 }
@@ -203,6 +235,11 @@ impl FieldInfoBuilder {
         self.read_action = value;
         self
     }
+    /// Set the data type of the field.
+    pub fn data_type(mut self, value: Option<DataType>) -> Self {
+        self.data_type = value;
+        self
+    }
     /// Set the enumerated values of the field
     pub fn enumerated_values(mut self, value: Vec<EnumeratedValues>) -> Self {
         self.enumerated_values = Some(value);
@@ -213,6 +250,11 @@ impl FieldInfoBuilder {
         self.derived_from = value;
         self
     }
+    /// Set the comments preceding the element
+    pub fn comments(mut self, value: Vec<String>) -> Self {
+        self.comments = Some(value);
+        self
+    }
     /// Validate and build a [`FieldInfo`].
     pub fn build(self, lvl: ValidateLevel) -> Result<FieldInfo, SvdError> {
         let field = FieldInfo {
@@ -227,8 +269,10 @@ impl FieldInfoBuilder {
             modified_write_values: self.modified_write_values,
             write_constraint: self.write_constraint,
             read_action: self.read_action,
+            data_type: self.data_type,
             enumerated_values: self.enumerated_values.unwrap_or_default(),
             derived_from: self.derived_from,
+            comments: self.comments.unwrap_or_default(),
         };
         field.validate(lvl)?;
         Ok(field)
@@ -272,6 +316,9 @@ impl FieldInfo {
         if builder.access.is_some() {
             self.access = builder.access;
         }
+        if let Some(comments) = builder.comments {
+            self.comments = comments;
+        }
         if builder.derived_from.is_some() {
             self.derived_from = builder.derived_from;
             self.modified_write_values = None;
@@ -287,6 +334,9 @@ impl FieldInfo {
             if builder.read_action.is_some() {
                 self.read_action = builder.read_action;
             }
+            if builder.data_type.is_some() {
+                self.data_type = builder.data_type;
+            }
             if let Some(enumerated_values) = builder.enumerated_values {
                 self.enumerated_values = enumerated_values;
             }
@@ -326,10 +376,30 @@ impl FieldInfo {
                             && matches!(ev1.usage(), None | Some(Usage::Write)) => {}
                     _ => return Err(Error::IncompatibleEnumeratedValues.into()),
                 }
+
+                // Toggle semantics only make sense for fields that can be written repeatedly
+                if let (
+                    Some(access @ (Access::WriteOnce | Access::ReadWriteOnce)),
+                    Some(
+                        mwv
+                        @ (ModifiedWriteValues::OneToToggle | ModifiedWriteValues::ZeroToToggle),
+                    ),
+                ) = (self.access, self.modified_write_values)
+                {
+                    return Err(Error::WriteOnceModifiedWriteValues(
+                        self.name.clone(),
+                        access,
+                        mwv,
+                    )
+                    .into());
+                }
             }
 
             if let Some(WriteConstraint::Range(constraint)) = self.write_constraint {
-                constraint.check_range(0..2_u64.pow(self.bit_range.width))?;
+                constraint.validate(lvl)?;
+                if self.bit_range.width < 64 {
+                    constraint.check_range(0..2_u64.pow(self.bit_range.width))?;
+                }
             }
         }
 
@@ -343,6 +413,45 @@ impl FieldInfo {
         self.validate(lvl)
     }
 
+    /// Add enumerated values of `other` to enumerated values with the same usage.
+    ///
+    /// Enumerated values with usages absent in `self` are copied.
+    /// Fails without changing `self` if the same value or default has different names
+    /// or the same name has different values.
+    pub fn merge_enums(&mut self, other: &FieldInfo) -> Result<(), SvdError> {
+        let mut merged = self.enumerated_values.clone();
+        for oevs in &other.enumerated_values {
+            let evs = match merged.iter_mut().find(|evs| evs.usage() == oevs.usage()) {
+                Some(evs) => evs,
+                None => {
+                    merged.push(oevs.clone());
+                    continue;
+                }
+            };
+            for ov in &oevs.values {
+                let existing = evs.values.iter().find(|v| {
+                    v.name == ov.name
+                        || (v.value.is_some() && v.value == ov.value)
+                        || (v.is_default() && ov.is_default())
+                });
+                match existing {
+                    Some(v) if v.name == ov.name && v.value == ov.value => {}
+                    Some(v) => {
+                        return Err(Error::ConflictingEnumeratedValues(
+                            self.name.clone(),
+                            v.name.clone(),
+                            ov.name.clone(),
+                        )
+                        .into())
+                    }
+                    None => evs.values.push(ov.clone()),
+                }
+            }
+        }
+        self.enumerated_values = merged;
+        Ok(())
+    }
+
     /// Get bit offset
     pub fn bit_offset(&self) -> u32 {
         self.bit_range.offset
@@ -362,6 +471,112 @@ impl FieldInfo {
         self.bit_range.msb()
     }
 
+    /// Get the bit mask of the field value, not shifted to the field position
+    pub fn value_mask(&self) -> u64 {
+        match self.bit_width() {
+            64.. => u64::MAX,
+            width => (1 << width) - 1,
+        }
+    }
+
+    /// Extract the value of this field from the reset value of its register.
+    ///
+    /// The value is not shifted to the field position.
+    pub fn reset_contribution(&self, register_reset: u64) -> u64 {
+        register_reset.checked_shr(self.bit_offset()).unwrap_or(0) & self.value_mask()
+    }
+
+    /// Get the reset value of this field from the `resetValue` of `register`.
+    ///
+    /// Returns `None` if the register has no reset value or if the `resetMask`
+    /// doesn't cover all the bits of the field. The value is not shifted to the field position.
+    pub fn reset_value(&self, register: &RegisterInfo) -> Option<u64> {
+        let value = register.properties.reset_value?;
+        if let Some(mask) = register.properties.reset_mask {
+            if self.reset_contribution(mask) != self.value_mask() {
+                return None;
+            }
+        }
+        Some(self.reset_contribution(value))
+    }
+
+    /// Compute the field value stored after writing `written` when the field holds `current`,
+    /// following `modifiedWriteValues`. Read-only fields keep their value.
+    ///
+    /// Values are not shifted to the field position.
+    pub fn apply_write(&self, current: u64, written: u64) -> u64 {
+        self.apply_write_with(current, written, None, None)
+    }
+
+    /// Same as [`apply_write`](Self::apply_write), using `access` and `modified_write_values`
+    /// inherited from the register if the field doesn't specify them
+    pub(crate) fn apply_write_with(
+        &self,
+        current: u64,
+        written: u64,
+        access: Option<Access>,
+        modified_write_values: Option<ModifiedWriteValues>,
+    ) -> u64 {
+        let value = if self.access.or(access) == Some(Access::ReadOnly) {
+            current
+        } else {
+            self.modified_write_values
+                .or(modified_write_values)
+                .unwrap_or_default()
+                .apply(current, written)
+        };
+        value & self.value_mask()
+    }
+
+    /// Get the access of the field, inherited from the register if not specified,
+    /// [`Access::ReadWrite`] by default.
+    ///
+    /// `register` are the properties of the register with inheritance resolved, as returned
+    /// by [`Device::effective_properties`](crate::Device::effective_properties)
+    pub fn effective_access(&self, register: &RegisterProperties) -> Access {
+        self.access.or(register.access).unwrap_or_default()
+    }
+
+    /// Get the name of the field as used for definitions in a C header
+    ///
+    /// It is composed as `{prefix}{peripheral}_{prepend}{register}{append}_{field}`, where
+    /// `prefix` is the device `headerDefinitionsPrefix`, `peripheral` is the `headerStructName`
+    /// (or the name) of the peripheral and `prepend`/`append` are the peripheral
+    /// `prependToName`/`appendToName`. Array placeholders are removed from all the names.
+    pub fn header_name(
+        &self,
+        register: &RegisterInfo,
+        peripheral: &PeripheralInfo,
+        device: &Device,
+    ) -> String {
+        self.header_name_with(register, peripheral, device, &CmsisMangler)
+    }
+
+    /// Same as [`FieldInfo::header_name`], but names are sanitized with `mangler`
+    pub fn header_name_with(
+        &self,
+        register: &RegisterInfo,
+        peripheral: &PeripheralInfo,
+        device: &Device,
+        mangler: &(impl NameMangler + ?Sized),
+    ) -> String {
+        let strip = |name: &str| mangler.sanitize(name);
+        format!(
+            "{}{}_{}{}{}_{}",
+            device.header_definitions_prefix.as_deref().unwrap_or(""),
+            strip(
+                peripheral
+                    .header_struct_name
+                    .as_deref()
+                    .unwrap_or(&peripheral.name)
+            ),
+            peripheral.prepend_to_name.as_deref().unwrap_or(""),
+            strip(&register.name),
+            peripheral.append_to_name.as_deref().unwrap_or(""),
+            strip(&self.name),
+        )
+    }
+
     /// Get enumeratedValues cluster by usage
     pub fn get_enumerated_values(&self, usage: Usage) -> Option<&EnumeratedValues> {
         match self.enumerated_values.len() {
@@ -386,10 +601,47 @@ impl FieldInfo {
 }
 
 impl Field {
+    /// Merges a partial field, e.g. of a patch file, into this field.
+    ///
+    /// Comments are kept if `other` has none.
+    /// `name` and `bitRange` are always present in `other` and are kept.
+    pub(crate) fn merge(&mut self, other: &Field, lvl: ValidateLevel) -> Result<(), SvdError> {
+        let mut builder = FieldInfoBuilder::from((**other).clone());
+        builder.name = None;
+        builder.bit_range = None;
+        builder.derived_from = None;
+        if other.comments.is_empty() {
+            builder.comments = None;
+        }
+        let info: &mut FieldInfo = self;
+        info.modify_from(builder, lvl)?;
+        if other.derived_from.is_some() {
+            self.derived_from = other.derived_from.clone();
+        }
+        merge_dim(self, other, lvl)
+    }
+
+    /// Validate that the field, or all the elements of the field array,
+    /// fit in a register of `size` bits. Only done on [`ValidateLevel::Strict`].
+    pub fn validate_register_size(&self, size: u32, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if !lvl.is_strict() {
+            return Ok(());
+        }
+        let last_offset = match self {
+            Self::Single(info) => info.bit_offset(),
+            Self::Array(info, dim) => bit_offsets(info, dim).max().unwrap_or(info.bit_offset()),
+        };
+        let end = last_offset as u64 + self.bit_width() as u64;
+        if end > size as u64 {
+            return Err(Error::ExceedsRegisterSize(self.name.clone(), end as u32 - 1, size).into());
+        }
+        Ok(())
+    }
+
     /// Validate the [`Field`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        if let Self::Array(_, dim) = self {
-            dim.validate(lvl)?;
+        if let Self::Array(info, dim) = self {
+            super::first_error(super::validate_array(&info.name, dim, lvl))?;
         }
         self.deref().validate_all(lvl)
     }
@@ -1,5 +1,17 @@
+use super::prelude::*;
 use super::{BuildError, Description, Name, SvdError, ValidateLevel};
 
+/// Maximum interrupt number supported by the Cortex-M NVIC
+pub const MAX_NVIC_INTERRUPT: u32 = 495;
+
+/// Errors for [`Interrupt::validate`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The interrupt number is too large
+    #[error("Interrupt `{0}` value {1} is out of range 0..={2}")]
+    ValueOutOfRange(String, u32, u32),
+}
+
 /// Describes an interrupt in the device
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -93,10 +105,16 @@ impl Interrupt {
     }
     /// Validate the [`Interrupt`].
     ///
-    /// # Notes
-    ///
-    /// This doesn't do anything.
-    pub fn validate(&self, _lvl: ValidateLevel) -> Result<(), SvdError> {
+    /// In strict mode the value must fit in the NVIC range `0..=`[`MAX_NVIC_INTERRUPT`]
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_max(MAX_NVIC_INTERRUPT, lvl)
+    }
+
+    /// Validate the [`Interrupt`] against a custom maximum interrupt number
+    pub fn validate_max(&self, max: u32, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if lvl.is_strict() && self.value > max {
+            return Err(Error::ValueOutOfRange(self.name.clone(), self.value, max).into());
+        }
         Ok(())
     }
 }
@@ -0,0 +1,134 @@
+use super::prelude::*;
+use super::{registercluster::children_end, MaybeArray, RegisterCluster};
+
+/// Byte layout of the registers of a peripheral or cluster
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PeripheralLayout {
+    /// Size of the layout in bytes, from offset 0 to the end of the last item
+    pub size: u64,
+    /// Items sorted by offset, holes are filled with [`LayoutKind::Reserved`] items
+    pub items: Vec<LayoutItem>,
+}
+
+/// Element of a [`PeripheralLayout`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LayoutItem {
+    /// Offset in bytes relative to the parent
+    pub offset: u64,
+    /// Size in bytes of the whole item, including all array elements
+    pub size: u64,
+    /// Kind of the item
+    pub kind: LayoutKind,
+}
+
+/// Kind of a [`LayoutItem`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LayoutKind {
+    /// A register or array of registers
+    Register {
+        /// Name of the register, with `%s` placeholder for arrays
+        name: String,
+        /// Size of one register in bytes
+        size: u64,
+        /// Dimension of array, `None` for single registers
+        dim: Option<ArrayDim>,
+    },
+    /// A cluster or array of clusters
+    Cluster {
+        /// Name of the cluster, with `%s` placeholder for arrays
+        name: String,
+        /// Layout of one cluster
+        layout: PeripheralLayout,
+        /// Dimension of array, `None` for single clusters
+        dim: Option<ArrayDim>,
+    },
+    /// Unused space
+    Reserved,
+}
+
+/// Dimension of an array in a [`LayoutItem`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArrayDim {
+    /// Number of elements
+    pub count: u32,
+    /// Distance between elements in bytes
+    pub stride: u32,
+}
+
+impl PeripheralLayout {
+    /// Compute layout of `children`, `default_size` is the register size inherited from the parent
+    pub(crate) fn new(children: &[RegisterCluster], default_size: Option<u32>) -> Self {
+        let mut items: Vec<_> = children
+            .iter()
+            .map(|rc| {
+                let (offset, element, kind) = match rc {
+                    RegisterCluster::Register(r) => {
+                        let size = (r.properties.size.or(default_size).unwrap_or(32) + 7) / 8;
+                        let size = size as u64;
+                        let kind = LayoutKind::Register {
+                            name: r.name.clone(),
+                            size,
+                            dim: array_dim(r),
+                        };
+                        (r.address_offset, size, kind)
+                    }
+                    RegisterCluster::Cluster(c) => {
+                        let size = c.default_register_properties.size.or(default_size);
+                        let layout = Self::new(&c.children, size);
+                        let element = children_end(&c.children, size);
+                        let kind = LayoutKind::Cluster {
+                            name: c.name.clone(),
+                            layout,
+                            dim: array_dim(c),
+                        };
+                        (c.address_offset, element, kind)
+                    }
+                };
+                let size = match &kind {
+                    LayoutKind::Register { dim: Some(dim), .. }
+                    | LayoutKind::Cluster { dim: Some(dim), .. } => {
+                        dim.count.saturating_sub(1) as u64 * dim.stride as u64 + element
+                    }
+                    _ => element,
+                };
+                LayoutItem {
+                    offset: offset as u64,
+                    size,
+                    kind,
+                }
+            })
+            .collect();
+        items.sort_by_key(|i| i.offset);
+
+        let mut filled = Vec::with_capacity(items.len());
+        let mut end = 0;
+        for item in items {
+            if item.offset > end {
+                filled.push(LayoutItem {
+                    offset: end,
+                    size: item.offset - end,
+                    kind: LayoutKind::Reserved,
+                });
+            }
+            end = end.max(item.offset + item.size);
+            filled.push(item);
+        }
+        Self {
+            size: end,
+            items: filled,
+        }
+    }
+}
+
+fn array_dim<T>(item: &MaybeArray<T>) -> Option<ArrayDim> {
+    match item {
+        MaybeArray::Single(_) => None,
+        MaybeArray::Array(_, dim) => Some(ArrayDim {
+            count: dim.dim,
+            stride: dim.dim_increment,
+        }),
+    }
+}
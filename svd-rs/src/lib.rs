@@ -1,6 +1,25 @@
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! SVD objects.
 //! This module defines components of an SVD along with parse and encode implementations
+//!
+//! Without the default `std` feature the crate only depends on `alloc`.
+
+extern crate alloc;
+
+/// Items of the `std` prelude missing in `no_std` builds
+#[allow(unused_imports)]
+mod prelude {
+    pub(crate) use alloc::{
+        borrow::ToOwned,
+        boxed::Box,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+}
+use prelude::*;
 
 /// Common things for structures which can be collected in arrays
 pub mod array;
@@ -76,7 +95,7 @@ pub use self::peripheral::{Peripheral, PeripheralInfo, PeripheralInfoBuilder};
 
 /// Device objects
 pub mod device;
-pub use self::device::{Device, DeviceBuilder};
+pub use self::device::{Device, DeviceBuilder, RegIter, RegisterRow};
 
 /// Modified Write Values objects
 pub mod modifiedwritevalues;
@@ -86,10 +105,30 @@ pub use self::modifiedwritevalues::ModifiedWriteValues;
 pub mod readaction;
 pub use self::readaction::ReadAction;
 
+/// Data Type objects
+pub mod datatype;
+pub use self::datatype::DataType;
+
+/// Number literals
+pub mod literals;
+pub use self::literals::Literals;
+
 /// Protection objects
 pub mod protection;
 pub use self::protection::Protection;
 
+/// Security Attribution Unit objects
+pub mod sauregion;
+pub use self::sauregion::{SauAccess, SauRegion, SauRegionsConfig};
+
+/// Register layout objects
+pub mod layout;
+pub use self::layout::PeripheralLayout;
+
+/// Name mangling
+pub mod mangle;
+pub use self::mangle::{CmsisMangler, NameMangler};
+
 /// Level of validation
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ValidateLevel {
@@ -127,7 +166,7 @@ pub mod derive_from;
 #[cfg(feature = "derive-from")]
 pub use derive_from::DeriveFrom;
 
-use once_cell::sync::Lazy;
+use once_cell::race::OnceBox;
 use regex::Regex;
 
 /// Errors that can occur during building.
@@ -139,6 +178,9 @@ pub enum SvdError {
     /// Name check error
     #[error("`Name check error: {0}")]
     Name(#[from] NameError),
+    /// Cpu error
+    #[error("`Cpu error: {0}")]
+    Cpu(#[from] cpu::Error),
     /// Device error
     #[error("`Device error: {0}")]
     Device(#[from] device::Error),
@@ -169,6 +211,84 @@ pub enum SvdError {
     /// WriteConstraint error
     #[error("`WriteConstraint error: {0}")]
     WriteConstraint(#[from] writeconstraint::Error),
+    /// Interrupt error
+    #[error("`Interrupt error: {0}")]
+    Interrupt(#[from] interrupt::Error),
+    /// DimElement error
+    #[error("`DimElement error: {0}")]
+    DimElement(#[from] dimelement::Error),
+    /// SauRegion error
+    #[error("`SauRegion error: {0}")]
+    SauRegion(#[from] sauregion::Error),
+    /// JSON (de)serialization error
+    #[cfg(feature = "serde")]
+    #[error("`JSON error: {0}")]
+    Json(String),
+    /// Error in the element with the dotted path, like `PERIPH.REG.FIELD`
+    #[error("{0}: {1}")]
+    At(String, Box<SvdError>),
+}
+
+impl SvdError {
+    /// Name of the error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Build(_) => "Build",
+            Self::Name(_) => "Name",
+            Self::Cpu(_) => "Cpu",
+            Self::Device(_) => "Device",
+            Self::Peripheral(_) => "Peripheral",
+            Self::Cluster(_) => "Cluster",
+            Self::Register(_) => "Register",
+            Self::Field(_) => "Field",
+            Self::BitRange(_) => "BitRange",
+            Self::EnumeratedValue(_) => "EnumeratedValue",
+            Self::EnumeratedValues(_) => "EnumeratedValues",
+            Self::RegisterProperties(_) => "RegisterProperties",
+            Self::WriteConstraint(_) => "WriteConstraint",
+            Self::Interrupt(_) => "Interrupt",
+            Self::DimElement(_) => "DimElement",
+            Self::SauRegion(_) => "SauRegion",
+            #[cfg(feature = "serde")]
+            Self::Json(_) => "Json",
+            Self::At(_, e) => e.kind(),
+        }
+    }
+
+    /// Prepend `name` of the parent element to the path of the error
+    pub fn at(self, name: &str) -> Self {
+        match self {
+            Self::At(path, e) => Self::At(format!("{name}.{path}"), e),
+            e => Self::At(name.into(), Box::new(e)),
+        }
+    }
+
+    /// Dotted path to the element the error occurred in
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::At(path, _) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Error without the element path
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::At(_, e) => e,
+            e => e,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SvdError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("SvdError", 2)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.end()
+    }
 }
 
 /// Errors from a builder
@@ -185,11 +305,15 @@ pub enum NameError {
     /// Name is invalid
     #[error("Name `{0}` contains unexpected symbol")]
     Invalid(String, String),
+    /// Name of array with several elements has no `%s` or `[%s]` placeholder
+    #[error("Array name `{0}` is missing a %s placeholder")]
+    MissingPlaceholder(String),
 }
 
 pub(crate) fn check_name(name: &str, tag: &str) -> Result<(), NameError> {
-    static PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new("^[_A-Za-z0-9]*$").unwrap());
-    if PATTERN.is_match(name) {
+    static PATTERN: OnceBox<Regex> = OnceBox::new();
+    let pattern = PATTERN.get_or_init(|| Box::new(Regex::new("^[_A-Za-z0-9]*$").unwrap()));
+    if pattern.is_match(name) {
         Ok(())
     } else {
         Err(NameError::Invalid(name.to_string(), tag.to_string()))
@@ -197,16 +321,43 @@ pub(crate) fn check_name(name: &str, tag: &str) -> Result<(), NameError> {
 }
 
 pub(crate) fn check_dimable_name(name: &str, tag: &str) -> Result<(), NameError> {
-    static PATTERN: Lazy<Regex> = Lazy::new(|| {
-        Regex::new("^(((%s)|(%s)[_A-Za-z]{1}[_A-Za-z0-9]*)|([_A-Za-z]{1}[_A-Za-z0-9]*(\\[%s\\])?)|([_A-Za-z]{1}[_A-Za-z0-9]*(%s)?[_A-Za-z0-9]*))$").unwrap()
+    static PATTERN: OnceBox<Regex> = OnceBox::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Box::new(Regex::new("^(((%s)|(%s)[_A-Za-z]{1}[_A-Za-z0-9]*)|([_A-Za-z]{1}[_A-Za-z0-9]*(\\[%s\\])?)|([_A-Za-z]{1}[_A-Za-z0-9]*(%s)?[_A-Za-z0-9]*))$").unwrap())
     });
-    if PATTERN.is_match(name) {
+    if pattern.is_match(name) {
         Ok(())
     } else {
         Err(NameError::Invalid(name.to_string(), tag.to_string()))
     }
 }
 
+pub(crate) fn check_placeholder(name: &str, dim: &DimElement) -> Result<(), NameError> {
+    if dim.dim > 1 && !name.contains("%s") {
+        Err(NameError::MissingPlaceholder(name.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the array description of the element `name`, returning all errors
+pub(crate) fn validate_array(name: &str, dim: &DimElement, lvl: ValidateLevel) -> Vec<SvdError> {
+    let mut errors = Vec::new();
+    if !lvl.is_disabled() {
+        errors.extend(check_placeholder(name, dim).err().map(Into::into));
+    }
+    errors.extend(dim.validate(lvl).err());
+    errors
+}
+
+/// The first of the collected validation `errors`
+pub(crate) fn first_error(errors: Vec<SvdError>) -> Result<(), SvdError> {
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
 pub(crate) fn check_derived_name(name: &str, tag: &str) -> Result<(), NameError> {
     for x in name.split('.') {
         check_dimable_name(x, tag)?
@@ -214,6 +365,18 @@ pub(crate) fn check_derived_name(name: &str, tag: &str) -> Result<(), NameError>
     Ok(())
 }
 
+/// Names which appear more than once, each reported once in order of the repetition
+pub(crate) fn duplicate_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut duplicates = Vec::new();
+    for name in names {
+        if !seen.insert(name) && !duplicates.contains(&name) {
+            duplicates.push(name);
+        }
+    }
+    duplicates
+}
+
 trait EmptyToNone {
     fn empty_to_none(self) -> Self;
 }
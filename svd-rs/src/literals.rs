@@ -0,0 +1,42 @@
+use super::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// Numbers as they were written in the SVD file, like `0x0000FFFF` or `#1010`
+///
+/// Filled by the parser on request and used by the encoder to write back
+/// the values which were not modified in their original format.
+///
+/// Only formatting is recorded, so all [`Literals`] compare equal and elements
+/// differing only in literals are equal.
+#[derive(Clone, Debug, Default)]
+pub struct Literals(BTreeMap<String, (u64, String)>);
+
+impl PartialEq for Literals {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Literals {}
+
+impl Literals {
+    /// Record that `value` of the child element `tag` was written as `text`
+    pub fn insert(&mut self, tag: &str, value: u64, text: String) {
+        self.0.insert(tag.to_string(), (value, text));
+    }
+    /// Get the text of the child element `tag` if it was written with the same `value`
+    pub fn get(&self, tag: &str, value: u64) -> Option<&str> {
+        match self.0.get(tag) {
+            Some((v, text)) if *v == value => Some(text),
+            _ => None,
+        }
+    }
+    /// Returns `true` if no literals are recorded
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Forget all recorded literals
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+}
@@ -0,0 +1,24 @@
+use super::prelude::*;
+use super::DimElement;
+
+/// Naming policy for names derived from SVD element names
+///
+/// Default methods follow CMSIS conventions.
+pub trait NameMangler {
+    /// Name of the array element with `index`,
+    /// `base` is the name of the array with `%s` or `[%s]` placeholder
+    fn array_element(&self, base: &str, index: &str) -> String {
+        DimElement::replace_placeholder(base, index)
+    }
+
+    /// Name usable as part of an identifier, array placeholders are removed
+    fn sanitize(&self, name: &str) -> String {
+        name.replace("[%s]", "").replace("%s", "")
+    }
+}
+
+/// CMSIS-conformant [`NameMangler`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CmsisMangler;
+
+impl NameMangler for CmsisMangler {}
@@ -59,6 +59,21 @@ impl ModifiedWriteValues {
         }
     }
 
+    /// Compute the value stored after writing `written` over `current`
+    pub const fn apply(self, current: u64, written: u64) -> u64 {
+        match self {
+            Self::OneToClear => current & !written,
+            Self::OneToSet => current | written,
+            Self::OneToToggle => current ^ written,
+            Self::ZeroToClear => current & written,
+            Self::ZeroToSet => current | !written,
+            Self::ZeroToToggle => current ^ !written,
+            Self::Clear => 0,
+            Self::Set => u64::MAX,
+            Self::Modify => written,
+        }
+    }
+
     /// Convert this [`ModifiedWriteValues`] into a static string.
     pub const fn as_str(self) -> &'static str {
         match self {
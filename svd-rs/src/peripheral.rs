@@ -1,13 +1,16 @@
+use super::prelude::*;
 use super::{
-    array::{descriptions, names},
+    array::{descriptions, merge_dim, names_with},
     registercluster::{
-        AllRegistersIter, AllRegistersIterMut, ClusterIter, ClusterIterMut, RegisterIter,
-        RegisterIterMut,
+        self, children_end, expand_arrays, join_path, merge_children, prune_padding, sort_children,
+        walk_registers, AllRegistersIter, AllRegistersIterMut, ClusterIter, ClusterIterMut,
+        RegisterIter, RegisterIterMut,
     },
-    AddressBlock, BuildError, Cluster, Description, DimElement, EmptyToNone, Interrupt, MaybeArray,
-    Name, Register, RegisterCluster, RegisterProperties, SvdError, ValidateLevel,
+    Access, AddressBlock, AddressBlockUsage, BuildError, Cluster, CmsisMangler, Description,
+    DimElement, EmptyToNone, Interrupt, Literals, MaybeArray, Name, NameMangler, PeripheralLayout,
+    Register, RegisterCluster, RegisterProperties, SvdError, ValidateLevel,
 };
-use std::ops::Deref;
+use core::ops::Deref;
 
 /// A single peripheral or array of peripherals
 pub type Peripheral = MaybeArray<PeripheralInfo>;
@@ -18,6 +21,23 @@ pub enum Error {
     /// The peripheral has no registers, but specified a `<registers>` tag.
     #[error("Peripheral have `registers` tag, but it is empty")]
     EmptyRegisters,
+    /// A register is not inside of any address block of the peripheral.
+    #[error("Register `{0}` at offset 0x{1:x} is outside of address blocks")]
+    OutOfAddressBlocks(String, u64),
+    /// Instances of a peripheral array overlap.
+    #[error(
+        "Peripheral array `{0}` has dimIncrement 0x{1:x} smaller than its register span 0x{2:x}"
+    )]
+    DimIncrementTooSmall(String, u32, u64),
+    /// Several interrupts of the peripheral have the same name.
+    #[error("Interrupt `{0}` is defined more than once")]
+    DuplicateInterrupt(String),
+    /// Address ranges of two registers overlap.
+    #[error("Register `{0}` at offset 0x{1:x} overlaps register `{2}` at offset 0x{3:x}")]
+    OverlappingRegisters(String, u64, String, u64),
+    /// Several registers or clusters of the peripheral have the same name.
+    #[error("Register or cluster `{0}` is defined more than once")]
+    DuplicateName(String),
 }
 
 /// A description of a peripheral in the [device](crate::Device), describing, for example, the [memory mappings](crate::RegisterInfo).
@@ -123,6 +143,17 @@ pub struct PeripheralInfo {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub derived_from: Option<String>,
+
+    /// Comments preceding the element in the SVD file
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub comments: Vec<String>,
+
+    /// Numbers as written in the SVD file
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub literals: Literals,
 }
 
 /// Return iterator over base addresses of each peripheral in array
@@ -137,9 +168,18 @@ pub fn base_addresses<'a>(
 pub fn expand<'a>(
     info: &'a PeripheralInfo,
     dim: &'a DimElement,
+) -> impl Iterator<Item = PeripheralInfo> + 'a {
+    expand_with(info, dim, &CmsisMangler)
+}
+
+/// Extract `PeripheralInfo` items from array, naming them with `mangler`
+pub fn expand_with<'a, M: NameMangler + ?Sized>(
+    info: &'a PeripheralInfo,
+    dim: &'a DimElement,
+    mangler: &'a M,
 ) -> impl Iterator<Item = PeripheralInfo> + 'a {
     dim.indexes()
-        .zip(names(info, dim))
+        .zip(names_with(info, dim, mangler))
         .zip(descriptions(info, dim))
         .zip(base_addresses(info, dim))
         .map(|(((idx, name), description), base_address)| {
@@ -149,7 +189,7 @@ pub fn expand<'a>(
             info.base_address = base_address;
             info.display_name = info
                 .display_name
-                .map(|d| d.replace("[%s]", &idx).replace("%s", &idx));
+                .map(|d| DimElement::replace_placeholder(&d, &idx));
             info
         })
 }
@@ -172,6 +212,8 @@ pub struct PeripheralInfoBuilder {
     interrupt: Option<Vec<Interrupt>>,
     registers: Option<Vec<RegisterCluster>>,
     derived_from: Option<String>,
+    comments: Option<Vec<String>>,
+    literals: Option<Literals>,
 }
 
 impl From<PeripheralInfo> for PeripheralInfoBuilder {
@@ -192,6 +234,8 @@ impl From<PeripheralInfo> for PeripheralInfoBuilder {
             interrupt: Some(p.interrupt),
             registers: p.registers,
             derived_from: p.derived_from,
+            comments: Some(p.comments),
+            literals: Some(p.literals),
         }
     }
 }
@@ -272,6 +316,16 @@ impl PeripheralInfoBuilder {
         self.derived_from = value;
         self
     }
+    /// Set the comments preceding the element
+    pub fn comments(mut self, value: Vec<String>) -> Self {
+        self.comments = Some(value);
+        self
+    }
+    /// Set the numbers as written in the SVD file
+    pub fn literals(mut self, value: Literals) -> Self {
+        self.literals = Some(value);
+        self
+    }
     /// Validate and build a [`PeripheralInfo`].
     pub fn build(self, lvl: ValidateLevel) -> Result<PeripheralInfo, SvdError> {
         let per = PeripheralInfo {
@@ -294,6 +348,8 @@ impl PeripheralInfoBuilder {
             interrupt: self.interrupt.unwrap_or_default(),
             registers: self.registers,
             derived_from: self.derived_from,
+            comments: self.comments.unwrap_or_default(),
+            literals: self.literals.unwrap_or_default(),
         };
         per.validate(lvl)?;
         Ok(per)
@@ -352,6 +408,12 @@ impl PeripheralInfo {
         if let Some(interrupt) = builder.interrupt {
             self.interrupt = interrupt;
         }
+        if let Some(comments) = builder.comments {
+            self.comments = comments;
+        }
+        if let Some(literals) = builder.literals {
+            self.literals = literals;
+        }
         if builder.derived_from.is_some() {
             self.derived_from = builder.derived_from;
             self.registers = None;
@@ -372,10 +434,20 @@ impl PeripheralInfo {
 
     /// Validate the [`PeripheralInfo`]
     pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_element(lvl)?;
+        match self.check_children(lvl).into_iter().next() {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
+    }
+    fn validate_element(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
         if !lvl.is_disabled() {
             // TODO
             if lvl.is_strict() {
                 super::check_dimable_name(&self.name, "name")?;
+                if let Some(name) = self.header_struct_name.as_ref() {
+                    super::check_name(name, "headerStructName")?;
+                }
             }
             if let Some(name) = self.derived_from.as_ref() {
                 if lvl.is_strict() {
@@ -389,28 +461,107 @@ impl PeripheralInfo {
         }
         Ok(())
     }
+    /// Check interrupts and registers against each other, returning all errors
+    fn check_children(&self, lvl: ValidateLevel) -> Vec<Error> {
+        let mut errors = Vec::new();
+        if !lvl.is_strict() {
+            return errors;
+        }
+        for name in super::duplicate_names(self.interrupt.iter().map(|i| i.name.as_str())) {
+            errors.push(Error::DuplicateInterrupt(name.into()));
+        }
+        if let (None, Some(registers)) = (&self.derived_from, &self.registers) {
+            for name in registercluster::duplicate_names(registers) {
+                errors.push(Error::DuplicateName(name.into()));
+            }
+            self.check_address_blocks(registers, &mut errors);
+            self.check_overlaps(registers, &mut errors);
+        }
+        errors
+    }
+
+    /// Check that each register, with arrays expanded, fits in one of the address blocks
+    fn check_address_blocks(&self, registers: &[RegisterCluster], errors: &mut Vec<Error>) {
+        let blocks = match self.address_block.as_deref() {
+            Some(blocks) if !blocks.is_empty() => blocks,
+            _ => return,
+        };
+        walk_registers(
+            registers,
+            0,
+            &self.default_register_properties,
+            &mut Vec::new(),
+            &mut |path, offset, r, _, props| {
+                let end = offset + (props.size.unwrap_or(32) as u64 + 7) / 8;
+                if !blocks
+                    .iter()
+                    .any(|b| b.offset as u64 <= offset && end <= b.offset as u64 + b.size as u64)
+                {
+                    errors.push(Error::OutOfAddressBlocks(join_path(path, &r.name), offset));
+                }
+            },
+        );
+    }
+
+    /// Check that registers, with arrays expanded, don't share addresses.
+    ///
+    /// Registers with `alternateGroup` or `alternateRegister` may overlap others.
+    fn check_overlaps(&self, registers: &[RegisterCluster], errors: &mut Vec<Error>) {
+        let mut spans = Vec::new();
+        walk_registers(
+            registers,
+            0,
+            &self.default_register_properties,
+            &mut Vec::new(),
+            &mut |path, offset, r, _, props| {
+                if r.alternate_group.is_none() && r.alternate_register.is_none() {
+                    let end = offset + (props.size.unwrap_or(32) as u64 + 7) / 8;
+                    spans.push((offset, end, join_path(path, &r.name)));
+                }
+            },
+        );
+        spans.sort_by_key(|(offset, _, _)| *offset);
+        let mut last: Option<&(u64, u64, String)> = None;
+        for span in &spans {
+            if let Some(prev) = last {
+                if span.0 < prev.1 {
+                    errors.push(Error::OverlappingRegisters(
+                        prev.2.clone(),
+                        prev.0,
+                        span.2.clone(),
+                        span.0,
+                    ));
+                }
+            }
+            if last.map_or(true, |prev| span.1 > prev.1) {
+                last = Some(span);
+            }
+        }
+    }
     /// Validate the [`PeripheralInfo`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        if let Some(abs) = self.address_block.as_ref() {
-            for ab in abs {
-                ab.validate(lvl)?;
-            }
+        super::first_error(self.validate_collect(lvl))
+    }
+    /// Validate the [`PeripheralInfo`] recursively, collecting all errors instead of stopping at the first one
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
+        for ab in self.address_block.iter().flatten() {
+            errors.extend(ab.validate(lvl).err());
         }
         for i in &self.interrupt {
-            i.validate(lvl)?;
-        }
-        self.default_register_properties.validate(lvl)?;
-        for r in self.registers() {
-            r.validate_all(lvl)?;
+            errors.extend(i.validate(lvl).err());
         }
-        for c in self.clusters() {
-            c.validate_all(lvl)?;
+        errors.extend(self.default_register_properties.validate(lvl).err());
+        if let Some(registers) = self.registers.as_ref() {
+            errors.extend(registercluster::validate_collect(registers, lvl));
         }
-        self.validate(lvl)
+        errors.extend(self.validate_element(lvl).err());
+        errors.extend(self.check_children(lvl).into_iter().map(SvdError::from));
+        errors
     }
 
     /// Returns iterator over child registers
-    pub fn registers(&self) -> RegisterIter {
+    pub fn registers(&self) -> RegisterIter<'_> {
         RegisterIter {
             all: match &self.registers {
                 Some(regs) => regs.iter(),
@@ -420,7 +571,7 @@ impl PeripheralInfo {
     }
 
     /// Returns mutable iterator over child registers
-    pub fn registers_mut(&mut self) -> RegisterIterMut {
+    pub fn registers_mut(&mut self) -> RegisterIterMut<'_> {
         RegisterIterMut {
             all: match &mut self.registers {
                 Some(regs) => regs.iter_mut(),
@@ -430,7 +581,7 @@ impl PeripheralInfo {
     }
 
     /// Returns iterator over child clusters
-    pub fn clusters(&self) -> ClusterIter {
+    pub fn clusters(&self) -> ClusterIter<'_> {
         ClusterIter {
             all: match &self.registers {
                 Some(regs) => regs.iter(),
@@ -440,7 +591,7 @@ impl PeripheralInfo {
     }
 
     /// Returns mutable iterator over child clusters
-    pub fn clusters_mut(&mut self) -> ClusterIterMut {
+    pub fn clusters_mut(&mut self) -> ClusterIterMut<'_> {
         ClusterIterMut {
             all: match &mut self.registers {
                 Some(regs) => regs.iter_mut(),
@@ -451,12 +602,12 @@ impl PeripheralInfo {
 
     /// Returns iterator over all descendant registers
     #[deprecated(since = "0.12.1", note = "Please use `all_registers` instead")]
-    pub fn reg_iter(&self) -> AllRegistersIter {
+    pub fn reg_iter(&self) -> AllRegistersIter<'_> {
         self.all_registers()
     }
 
     /// Returns iterator over all descendant registers
-    pub fn all_registers(&self) -> AllRegistersIter {
+    pub fn all_registers(&self) -> AllRegistersIter<'_> {
         AllRegistersIter {
             rem: match &self.registers {
                 Some(regs) => regs.iter().rev().collect(),
@@ -467,12 +618,12 @@ impl PeripheralInfo {
 
     /// Returns mutable iterator over all descendant registers
     #[deprecated(since = "0.12.1", note = "Please use `all_registers_mut` instead")]
-    pub fn reg_iter_mut(&mut self) -> AllRegistersIterMut {
+    pub fn reg_iter_mut(&mut self) -> AllRegistersIterMut<'_> {
         self.all_registers_mut()
     }
 
     /// Returns mutable iterator over all descendant registers
-    pub fn all_registers_mut(&mut self) -> AllRegistersIterMut {
+    pub fn all_registers_mut(&mut self) -> AllRegistersIterMut<'_> {
         AllRegistersIterMut {
             rem: match &mut self.registers {
                 Some(regs) => regs.iter_mut().rev().collect(),
@@ -481,24 +632,35 @@ impl PeripheralInfo {
         }
     }
 
-    /// Get register by name
+    /// Get register by name.
+    ///
+    /// Arrays are also found by element names like `DR3` or `DR[3]`,
+    /// registers inside of clusters by `CLUSTER.REG` paths.
     pub fn get_register(&self, name: &str) -> Option<&Register> {
-        self.registers().find(|f| f.name == name)
+        registercluster::get_register(self.registers.as_deref()?, name)
     }
 
     /// Get mutable register by name
     pub fn get_mut_register(&mut self, name: &str) -> Option<&mut Register> {
-        self.registers_mut().find(|f| f.name == name)
+        registercluster::get_mut_register(self.registers.as_deref_mut()?, name)
     }
 
-    /// Get cluster by name
+    /// Get cluster by name.
+    ///
+    /// Arrays are also found by element names,
+    /// nested clusters by `CLUSTER.NESTED` paths.
     pub fn get_cluster(&self, name: &str) -> Option<&Cluster> {
-        self.clusters().find(|f| f.name == name)
+        registercluster::get_cluster(self.registers.as_deref()?, name)
     }
 
     /// Get mutable cluster by name
     pub fn get_mut_cluster(&mut self, name: &str) -> Option<&mut Cluster> {
-        self.clusters_mut().find(|f| f.name == name)
+        registercluster::get_mut_cluster(self.registers.as_deref_mut()?, name)
+    }
+
+    /// Get the default access of the registers, [`Access::ReadWrite`] if not specified
+    pub fn effective_access(&self) -> Access {
+        self.default_register_properties.access.unwrap_or_default()
     }
 
     /// Get interrupt by name
@@ -510,15 +672,223 @@ impl PeripheralInfo {
     pub fn get_mut_interrupt(&mut self, name: &str) -> Option<&mut Interrupt> {
         self.interrupt.iter_mut().find(|e| e.name == name)
     }
+
+    /// Remove registers which only reserve space, see [`RegisterInfo::is_padding`](crate::RegisterInfo::is_padding)
+    pub fn prune_padding(&mut self) {
+        self.prune_padding_with(super::register::DEFAULT_PADDING_PREFIXES)
+    }
+
+    /// Remove registers which only reserve space, see [`RegisterInfo::is_padding_with`](crate::RegisterInfo::is_padding_with)
+    pub fn prune_padding_with(&mut self, prefixes: &[&str]) {
+        if let Some(regs) = self.registers.as_mut() {
+            prune_padding(regs, prefixes);
+        }
+    }
+
+    /// Replace arrays of registers, clusters and fields with their single elements,
+    /// see [`Register::expanded`](crate::Register::expanded)
+    pub fn expand_arrays(&mut self) {
+        if let Some(regs) = self.registers.as_mut() {
+            expand_arrays(regs);
+        }
+    }
+
+    /// Compute the byte layout of registers and clusters with holes filled with reserved items.
+    ///
+    /// The layout of derived peripherals without own registers is empty.
+    pub fn layout(&self) -> PeripheralLayout {
+        match &self.registers {
+            Some(regs) => PeripheralLayout::new(regs, self.default_register_properties.size),
+            None => PeripheralLayout::new(&[], None),
+        }
+    }
+
+    /// Returns unused byte ranges, relative to the base address, with arrays expanded.
+    ///
+    /// Only the address blocks are searched for holes if the peripheral has them,
+    /// otherwise the range from 0 to the end of the last register.
+    pub fn address_gaps(&self) -> Vec<core::ops::Range<u64>> {
+        let registers = match self.registers.as_deref() {
+            Some(registers) => registers,
+            None => return Vec::new(),
+        };
+        let mut used = Vec::new();
+        walk_registers(
+            registers,
+            0,
+            &self.default_register_properties,
+            &mut Vec::new(),
+            &mut |_, offset, _, _, props| {
+                used.push(offset..offset + (props.size.unwrap_or(32) as u64 + 7) / 8);
+            },
+        );
+        used.sort_by_key(|r| r.start);
+
+        let areas = match self.address_block.as_deref() {
+            Some(blocks) if !blocks.is_empty() => blocks
+                .iter()
+                .map(|b| b.offset as u64..b.offset as u64 + b.size as u64)
+                .collect(),
+            _ => vec![core::ops::Range {
+                start: 0,
+                end: self.registers_end(),
+            }],
+        };
+        let mut gaps = Vec::new();
+        for area in areas {
+            let mut start = area.start;
+            for r in &used {
+                if r.end <= start || r.start >= area.end {
+                    continue;
+                }
+                if r.start > start {
+                    gaps.push(start..r.start);
+                }
+                start = start.max(r.end);
+            }
+            if start < area.end {
+                gaps.push(start..area.end);
+            }
+        }
+        gaps
+    }
+
+    /// Sort registers and clusters by address offset, recursing into clusters
+    pub fn sort_registers_by_offset(&mut self) {
+        if let Some(regs) = self.registers.as_mut() {
+            sort_children(regs, &|rc| rc.address_offset());
+        }
+    }
+
+    /// Sort registers and clusters by name, recursing into clusters
+    pub fn sort_registers_by_name(&mut self) {
+        if let Some(regs) = self.registers.as_mut() {
+            sort_children(regs, &|rc| rc.name().clone());
+        }
+    }
+
+    /// Compute an [`AddressBlock`] at offset 0 covering all registers and clusters of the peripheral.
+    ///
+    /// The size is rounded up to the next power of two.
+    pub fn generate_address_block(&self, usage: AddressBlockUsage) -> AddressBlock {
+        let end = self.registers_end();
+        let size = if end == 0 { 0 } else { end.next_power_of_two() };
+        Self::covering_block(size, usage)
+    }
+
+    /// Compute an [`AddressBlock`] at offset 0 covering all registers and clusters of the peripheral.
+    ///
+    /// The size is rounded up to a multiple of `granularity` bytes.
+    pub fn generate_address_block_aligned(
+        &self,
+        usage: AddressBlockUsage,
+        granularity: u32,
+    ) -> AddressBlock {
+        let granularity = granularity.max(1) as u64;
+        let size = (self.registers_end() + granularity - 1) / granularity * granularity;
+        Self::covering_block(size, usage)
+    }
+
+    /// Add a generated [`AddressBlock`] with `registers` usage if the peripheral has none
+    pub fn ensure_address_block(&mut self) {
+        if self.address_block.as_ref().map_or(true, Vec::is_empty) {
+            self.address_block = Some(vec![
+                self.generate_address_block(AddressBlockUsage::Registers)
+            ]);
+        }
+    }
+
+    fn registers_end(&self) -> u64 {
+        match &self.registers {
+            Some(regs) => children_end(regs, self.default_register_properties.size),
+            None => 0,
+        }
+    }
+
+    fn covering_block(size: u64, usage: AddressBlockUsage) -> AddressBlock {
+        AddressBlock {
+            offset: 0,
+            size: size.min(u32::MAX as u64) as u32,
+            usage,
+            protection: None,
+        }
+    }
 }
 
 impl Peripheral {
     /// Validate the [`Peripheral`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        if let Self::Array(_, dim) = self {
-            dim.validate(lvl)?;
+        super::first_error(self.validate_collect(lvl))
+    }
+
+    /// Validate the [`Peripheral`] recursively, collecting all errors instead of stopping at the first one
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
+        if let Self::Array(info, dim) = self {
+            errors.extend(super::validate_array(&info.name, dim, lvl));
+            if lvl.is_strict() && dim.dim > 1 {
+                let span = info.registers_end();
+                if (dim.dim_increment as u64) < span {
+                    errors.push(
+                        Error::DimIncrementTooSmall(info.name.clone(), dim.dim_increment, span)
+                            .into(),
+                    );
+                }
+            }
+        }
+        errors.extend(self.deref().validate_collect(lvl));
+        errors
+    }
+
+    /// Merges a partial peripheral, e.g. of a patch file, into this peripheral.
+    ///
+    /// Values set in `other` replace the values of `self`. Registers, clusters and interrupts
+    /// of `other` modify the ones with the same name or are appended,
+    /// the rest of `self` is left untouched, also if `other` is `derivedFrom` another peripheral.
+    ///
+    /// `name` and `baseAddress` are always present in `other`, so they are kept as they are:
+    /// use [`PeripheralInfo::modify_from`] to change them.
+    pub fn merge(&mut self, other: &Peripheral, lvl: ValidateLevel) -> Result<(), SvdError> {
+        let mut builder = PeripheralInfoBuilder::from((**other).clone());
+        builder.name = None;
+        builder.base_address = None;
+        builder.derived_from = None;
+        builder.registers = None;
+        builder.interrupt = None;
+        if other.comments.is_empty() {
+            builder.comments = None;
+        }
+        if other.literals.is_empty() {
+            builder.literals = None;
+        }
+        self.modify_from(builder, lvl)?;
+        if other.derived_from.is_some() {
+            self.derived_from = other.derived_from.clone();
+        }
+        if let Some(registers) = &other.registers {
+            merge_children(self.registers.get_or_insert_with(Vec::new), registers, lvl)?;
+        }
+        for interrupt in &other.interrupt {
+            match self.get_mut_interrupt(&interrupt.name) {
+                Some(existing) => *existing = interrupt.clone(),
+                None => self.interrupt.push(interrupt.clone()),
+            }
+        }
+        (**self).validate(lvl)?;
+        merge_dim(self, other, lvl)
+    }
+
+    /// Returns the peripheral instances, each array element at `baseAddress + i * dimIncrement`
+    pub fn expanded(&self) -> Vec<PeripheralInfo> {
+        self.expanded_with(&CmsisMangler)
+    }
+
+    /// Same as [`Peripheral::expanded`], but array elements are named with `mangler`
+    pub fn expanded_with(&self, mangler: &(impl NameMangler + ?Sized)) -> Vec<PeripheralInfo> {
+        match self {
+            Self::Single(info) => vec![info.clone()],
+            Self::Array(info, dim) => expand_with(info, dim, mangler).collect(),
         }
-        self.deref().validate_all(lvl)
     }
 }
 
@@ -1,10 +1,12 @@
+use super::prelude::*;
 use super::{
-    array::{descriptions, names},
-    Access, BuildError, Description, DimElement, EmptyToNone, Field, MaybeArray,
-    ModifiedWriteValues, Name, ReadAction, RegisterProperties, SvdError, ValidateLevel,
-    WriteConstraint,
+    array::{descriptions, element_description, element_name, matches_name, merge_dim, names},
+    field, Access, BuildError, CmsisMangler, DataType, Description, DimElement, EmptyToNone, Field,
+    FieldInfo, Literals, MaybeArray, ModifiedWriteValues, Name, ReadAction, RegisterProperties,
+    SvdError, ValidateLevel, WriteConstraint,
 };
-use std::ops::Deref;
+use alloc::borrow::Cow;
+use core::ops::Deref;
 
 /// A single register or array of registers. A register is a named, programmable resource that belongs to a [peripheral](crate::Peripheral).
 pub type Register = MaybeArray<RegisterInfo>;
@@ -15,6 +17,15 @@ pub enum Error {
     /// Register had no fields, but specified a `<fields>` tag.
     #[error("Register have `fields` tag, but it is empty")]
     EmptyFields,
+    /// Elements of the register array overlap
+    #[error("dimIncrement {0:#x} is smaller than the register size of {1} bits")]
+    DimIncrementTooSmall(u32, u32),
+    /// Several fields of the register have the same name
+    #[error("Field `{0}` is defined more than once")]
+    DuplicateField(String),
+    /// Two fields share some bits, only reported by [`RegisterInfo::validate_collect`]
+    #[error("Field `{0}` overlaps field `{1}`")]
+    OverlappingFields(String, String),
 }
 
 /// A register is a named, programmable resource that belongs to a [peripheral](crate::Peripheral).
@@ -88,6 +99,13 @@ pub struct RegisterInfo {
     )]
     pub read_action: Option<ReadAction>,
 
+    /// C data type of the register in the device header
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub data_type: Option<DataType>,
+
     /// `None` indicates that the `<fields>` node is not present
     #[cfg_attr(
         feature = "serde",
@@ -102,14 +120,43 @@ pub struct RegisterInfo {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub derived_from: Option<String>,
+
+    /// Comments preceding the element in the SVD file
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub comments: Vec<String>,
+
+    /// Numbers as written in the SVD file
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub literals: Literals,
+}
+
+/// Default name prefixes of registers and fields which only reserve space
+pub const DEFAULT_PADDING_PREFIXES: &[&str] = &["RESERVED", "RSVD"];
+
+/// Returns true if `name` is one of `prefixes` followed only by digits and `_`,
+/// ignoring case and array placeholders
+pub(crate) fn is_padding_name(name: &str, prefixes: &[&str]) -> bool {
+    let name = name.replace("[%s]", "").replace("%s", "").to_uppercase();
+    prefixes.iter().any(|prefix| {
+        name.strip_prefix(&prefix.to_uppercase())
+            .map_or(false, |rest| {
+                rest.chars().all(|c| c.is_ascii_digit() || c == '_')
+            })
+    })
 }
 
-/// Return iterator over address offsets of each register in array
+/// Return iterator over address offsets of each register in array, wrapping around `u32::MAX`
 pub fn address_offsets<'a>(
     info: &'a RegisterInfo,
     dim: &'a DimElement,
 ) -> impl Iterator<Item = u32> + 'a {
-    (0..dim.dim).map(move |i| info.address_offset + i * dim.dim_increment)
+    (0..dim.dim).map(move |i| {
+        info.address_offset
+            .wrapping_add(i.wrapping_mul(dim.dim_increment))
+    })
 }
 
 /// Extract `RegisterInfo` items from array
@@ -128,11 +175,64 @@ pub fn expand<'a>(
             info.address_offset = address_offset;
             info.display_name = info
                 .display_name
-                .map(|d| d.replace("[%s]", &idx).replace("%s", &idx));
+                .map(|d| DimElement::replace_placeholder(&d, &idx));
             info
         })
 }
 
+/// Element of a register array, which borrows the base [`RegisterInfo`]
+/// and computes its name and description on demand
+///
+/// Created by [`Register::expanded_lazy`].
+#[derive(Clone, Debug)]
+pub struct ExpandedRegister<'a> {
+    info: &'a RegisterInfo,
+    element: Option<(&'a DimElement, Cow<'a, str>)>,
+    address_offset: u32,
+}
+
+impl<'a> ExpandedRegister<'a> {
+    /// Base register description shared by all elements
+    pub fn info(&self) -> &'a RegisterInfo {
+        self.info
+    }
+    /// Index of the element in the array, `None` for single registers
+    pub fn index(&self) -> Option<&str> {
+        self.element.as_ref().map(|(_, i)| i.as_ref())
+    }
+    /// Address offset of the element
+    pub fn address_offset(&self) -> u32 {
+        self.address_offset
+    }
+    /// Name of the element
+    pub fn name(&self) -> Cow<'a, str> {
+        match &self.element {
+            Some((dim, i)) => element_name(self.info, dim, i, &CmsisMangler).into(),
+            None => self.info.name.as_str().into(),
+        }
+    }
+    /// Description of the element
+    pub fn description(&self) -> Option<Cow<'a, str>> {
+        match &self.element {
+            Some((dim, i)) => element_description(self.info, dim, i).map(Into::into),
+            None => self.info.description.as_deref().map(Into::into),
+        }
+    }
+    /// Clone into a [`RegisterInfo`] as returned by [`expand`]
+    pub fn cloned(&self) -> RegisterInfo {
+        let mut info = self.info.clone();
+        if let Some((_, i)) = &self.element {
+            info.name = self.name().into_owned();
+            info.description = self.description().map(Cow::into_owned);
+            info.address_offset = self.address_offset;
+            info.display_name = info
+                .display_name
+                .map(|d| DimElement::replace_placeholder(&d, i));
+        }
+        info
+    }
+}
+
 /// Builder for [`RegisterInfo`]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct RegisterInfoBuilder {
@@ -146,8 +246,12 @@ pub struct RegisterInfoBuilder {
     modified_write_values: Option<ModifiedWriteValues>,
     write_constraint: Option<WriteConstraint>,
     read_action: Option<ReadAction>,
+    data_type: Option<DataType>,
     fields: Option<Vec<Field>>,
     derived_from: Option<String>,
+    comments: Option<Vec<String>>,
+    literals: Option<Literals>,
+    dim: Option<DimElement>,
 }
 
 impl From<RegisterInfo> for RegisterInfoBuilder {
@@ -163,8 +267,12 @@ impl From<RegisterInfo> for RegisterInfoBuilder {
             modified_write_values: r.modified_write_values,
             write_constraint: r.write_constraint,
             read_action: r.read_action,
+            data_type: r.data_type,
             fields: r.fields,
             derived_from: r.derived_from,
+            comments: Some(r.comments),
+            literals: Some(r.literals),
+            dim: None,
         }
     }
 }
@@ -240,6 +348,11 @@ impl RegisterInfoBuilder {
         self.read_action = value;
         self
     }
+    /// Set the data type of the register.
+    pub fn data_type(mut self, value: Option<DataType>) -> Self {
+        self.data_type = value;
+        self
+    }
     /// Set the fields of the register.
     pub fn fields(mut self, value: Option<Vec<Field>>) -> Self {
         self.fields = value;
@@ -250,6 +363,32 @@ impl RegisterInfoBuilder {
         self.derived_from = value;
         self
     }
+    /// Set the comments preceding the element
+    pub fn comments(mut self, value: Vec<String>) -> Self {
+        self.comments = Some(value);
+        self
+    }
+    /// Set the numbers as written in the SVD file
+    pub fn literals(mut self, value: Literals) -> Self {
+        self.literals = Some(value);
+        self
+    }
+    /// Set the dimension of the register array.
+    ///
+    /// Only used by [`build_register`](Self::build_register) and [`Register::modify_from`].
+    pub fn dim(mut self, value: Option<DimElement>) -> Self {
+        self.dim = value;
+        self
+    }
+    /// Validate and build a [`Register`], an array if [`dim`](Self::dim) is set.
+    pub fn build_register(mut self, lvl: ValidateLevel) -> Result<Register, SvdError> {
+        let dim = self.dim.take();
+        let info = self.build(lvl)?;
+        match dim {
+            Some(dim) => info.into_array(dim, lvl),
+            None => Ok(info.single()),
+        }
+    }
     /// Validate and build a [`RegisterInfo`].
     pub fn build(self, lvl: ValidateLevel) -> Result<RegisterInfo, SvdError> {
         let reg = RegisterInfo {
@@ -267,8 +406,11 @@ impl RegisterInfoBuilder {
             modified_write_values: self.modified_write_values,
             write_constraint: self.write_constraint,
             read_action: self.read_action,
+            data_type: self.data_type,
             fields: self.fields,
             derived_from: self.derived_from,
+            comments: self.comments.unwrap_or_default(),
+            literals: self.literals.unwrap_or_default(),
         };
         reg.validate(lvl)?;
         Ok(reg)
@@ -288,6 +430,14 @@ impl RegisterInfo {
     pub const fn array(self, dim: DimElement) -> Register {
         Register::Array(self, dim)
     }
+    /// Construct validated [`Register`] array.
+    ///
+    /// Unlike [`array`](Self::array) checks that the name has a placeholder
+    /// and that `dimIncrement` is not smaller than the register `size`.
+    pub fn into_array(self, dim: DimElement, lvl: ValidateLevel) -> Result<Register, SvdError> {
+        check_array(&self, &dim, lvl)?;
+        Ok(Register::Array(self, dim))
+    }
     /// Modify an existing [`RegisterInfo`] based on a [builder](RegisterInfoBuilder).
     pub fn modify_from(
         &mut self,
@@ -312,6 +462,12 @@ impl RegisterInfo {
         if let Some(address_offset) = builder.address_offset {
             self.address_offset = address_offset;
         }
+        if let Some(comments) = builder.comments {
+            self.comments = comments;
+        }
+        if let Some(literals) = builder.literals {
+            self.literals = literals;
+        }
         if builder.derived_from.is_some() {
             self.derived_from = builder.derived_from;
             self.fields = None;
@@ -329,6 +485,9 @@ impl RegisterInfo {
             if builder.read_action.is_some() {
                 self.read_action = builder.read_action;
             }
+            if builder.data_type.is_some() {
+                self.data_type = builder.data_type;
+            }
             if builder.fields.is_some() {
                 self.fields = builder.fields.empty_to_none();
             }
@@ -337,6 +496,13 @@ impl RegisterInfo {
     }
     /// Validate the [`RegisterInfo`]
     pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        self.validate_element(lvl)?;
+        match self.check_fields(lvl).into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+    fn validate_element(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
         if !lvl.is_disabled() {
             if lvl.is_strict() {
                 super::check_dimable_name(&self.name, "name")?;
@@ -356,20 +522,56 @@ impl RegisterInfo {
                     return Err(Error::EmptyFields.into());
                 }
             }
+            if let Some(WriteConstraint::Range(constraint)) = self.write_constraint {
+                constraint.validate(lvl)?;
+            }
         }
         Ok(())
     }
+    /// Check the fields against the register size and each other, returning all errors
+    fn check_fields(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
+        let fields = match self.fields.as_deref() {
+            Some(fields) if self.derived_from.is_none() && !lvl.is_disabled() => fields,
+            _ => return errors,
+        };
+        if let Some(size) = self.properties.size {
+            for f in fields {
+                errors.extend(f.validate_register_size(size, lvl).err());
+            }
+        }
+        if lvl.is_strict() {
+            for name in super::duplicate_names(fields.iter().map(|f| f.name.as_str())) {
+                errors.push(Error::DuplicateField(name.into()).into());
+            }
+        }
+        errors
+    }
     /// Validate the [`RegisterInfo`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        self.properties.validate(lvl)?;
+        super::first_error(self.validate_collect(lvl))
+    }
+    /// Validate the [`RegisterInfo`] recursively, collecting all errors instead of stopping at the first one.
+    ///
+    /// On [`ValidateLevel::Strict`] it also reports the [overlapping fields](Self::overlapping_fields),
+    /// which are not checked by [`RegisterInfo::validate`].
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors: Vec<_> = self.properties.validate(lvl).err().into_iter().collect();
         for f in self.fields() {
-            f.validate_all(lvl)?;
+            errors.extend(f.validate_all(lvl).err().map(|e| e.at(&f.name)));
         }
-        self.validate(lvl)
+        errors.extend(self.validate_element(lvl).err());
+        errors.extend(self.check_fields(lvl));
+        if lvl.is_strict() && self.derived_from.is_none() {
+            for (f1, f2) in self.overlapping_fields() {
+                errors.push(Error::OverlappingFields(f1.name.clone(), f2.name.clone()).into());
+            }
+        }
+        errors
     }
 
     /// Returns iterator over child fields
-    pub fn fields(&self) -> std::slice::Iter<Field> {
+    pub fn fields(&self) -> core::slice::Iter<'_, Field> {
         match &self.fields {
             Some(fields) => fields.iter(),
             None => [].iter(),
@@ -377,31 +579,281 @@ impl RegisterInfo {
     }
 
     /// Returns mutable iterator over child fields
-    pub fn fields_mut(&mut self) -> std::slice::IterMut<Field> {
+    pub fn fields_mut(&mut self) -> core::slice::IterMut<'_, Field> {
         match &mut self.fields {
             Some(fields) => fields.iter_mut(),
             None => [].iter_mut(),
         }
     }
 
-    /// Get field by name
+    /// Get field by name, arrays are also found by element names
     pub fn get_field(&self, name: &str) -> Option<&Field> {
-        self.fields().find(|f| f.name == name)
+        self.fields()
+            .find(|f| f.name == name)
+            .or_else(|| self.fields().find(|f| matches_name(f, name)))
     }
 
     /// Get mutable field by name
     pub fn get_mut_field(&mut self, name: &str) -> Option<&mut Field> {
-        self.fields_mut().find(|f| f.name == name)
+        if self.fields().any(|f| f.name == name) {
+            self.fields_mut().find(|f| f.name == name)
+        } else {
+            self.fields_mut().find(|f| matches_name(f, name))
+        }
     }
+
+    /// Compute the register value stored after writing `written` when the register holds `current`.
+    ///
+    /// Each field follows its `modifiedWriteValues` and `access`, or the ones of the register
+    /// if not specified; read-only fields keep their value. Bits not covered by fields follow
+    /// the register ones. The register is assumed to be 32 bits wide if `size` is not specified.
+    pub fn apply_write(&self, current: u64, written: u64) -> u64 {
+        let size = self.properties.size.unwrap_or(32);
+        let access = self.properties.access;
+        let mwv = self.modified_write_values;
+        let mut value = if access == Some(Access::ReadOnly) {
+            current
+        } else {
+            mwv.unwrap_or_default().apply(current, written)
+        };
+        if size < 64 {
+            value &= (1 << size) - 1;
+        }
+
+        let mut apply = |f: &FieldInfo| {
+            let offset = f.bit_offset();
+            let mask = f.value_mask() << offset;
+            let v = f.apply_write_with(current >> offset, written >> offset, access, mwv);
+            value = (value & !mask) | ((v << offset) & mask);
+        };
+        for f in self.fields() {
+            match f {
+                Field::Single(info) => apply(info),
+                Field::Array(info, dim) => field::expand(info, dim).for_each(|f| apply(&f)),
+            }
+        }
+        value
+    }
+
+    /// Returns true if the register only reserves space, see [`is_padding_with`](Self::is_padding_with)
+    pub fn is_padding(&self) -> bool {
+        self.is_padding_with(DEFAULT_PADDING_PREFIXES)
+    }
+
+    /// Returns true if the register only reserves space.
+    ///
+    /// That is the case when the register name is one of `prefixes` followed only by
+    /// digits or `_` (like `RESERVED0`), ignoring case, and all fields of the register
+    /// (if any) are named alike.
+    pub fn is_padding_with(&self, prefixes: &[&str]) -> bool {
+        is_padding_name(&self.name, prefixes)
+            && self.fields().all(|f| is_padding_name(&f.name, prefixes))
+    }
+
+    /// Get the access of the register, inferred from its fields if not specified
+    ///
+    /// The rules are applied in order, considering only fields with explicit `access`:
+    ///
+    /// - the register `access` is returned if present;
+    /// - if there are no such fields, the default [`Access::ReadWrite`] is returned;
+    /// - if all fields have the same access, it is returned;
+    /// - if some field is readable and some field is writable, [`Access::ReadWrite`] is returned;
+    /// - otherwise all fields are write-only or write-once, so [`Access::WriteOnly`] is returned.
+    pub fn inferred_access(&self) -> Access {
+        if let Some(access) = self.properties.access {
+            return access;
+        }
+        let mut accesses = self.fields().filter_map(|f| f.access);
+        let first = match accesses.next() {
+            Some(first) => first,
+            None => return Access::default(),
+        };
+        let (mut same, mut read, mut write) = (true, first.can_read(), first.can_write());
+        for access in accesses {
+            same &= access == first;
+            read |= access.can_read();
+            write |= access.can_write();
+        }
+        if same {
+            first
+        } else if read && write {
+            Access::ReadWrite
+        } else {
+            Access::WriteOnly
+        }
+    }
+
+    /// Get the access of the register, inherited from `parent` if not specified,
+    /// [`Access::ReadWrite`] by default.
+    ///
+    /// `parent` are the register properties of the enclosing clusters, peripheral and device
+    /// with inheritance resolved, like [`Device::effective_properties`](crate::Device::effective_properties)
+    /// does. Unlike [`inferred_access`](Self::inferred_access) the fields are not considered.
+    pub fn effective_access(&self, parent: &RegisterProperties) -> Access {
+        self.properties.access.or(parent.access).unwrap_or_default()
+    }
+
+    /// Get the bits of the register described by fields, including all elements of field arrays
+    pub fn field_coverage(&self) -> u64 {
+        self.fields().fold(0, |mask, f| mask | field_mask(f))
+    }
+
+    /// Get the pairs of fields which share some bits, in order of appearance
+    pub fn overlapping_fields(&self) -> Vec<(&FieldInfo, &FieldInfo)> {
+        let fields: Vec<_> = self.fields().map(|f| (&**f, field_mask(f))).collect();
+        let mut overlaps = Vec::new();
+        for (i, (f1, mask1)) in fields.iter().enumerate() {
+            for (f2, mask2) in &fields[i + 1..] {
+                if mask1 & mask2 != 0 {
+                    overlaps.push((*f1, *f2));
+                }
+            }
+        }
+        overlaps
+    }
+}
+
+/// Bits of the register taken by `f`, by all elements for arrays
+fn field_mask(f: &Field) -> u64 {
+    let mask = |offset: u32| f.value_mask().checked_shl(offset).unwrap_or(0);
+    match f {
+        Field::Single(info) => mask(info.bit_offset()),
+        Field::Array(info, dim) => field::bit_offsets(info, dim).fold(0, |m, o| m | mask(o)),
+    }
+}
+
+fn check_array(info: &RegisterInfo, dim: &DimElement, lvl: ValidateLevel) -> Result<(), SvdError> {
+    dim.validate(lvl)?;
+    if !lvl.is_disabled() {
+        super::check_placeholder(&info.name, dim)?;
+        if let Some(size) = info.properties.size {
+            if dim.dim > 1 && (dim.dim_increment as u64) * 8 < size as u64 {
+                return Err(Error::DimIncrementTooSmall(dim.dim_increment, size).into());
+            }
+        }
+    }
+    Ok(())
 }
 
 impl Register {
+    /// Merges a partial register, e.g. of a patch file, into this register.
+    ///
+    /// Fields with the same name are merged, new fields are appended.
+    /// Comments and literals are kept if `other` has none.
+    /// `name` and `addressOffset` are always present in `other` and are kept.
+    pub(crate) fn merge(&mut self, other: &Register, lvl: ValidateLevel) -> Result<(), SvdError> {
+        let mut builder = RegisterInfoBuilder::from((**other).clone());
+        builder.name = None;
+        builder.address_offset = None;
+        builder.derived_from = None;
+        builder.fields = None;
+        if other.comments.is_empty() {
+            builder.comments = None;
+        }
+        if other.literals.is_empty() {
+            builder.literals = None;
+        }
+        self.modify_from(builder, lvl)?;
+        if other.derived_from.is_some() {
+            self.derived_from = other.derived_from.clone();
+        }
+        if let Some(fields) = &other.fields {
+            let own = self.fields.get_or_insert_with(Vec::new);
+            for f in fields {
+                match own.iter_mut().find(|own| own.name == f.name) {
+                    Some(existing) => existing.merge(f, lvl)?,
+                    None => own.push(f.clone()),
+                }
+            }
+        }
+        (**self).validate(lvl)?;
+        merge_dim(self, other, lvl)
+    }
+
+    /// Modify an existing [`Register`] based on a [builder](RegisterInfoBuilder).
+    ///
+    /// If the builder has a [`dim`](RegisterInfoBuilder::dim), single registers
+    /// are promoted to arrays and the dimension of arrays is replaced.
+    pub fn modify_from(
+        &mut self,
+        mut builder: RegisterInfoBuilder,
+        lvl: ValidateLevel,
+    ) -> Result<(), SvdError> {
+        let dim = builder.dim.take();
+        let info: &mut RegisterInfo = self;
+        info.modify_from(builder, lvl)?;
+        if let Some(dim) = dim {
+            check_array(info, &dim, lvl)?;
+            *self = Self::Array(info.clone(), dim);
+        }
+        Ok(())
+    }
+
+    /// Collapse an array into its first element, with substituted name and description.
+    ///
+    /// Single registers are returned as is.
+    pub fn into_single(self) -> Register {
+        match self {
+            Self::Single(_) => self,
+            Self::Array(info, dim) => {
+                let first = expand(&info, &dim).next();
+                Self::Single(first.unwrap_or(info))
+            }
+        }
+    }
+
+    /// Expand array into single registers with substituted names and descriptions
+    /// and the address offsets of the elements.
+    ///
+    /// Single registers are returned as is.
+    pub fn expanded(&self) -> Vec<RegisterInfo> {
+        match self {
+            Self::Single(info) => vec![info.clone()],
+            Self::Array(info, dim) => expand(info, dim).collect(),
+        }
+    }
+
+    /// Iterate over the array elements without cloning the [`RegisterInfo`].
+    ///
+    /// Single registers yield one element. Use [`ExpandedRegister::cloned`] to get owned values.
+    pub fn expanded_lazy(&self) -> impl Iterator<Item = ExpandedRegister<'_>> {
+        let (info, dim) = match self {
+            Self::Single(info) => (info, None),
+            Self::Array(info, dim) => (info, Some(dim)),
+        };
+        let single = match dim {
+            None => Some(ExpandedRegister {
+                info,
+                element: None,
+                address_offset: info.address_offset,
+            }),
+            Some(_) => None,
+        };
+        let elements = dim.into_iter().flat_map(move |dim| {
+            dim.indexes()
+                .zip(address_offsets(info, dim))
+                .map(move |(i, address_offset)| ExpandedRegister {
+                    info,
+                    element: Some((dim, i)),
+                    address_offset,
+                })
+        });
+        single.into_iter().chain(elements)
+    }
+
     /// Validate the [`Register`] recursively
     pub fn validate_all(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
-        if let Self::Array(_, dim) = self {
-            dim.validate(lvl)?;
+        super::first_error(self.validate_collect(lvl))
+    }
+
+    /// Validate the [`Register`] recursively, collecting all errors instead of stopping at the first one
+    pub fn validate_collect(&self, lvl: ValidateLevel) -> Vec<SvdError> {
+        let mut errors = Vec::new();
+        if let Self::Array(info, dim) = self {
+            errors.extend(super::validate_array(&info.name, dim, lvl));
         }
-        self.deref().validate_all(lvl)
+        errors.extend(self.deref().validate_collect(lvl));
+        errors
     }
 }
 
@@ -1,4 +1,9 @@
-use super::{Cluster, Register};
+use super::prelude::*;
+use super::{
+    array::{matches_name, names},
+    cluster, field, register, Cluster, DimElement, Field, MaybeArray, Register, RegisterInfo,
+    RegisterProperties, SvdError, ValidateLevel,
+};
 
 /// A [cluster](crate::Cluster) or a [register](crate::Register)
 #[cfg_attr(
@@ -58,12 +63,340 @@ impl RegisterCluster {
     }
 }
 
+/// Returns the end of the memory area (in bytes, relative to the parent) occupied by `children`.
+///
+/// `default_size` is the register size in bits inherited from the parent element.
+/// Registers without any size information are assumed to be 32 bits wide.
+pub(crate) fn children_end(children: &[RegisterCluster], default_size: Option<u32>) -> u64 {
+    children
+        .iter()
+        .map(|rc| {
+            let (offset, len, dim) = match rc {
+                RegisterCluster::Register(r) => {
+                    let size = r.properties.size.or(default_size).unwrap_or(32);
+                    (r.address_offset, ((size + 7) / 8) as u64, dim_of(r))
+                }
+                RegisterCluster::Cluster(c) => {
+                    let size = c.default_register_properties.size.or(default_size);
+                    (c.address_offset, children_end(&c.children, size), dim_of(c))
+                }
+            };
+            let last = match dim {
+                Some((dim, increment)) => {
+                    offset as u64 + dim.saturating_sub(1) as u64 * increment as u64
+                }
+                None => offset as u64,
+            };
+            last + len
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Calls `f` for each register in `children` with arrays expanded.
+///
+/// `f` receives the names of the enclosing clusters, the address of the register
+/// relative to `base`, the register, the register as declared in `children`
+/// (the whole array for elements of register arrays) and the register properties
+/// resolved from the parent elements
+pub(crate) fn walk_registers<'a, F>(
+    children: &'a [RegisterCluster],
+    base: u64,
+    props: &RegisterProperties,
+    path: &mut Vec<String>,
+    f: &mut F,
+) where
+    F: FnMut(&[String], u64, &RegisterInfo, &'a RegisterInfo, &RegisterProperties),
+{
+    for rc in children {
+        match rc {
+            RegisterCluster::Register(Register::Single(info)) => {
+                let reg_props = info.properties.inherit_from(props);
+                f(
+                    path,
+                    base + info.address_offset as u64,
+                    info,
+                    info,
+                    &reg_props,
+                );
+            }
+            RegisterCluster::Register(Register::Array(array, dim)) => {
+                let reg_props = array.properties.inherit_from(props);
+                let addresses = element_addresses(base, array.address_offset, dim);
+                for (info, address) in register::expand(array, dim).zip(addresses) {
+                    f(path, address, &info, array, &reg_props);
+                }
+            }
+            RegisterCluster::Cluster(Cluster::Single(info)) => {
+                let props = info.default_register_properties.inherit_from(props);
+                path.push(info.name.clone());
+                let base = base + info.address_offset as u64;
+                walk_registers(&info.children, base, &props, path, f);
+                path.pop();
+            }
+            RegisterCluster::Cluster(Cluster::Array(array, dim)) => {
+                let props = array.default_register_properties.inherit_from(props);
+                let addresses = element_addresses(base, array.address_offset, dim);
+                for (name, base) in names(array, dim).zip(addresses) {
+                    path.push(name);
+                    walk_registers(&array.children, base, &props, path, f);
+                    path.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Addresses of the elements of an array at `offset` relative to `base`
+fn element_addresses(base: u64, offset: u32, dim: &DimElement) -> impl Iterator<Item = u64> + '_ {
+    (0..dim.dim as u64).map(move |i| base + offset as u64 + i * dim.dim_increment as u64)
+}
+
+/// Joins the names of enclosing clusters and the register name with `.`
+pub(crate) fn join_path(path: &[String], name: &str) -> String {
+    let mut full = String::new();
+    for p in path {
+        full.push_str(p);
+        full.push('.');
+    }
+    full.push_str(name);
+    full
+}
+
+/// Index of the register or cluster named `name` or with an array element named `name`,
+/// exact names take precedence over array elements
+fn find_child(children: &[RegisterCluster], name: &str, cluster: bool) -> Option<usize> {
+    let candidates = || {
+        children
+            .iter()
+            .enumerate()
+            .filter(move |(_, rc)| matches!(rc, RegisterCluster::Cluster(_)) == cluster)
+    };
+    candidates()
+        .find(|(_, rc)| rc.name() == name)
+        .or_else(|| {
+            candidates().find(|(_, rc)| match rc {
+                RegisterCluster::Register(r) => matches_name(r, name),
+                RegisterCluster::Cluster(c) => matches_name(c, name),
+            })
+        })
+        .map(|(i, _)| i)
+}
+
+/// Finds register or cluster by `name` in `children`,
+/// registers and clusters inside of clusters are found by `CLUSTER.NAME` paths
+fn get_child<'a>(
+    children: &'a [RegisterCluster],
+    name: &str,
+    cluster: bool,
+) -> Option<&'a RegisterCluster> {
+    if let Some(i) = find_child(children, name, cluster) {
+        return Some(&children[i]);
+    }
+    let (first, rest) = name.split_once('.')?;
+    match &children[find_child(children, first, true)?] {
+        RegisterCluster::Cluster(c) => get_child(&c.children, rest, cluster),
+        RegisterCluster::Register(_) => None,
+    }
+}
+
+fn get_mut_child<'a>(
+    children: &'a mut [RegisterCluster],
+    name: &str,
+    cluster: bool,
+) -> Option<&'a mut RegisterCluster> {
+    if let Some(i) = find_child(children, name, cluster) {
+        return Some(&mut children[i]);
+    }
+    let (first, rest) = name.split_once('.')?;
+    match &mut children[find_child(children, first, true)?] {
+        RegisterCluster::Cluster(c) => get_mut_child(&mut c.children, rest, cluster),
+        RegisterCluster::Register(_) => None,
+    }
+}
+
+pub(crate) fn get_register<'a>(
+    children: &'a [RegisterCluster],
+    name: &str,
+) -> Option<&'a Register> {
+    match get_child(children, name, false)? {
+        RegisterCluster::Register(r) => Some(r),
+        RegisterCluster::Cluster(_) => None,
+    }
+}
+
+/// Finds register by `name` like [`get_register`] and returns its properties,
+/// inherited from the clusters on the way and `props` of the parent
+pub(crate) fn get_register_properties(
+    children: &[RegisterCluster],
+    name: &str,
+    props: &RegisterProperties,
+) -> Option<RegisterProperties> {
+    if let Some(i) = find_child(children, name, false) {
+        return match &children[i] {
+            RegisterCluster::Register(r) => Some(r.properties.inherit_from(props)),
+            RegisterCluster::Cluster(_) => None,
+        };
+    }
+    let (first, rest) = name.split_once('.')?;
+    match &children[find_child(children, first, true)?] {
+        RegisterCluster::Cluster(c) => {
+            let props = c.default_register_properties.inherit_from(props);
+            get_register_properties(&c.children, rest, &props)
+        }
+        RegisterCluster::Register(_) => None,
+    }
+}
+
+pub(crate) fn get_mut_register<'a>(
+    children: &'a mut [RegisterCluster],
+    name: &str,
+) -> Option<&'a mut Register> {
+    match get_mut_child(children, name, false)? {
+        RegisterCluster::Register(r) => Some(r),
+        RegisterCluster::Cluster(_) => None,
+    }
+}
+
+pub(crate) fn get_cluster<'a>(children: &'a [RegisterCluster], name: &str) -> Option<&'a Cluster> {
+    match get_child(children, name, true)? {
+        RegisterCluster::Cluster(c) => Some(c),
+        RegisterCluster::Register(_) => None,
+    }
+}
+
+pub(crate) fn get_mut_cluster<'a>(
+    children: &'a mut [RegisterCluster],
+    name: &str,
+) -> Option<&'a mut Cluster> {
+    match get_mut_child(children, name, true)? {
+        RegisterCluster::Cluster(c) => Some(c),
+        RegisterCluster::Register(_) => None,
+    }
+}
+
+/// Merges `other` into `children`.
+///
+/// Registers and clusters with the same name are modified with the values of `other`,
+/// fields of registers and children of clusters are merged recursively.
+/// The rest of `other` is appended.
+pub(crate) fn merge_children(
+    children: &mut Vec<RegisterCluster>,
+    other: &[RegisterCluster],
+    lvl: ValidateLevel,
+) -> Result<(), SvdError> {
+    for rc in other {
+        let existing = children.iter_mut().find(|c| {
+            matches!(
+                (&**c, rc),
+                (RegisterCluster::Register(_), RegisterCluster::Register(_))
+                    | (RegisterCluster::Cluster(_), RegisterCluster::Cluster(_))
+            ) && c.name() == rc.name()
+        });
+        match (existing, rc) {
+            (Some(RegisterCluster::Register(r)), RegisterCluster::Register(o)) => {
+                r.merge(o, lvl)?
+            }
+            (Some(RegisterCluster::Cluster(c)), RegisterCluster::Cluster(o)) => c.merge(o, lvl)?,
+            _ => children.push(rc.clone()),
+        }
+    }
+    Ok(())
+}
+
+/// Removes padding registers from `children` and their descendants
+pub(crate) fn prune_padding(children: &mut Vec<RegisterCluster>, prefixes: &[&str]) {
+    children.retain_mut(|rc| match rc {
+        RegisterCluster::Register(r) => !r.is_padding_with(prefixes),
+        RegisterCluster::Cluster(c) => {
+            prune_padding(&mut c.children, prefixes);
+            true
+        }
+    });
+}
+
+/// Replaces arrays of registers, clusters and fields in `children` with their single elements
+pub(crate) fn expand_arrays(children: &mut Vec<RegisterCluster>) {
+    for rc in core::mem::take(children) {
+        match rc {
+            RegisterCluster::Register(mut r) => {
+                if let Some(fields) = r.fields.as_mut() {
+                    for f in core::mem::take(fields) {
+                        match f {
+                            Field::Single(_) => fields.push(f),
+                            Field::Array(info, dim) => {
+                                fields.extend(field::expand(&info, &dim).map(Field::Single))
+                            }
+                        }
+                    }
+                }
+                match r {
+                    Register::Single(_) => children.push(r.into()),
+                    Register::Array(info, dim) => {
+                        children.extend(register::expand(&info, &dim).map(|r| r.single().into()))
+                    }
+                }
+            }
+            RegisterCluster::Cluster(mut c) => {
+                expand_arrays(&mut c.children);
+                match c {
+                    Cluster::Single(_) => children.push(c.into()),
+                    Cluster::Array(info, dim) => {
+                        children.extend(cluster::expand(&info, &dim).map(|c| c.single().into()))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sorts `children` and children of nested clusters with a stable sort, arrays are kept as is
+pub(crate) fn sort_children<K: Ord>(
+    children: &mut [RegisterCluster],
+    key: &impl Fn(&RegisterCluster) -> K,
+) {
+    children.sort_by_key(key);
+    for rc in children {
+        if let RegisterCluster::Cluster(c) = rc {
+            sort_children(&mut c.children, key);
+        }
+    }
+}
+
+/// Sums `count` of all registers in `children`, multiplied with array sizes if `expand_arrays`
+pub(crate) fn count_registers(
+    children: &[RegisterCluster],
+    expand_arrays: bool,
+    count: &impl Fn(&Register) -> usize,
+) -> usize {
+    let times = |dim: Option<(u32, u32)>| match dim {
+        Some((dim, _)) if expand_arrays => dim as usize,
+        _ => 1,
+    };
+    children
+        .iter()
+        .map(|rc| match rc {
+            RegisterCluster::Register(r) => times(dim_of(r)) * count(r),
+            RegisterCluster::Cluster(c) => {
+                times(dim_of(c)) * count_registers(&c.children, expand_arrays, count)
+            }
+        })
+        .sum()
+}
+
+fn dim_of<T>(item: &MaybeArray<T>) -> Option<(u32, u32)> {
+    match item {
+        MaybeArray::Single(_) => None,
+        MaybeArray::Array(_, dim) => Some((dim.dim, dim.dim_increment)),
+    }
+}
+
 /// Register iterator
 pub struct RegisterIter<'a> {
-    pub(crate) all: std::slice::Iter<'a, RegisterCluster>,
+    pub(crate) all: core::slice::Iter<'a, RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for RegisterIter<'a> {
+impl<'a> core::iter::Iterator for RegisterIter<'a> {
     type Item = &'a Register;
     fn next(&mut self) -> Option<Self::Item> {
         match self.all.next() {
@@ -76,10 +409,10 @@ impl<'a> std::iter::Iterator for RegisterIter<'a> {
 
 /// Mutable register iterator
 pub struct RegisterIterMut<'a> {
-    pub(crate) all: std::slice::IterMut<'a, RegisterCluster>,
+    pub(crate) all: core::slice::IterMut<'a, RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for RegisterIterMut<'a> {
+impl<'a> core::iter::Iterator for RegisterIterMut<'a> {
     type Item = &'a mut Register;
     fn next(&mut self) -> Option<Self::Item> {
         match self.all.next() {
@@ -92,10 +425,10 @@ impl<'a> std::iter::Iterator for RegisterIterMut<'a> {
 
 /// Cluster iterator
 pub struct ClusterIter<'a> {
-    pub(crate) all: std::slice::Iter<'a, RegisterCluster>,
+    pub(crate) all: core::slice::Iter<'a, RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for ClusterIter<'a> {
+impl<'a> core::iter::Iterator for ClusterIter<'a> {
     type Item = &'a Cluster;
     fn next(&mut self) -> Option<Self::Item> {
         match self.all.next() {
@@ -108,10 +441,10 @@ impl<'a> std::iter::Iterator for ClusterIter<'a> {
 
 /// Mutable cluster iterator
 pub struct ClusterIterMut<'a> {
-    pub(crate) all: std::slice::IterMut<'a, RegisterCluster>,
+    pub(crate) all: core::slice::IterMut<'a, RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for ClusterIterMut<'a> {
+impl<'a> core::iter::Iterator for ClusterIterMut<'a> {
     type Item = &'a mut Cluster;
     fn next(&mut self) -> Option<Self::Item> {
         match self.all.next() {
@@ -127,7 +460,7 @@ pub struct AllRegistersIter<'a> {
     pub(crate) rem: Vec<&'a RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for AllRegistersIter<'a> {
+impl<'a> core::iter::Iterator for AllRegistersIter<'a> {
     type Item = &'a Register;
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(b) = self.rem.pop() {
@@ -151,7 +484,7 @@ pub struct AllRegistersIterMut<'a> {
     pub(crate) rem: Vec<&'a mut RegisterCluster>,
 }
 
-impl<'a> std::iter::Iterator for AllRegistersIterMut<'a> {
+impl<'a> core::iter::Iterator for AllRegistersIterMut<'a> {
     type Item = &'a mut Register;
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(b) = self.rem.pop() {
@@ -169,3 +502,21 @@ impl<'a> std::iter::Iterator for AllRegistersIterMut<'a> {
         None
     }
 }
+
+/// Validate `children` recursively, collecting all errors with paths relative to the parent
+pub(crate) fn validate_collect(children: &[RegisterCluster], lvl: ValidateLevel) -> Vec<SvdError> {
+    let mut errors = Vec::new();
+    for rc in children {
+        let (name, child_errors) = match rc {
+            RegisterCluster::Register(r) => (&r.name, r.validate_collect(lvl)),
+            RegisterCluster::Cluster(c) => (&c.name, c.validate_collect(lvl)),
+        };
+        errors.extend(child_errors.into_iter().map(|e| e.at(name)));
+    }
+    errors
+}
+
+/// Names of registers and clusters which are defined more than once in `children`
+pub(crate) fn duplicate_names(children: &[RegisterCluster]) -> Vec<&str> {
+    super::duplicate_names(children.iter().map(|rc| rc.name().as_str()))
+}
@@ -64,6 +64,16 @@ impl RegisterProperties {
     pub fn new() -> Self {
         Self::default()
     }
+    /// Fill properties which are not specified with the ones of `parent`.
+    pub fn inherit_from(&self, parent: &Self) -> Self {
+        Self {
+            size: self.size.or(parent.size),
+            access: self.access.or(parent.access),
+            protection: self.protection.or(parent.protection),
+            reset_value: self.reset_value.or(parent.reset_value),
+            reset_mask: self.reset_mask.or(parent.reset_mask),
+        }
+    }
     /// Modify an existing [`RegisterProperties`] based on another.
     pub fn modify_from(
         &mut self,
@@ -133,7 +143,7 @@ pub(crate) fn check_reset_value(
     mask: Option<u64>,
     lvl: ValidateLevel,
 ) -> Result<(), Error> {
-    const MAX_BITS: u32 = core::u64::MAX.count_ones();
+    const MAX_BITS: u32 = u64::MAX.count_ones();
 
     if let (Some(size), Some(value)) = (size, value) {
         if MAX_BITS - value.leading_zeros() > size {
@@ -0,0 +1,276 @@
+use super::prelude::*;
+use super::{BuildError, Protection, SvdError, ValidateLevel};
+
+/// Errors for [`SauRegion::validate`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The limit of the region is below its base
+    #[error("SAU region limit {1:#x} is below its base {0:#x}")]
+    LimitBelowBase(u64, u64),
+}
+
+/// Configuration of the Security Attribution Unit (SAU) of Armv8-M processors
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SauRegionsConfig {
+    /// Whether the SAU is enabled
+    pub enabled: bool,
+
+    /// Security privilege of the memory when the SAU is disabled
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub protection_when_disabled: Option<Protection>,
+
+    /// Predefined SAU regions
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty", rename = "region")
+    )]
+    pub regions: Vec<SauRegion>,
+}
+
+/// Predefined region of the Security Attribution Unit
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SauRegion {
+    /// Whether the region is enabled
+    pub enabled: bool,
+
+    /// Name of the region
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub name: Option<String>,
+
+    /// Start address of the region
+    pub base: u64,
+
+    /// Last address of the region
+    pub limit: u64,
+
+    /// Security attribute of the region
+    pub access: SauAccess,
+}
+
+/// Security attribute of a [`SauRegion`]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SauAccess {
+    /// Non-secure
+    #[cfg_attr(feature = "serde", serde(rename = "n"))]
+    NonSecure,
+
+    /// Non-secure callable
+    #[cfg_attr(feature = "serde", serde(rename = "c"))]
+    NonSecureCallable,
+}
+
+impl SauAccess {
+    /// Parse a string into an [`SauAccess`] value, returning [`Option::None`] if the string is not valid.
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "n" => Some(Self::NonSecure),
+            "c" => Some(Self::NonSecureCallable),
+            _ => None,
+        }
+    }
+
+    /// Convert this [`SauAccess`] into a static string.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NonSecure => "n",
+            Self::NonSecureCallable => "c",
+        }
+    }
+}
+
+/// Builder for [`SauRegionsConfig`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SauRegionsConfigBuilder {
+    enabled: Option<bool>,
+    protection_when_disabled: Option<Protection>,
+    regions: Option<Vec<SauRegion>>,
+}
+
+impl From<SauRegionsConfig> for SauRegionsConfigBuilder {
+    fn from(c: SauRegionsConfig) -> Self {
+        Self {
+            enabled: Some(c.enabled),
+            protection_when_disabled: c.protection_when_disabled,
+            regions: Some(c.regions),
+        }
+    }
+}
+
+impl SauRegionsConfigBuilder {
+    /// Set whether the SAU is enabled, `true` by default
+    pub fn enabled(mut self, value: bool) -> Self {
+        self.enabled = Some(value);
+        self
+    }
+    /// Set the protection of the memory when the SAU is disabled
+    pub fn protection_when_disabled(mut self, value: Option<Protection>) -> Self {
+        self.protection_when_disabled = value;
+        self
+    }
+    /// Set the regions of the SAU
+    pub fn regions(mut self, value: Vec<SauRegion>) -> Self {
+        self.regions = Some(value);
+        self
+    }
+    /// Validate and build a [`SauRegionsConfig`].
+    pub fn build(self, lvl: ValidateLevel) -> Result<SauRegionsConfig, SvdError> {
+        let config = SauRegionsConfig {
+            enabled: self.enabled.unwrap_or(true),
+            protection_when_disabled: self.protection_when_disabled,
+            regions: self.regions.unwrap_or_default(),
+        };
+        config.validate(lvl)?;
+        Ok(config)
+    }
+}
+
+impl SauRegionsConfig {
+    /// Make a builder for [`SauRegionsConfig`]
+    pub fn builder() -> SauRegionsConfigBuilder {
+        SauRegionsConfigBuilder::default()
+    }
+    /// Modify an existing [`SauRegionsConfig`] based on a [builder](SauRegionsConfigBuilder).
+    pub fn modify_from(
+        &mut self,
+        builder: SauRegionsConfigBuilder,
+        lvl: ValidateLevel,
+    ) -> Result<(), SvdError> {
+        if let Some(enabled) = builder.enabled {
+            self.enabled = enabled;
+        }
+        if builder.protection_when_disabled.is_some() {
+            self.protection_when_disabled = builder.protection_when_disabled;
+        }
+        if let Some(regions) = builder.regions {
+            self.regions = regions;
+        }
+        self.validate(lvl)
+    }
+    /// Validate the [`SauRegionsConfig`] and its regions
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        for region in &self.regions {
+            region.validate(lvl)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`SauRegion`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SauRegionBuilder {
+    enabled: Option<bool>,
+    name: Option<String>,
+    base: Option<u64>,
+    limit: Option<u64>,
+    access: Option<SauAccess>,
+}
+
+impl From<SauRegion> for SauRegionBuilder {
+    fn from(r: SauRegion) -> Self {
+        Self {
+            enabled: Some(r.enabled),
+            name: r.name,
+            base: Some(r.base),
+            limit: Some(r.limit),
+            access: Some(r.access),
+        }
+    }
+}
+
+impl SauRegionBuilder {
+    /// Set whether the region is enabled, `true` by default
+    pub fn enabled(mut self, value: bool) -> Self {
+        self.enabled = Some(value);
+        self
+    }
+    /// Set the name of the region
+    pub fn name(mut self, value: Option<String>) -> Self {
+        self.name = value;
+        self
+    }
+    /// Set the start address of the region
+    pub fn base(mut self, value: u64) -> Self {
+        self.base = Some(value);
+        self
+    }
+    /// Set the last address of the region
+    pub fn limit(mut self, value: u64) -> Self {
+        self.limit = Some(value);
+        self
+    }
+    /// Set the security attribute of the region
+    pub fn access(mut self, value: SauAccess) -> Self {
+        self.access = Some(value);
+        self
+    }
+    /// Validate and build a [`SauRegion`].
+    pub fn build(self, lvl: ValidateLevel) -> Result<SauRegion, SvdError> {
+        let region = SauRegion {
+            enabled: self.enabled.unwrap_or(true),
+            name: self.name,
+            base: self
+                .base
+                .ok_or_else(|| BuildError::Uninitialized("base".to_string()))?,
+            limit: self
+                .limit
+                .ok_or_else(|| BuildError::Uninitialized("limit".to_string()))?,
+            access: self
+                .access
+                .ok_or_else(|| BuildError::Uninitialized("access".to_string()))?,
+        };
+        region.validate(lvl)?;
+        Ok(region)
+    }
+}
+
+impl SauRegion {
+    /// Make a builder for [`SauRegion`]
+    pub fn builder() -> SauRegionBuilder {
+        SauRegionBuilder::default()
+    }
+    /// Modify an existing [`SauRegion`] based on a [builder](SauRegionBuilder).
+    pub fn modify_from(
+        &mut self,
+        builder: SauRegionBuilder,
+        lvl: ValidateLevel,
+    ) -> Result<(), SvdError> {
+        if let Some(enabled) = builder.enabled {
+            self.enabled = enabled;
+        }
+        if builder.name.is_some() {
+            self.name = builder.name;
+        }
+        if let Some(base) = builder.base {
+            self.base = base;
+        }
+        if let Some(limit) = builder.limit {
+            self.limit = limit;
+        }
+        if let Some(access) = builder.access {
+            self.access = access;
+        }
+        self.validate(lvl)
+    }
+    /// Validate the [`SauRegion`]
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if !lvl.is_disabled() && self.limit < self.base {
+            return Err(Error::LimitBelowBase(self.base, self.limit).into());
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::SvdError;
+use super::{SvdError, ValidateLevel};
 
 /// Define constraints for writing values to a field
 #[cfg_attr(
@@ -40,7 +40,19 @@ pub enum Error {
 }
 
 impl WriteConstraintRange {
+    /// Validate the [`WriteConstraintRange`]
+    pub fn validate(&self, lvl: ValidateLevel) -> Result<(), SvdError> {
+        if lvl.is_strict() && self.min > self.max {
+            return Err(Error::ReversedRange(self.min, self.max).into());
+        }
+        Ok(())
+    }
     pub(crate) fn check_range(&self, range: core::ops::Range<u64>) -> Result<(), SvdError> {
+        for value in [self.min, self.max] {
+            if !range.contains(&value) {
+                return Err(Error::OutOfRange(value, range).into());
+            }
+        }
         Ok(())
     }
 }
@@ -1,5 +1,5 @@
-use super::run_test;
-use crate::svd::{AddressBlock, AddressBlockUsage, ValidateLevel};
+use super::{parse_with_config, run_test};
+use crate::svd::{AddressBlock, AddressBlockUsage, Protection, ValidateLevel};
 
 #[test]
 fn decode_encode() {
@@ -8,7 +8,7 @@ fn decode_encode() {
     encode_config.update("address_block_offset", "Dec");
     encode_config.update("address_block_size", "UpperHex8");
 
-    let tests = vec![(
+    let tests = [(
         AddressBlock::builder()
             .offset(0)
             .size(0x00000F00)
@@ -30,3 +30,33 @@ fn decode_encode() {
 
     run_test::<AddressBlock>(&tests[..], Some(parse_config), Some(encode_config));
 }
+
+#[test]
+fn protection() {
+    let tests = [(
+        AddressBlock::builder()
+            .offset(0)
+            .size(0x400)
+            .usage(AddressBlockUsage::Registers)
+            .protection(Some(Protection::Secure))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "<addressBlock>
+            <offset>0x0</offset>
+            <size>0x400</size>
+            <usage>registers</usage>
+            <protection>s</protection>
+        </addressBlock>",
+        "<addressBlock>
+            <offset>0x0</offset>
+            <size>0x400</size>
+            <usage>registers</usage>
+            <protection>s</protection>
+        </addressBlock>",
+    )];
+    run_test::<AddressBlock>(&tests[..], None, None);
+
+    let xml = tests[0].1.replace(">s<", ">x<");
+    let err = parse_with_config::<AddressBlock>(&xml, &Default::default()).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid protection variant, found x");
+}
@@ -1,3 +1,4 @@
+use crate::device_xml;
 use svd_parser as svd;
 
 #[test]
@@ -14,3 +15,171 @@ fn arm_sample_faulty() {
         panic!()
     }
 }
+
+#[test]
+fn error_position() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>foo</size>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let err = svd::parse(&xml).unwrap_err();
+    let e = err.downcast_ref::<svd::SVDErrorAt>().unwrap();
+    let pos = e.text_pos().unwrap();
+    assert_eq!((pos.row, pos.col), (16, 11));
+    assert_eq!(
+        e.xml_path(),
+        Some("device/peripherals/peripheral/registers/register/size")
+    );
+    assert!(
+        e.to_string()
+            .ends_with(" at 16:11 (device/peripherals/peripheral/registers/register/size)"),
+        "{e}"
+    );
+}
+
+#[test]
+fn parse_collect() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>foo</size>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+          <fields>
+            <field>
+              <name>BUSY</name>
+              <bitRange>[0:0]</bitRange>
+              <access>read-maybe</access>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let (device, errors) = svd::parse_collect(&xml);
+    let errors: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        [
+            "Failed to parse `invalid digit found in string` at 16:11 \
+             (device/peripherals/peripheral/registers/register[1]/size)",
+            "unknown access variant 'read-maybe' found at 25:15 \
+             (device/peripherals/peripheral/registers/register[2]/fields/field/access)"
+        ]
+    );
+    let device = device.unwrap();
+    let timer = &device.peripherals[0];
+    assert_eq!(timer.registers().count(), 2);
+    let cr = timer.get_register("CR").unwrap();
+    assert_eq!(cr.properties.size, None);
+    let busy = timer.get_register("SR").unwrap().get_field("BUSY").unwrap();
+    assert_eq!(busy.access, None);
+
+    let (device, errors) = svd::parse_collect(&xml.replace("device", "dev"));
+    assert!(device.is_none());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error().kind(), "MissingTag");
+}
+
+#[test]
+fn parse_collect_skips_broken_register() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40001000</baseAddress>
+    </peripheral>"#,
+    );
+    let (device, errors) = svd::parse_collect(&xml);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].xml_path(),
+        Some("device/peripherals/peripheral[1]/registers/register[1]")
+    );
+    let device = device.unwrap();
+    let names: Vec<_> = device.peripherals.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["TIMER", "UART"]);
+    let registers: Vec<_> = device.peripherals[0]
+        .registers()
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(registers, ["SR"]);
+}
+
+#[test]
+fn parse_collect_paths_of_sibling_errors() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x8</addressOffset>
+          <size>wide</size>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let (device, errors) = svd::parse_collect(&xml);
+    let paths: Vec<_> = errors.iter().map(|e| e.xml_path().unwrap()).collect();
+    assert_eq!(
+        paths,
+        [
+            "device/peripherals/peripheral/registers/register[1]",
+            "device/peripherals/peripheral/registers/register[3]/size",
+        ]
+    );
+    assert!(
+        errors[1]
+            .to_string()
+            .ends_with(" at 23:11 (device/peripherals/peripheral/registers/register[3]/size)"),
+        "{}",
+        errors[1]
+    );
+    let device = device.unwrap();
+    let registers: Vec<_> = device.peripherals[0]
+        .registers()
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(registers, ["SR", "DR"]);
+}
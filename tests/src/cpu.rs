@@ -1,15 +1,16 @@
-use super::run_test;
-use crate::svd::{Cpu, Endian, ValidateLevel};
+use super::{parse_with_config, run_test};
+use crate::svd::{Cpu, Endian, Protection, SauAccess, SauRegion, SauRegionsConfig, ValidateLevel};
+use svd_parser::Config;
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         Cpu::builder()
             .name("EFM32JG12B500F512GM48".to_string())
             .revision("5.1.1".to_string())
             .endian(Endian::Little)
             .mpu_present(true)
-            .fpu_present(true)
+            .fpu_present(Some(true))
             .nvic_priority_bits(8)
             .has_vendor_systick(false)
             .build(ValidateLevel::Strict)
@@ -40,3 +41,165 @@ fn decode_encode() {
 
     run_test::<Cpu>(&tests[..], None, None);
 }
+
+#[test]
+fn cpu_names() {
+    let cpu = |name: &str| {
+        Cpu::builder()
+            .name(name.to_string())
+            .revision("r0p0".to_string())
+            .endian(Endian::Little)
+            .mpu_present(false)
+            .nvic_priority_bits(3)
+            .has_vendor_systick(false)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    let xml = |name: &str| {
+        format!(
+            "
+                <cpu>
+                    <name>{name}</name>
+                    <revision>r0p0</revision>
+                    <endian>little</endian>
+                    <mpuPresent>false</mpuPresent>
+                    <nvicPrioBits>3</nvicPrioBits>
+                    <vendorSystickConfig>false</vendorSystickConfig>
+                </cpu>
+            "
+        )
+    };
+
+    let (custom, custom_xml) = (cpu("XCORE9000"), xml("XCORE9000"));
+    assert_eq!(custom.known_name(), None);
+    let (input, output) = (xml("Cortex-M0+"), xml("CM0PLUS"));
+    let tests = [
+        (custom, custom_xml.as_str(), custom_xml.as_str()),
+        (cpu("CM0PLUS"), input.as_str(), output.as_str()),
+    ];
+    run_test::<Cpu>(&tests[..], None, None);
+
+    assert_eq!(cpu("cm4").known_name(), Some("CM4"));
+    assert_eq!(cpu("Cortex-A53").known_name(), Some("CA53"));
+}
+
+#[test]
+fn sau_num_regions() {
+    let xml = "
+                <cpu>
+                    <name>CM33</name>
+                    <revision>r0p4</revision>
+                    <endian>little</endian>
+                    <mpuPresent>true</mpuPresent>
+                    <fpuPresent>true</fpuPresent>
+                    <nvicPrioBits>3</nvicPrioBits>
+                    <vendorSystickConfig>false</vendorSystickConfig>
+                    <sauNumRegions>2</sauNumRegions>
+                </cpu>
+            ";
+    let cpu = Cpu::builder()
+        .name("CM33".to_string())
+        .revision("r0p4".to_string())
+        .endian(Endian::Little)
+        .mpu_present(true)
+        .fpu_present(Some(true))
+        .nvic_priority_bits(3)
+        .has_vendor_systick(false)
+        .sau_num_regions(Some(2))
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    run_test::<Cpu>(&[(cpu, xml, xml)], None, None);
+
+    let regions = |n| {
+        let regions: String = (0..n)
+            .map(|i| {
+                format!(
+                    "<region enabled=\"true\" name=\"R{i}\"><base>0x{i}0000000</base><limit>0x{i}0000FFF</limit><access>n</access></region>"
+                )
+            })
+            .collect();
+        xml.replace(
+            "</cpu>",
+            &format!("<sauRegionsConfig enabled=\"true\">{regions}</sauRegionsConfig></cpu>"),
+        )
+    };
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+    assert!(parse_with_config::<Cpu>(&regions(2), &strict).is_ok());
+    let err = parse_with_config::<Cpu>(&regions(3), &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Cpu error: sauRegionsConfig declares 3 regions, but sauNumRegions is 2"
+    );
+    let cpu = parse_with_config::<Cpu>(&regions(3), &Config::default()).unwrap();
+    assert!(cpu.validate(ValidateLevel::Weak).is_ok());
+    // the model is checked, not only the parsed XML
+    assert!(cpu.validate(ValidateLevel::Strict).is_err());
+}
+
+#[test]
+fn sau_regions_config() {
+    let xml = "
+                <cpu>
+                    <name>CM33</name>
+                    <revision>r0p4</revision>
+                    <endian>little</endian>
+                    <mpuPresent>true</mpuPresent>
+                    <nvicPrioBits>3</nvicPrioBits>
+                    <vendorSystickConfig>false</vendorSystickConfig>
+                    <sauNumRegions>2</sauNumRegions>
+                    <sauRegionsConfig enabled=\"true\" protectionWhenDisabled=\"s\">
+                        <region enabled=\"true\" name=\"FLASH_NS\">
+                            <base>0x00040000</base>
+                            <limit>0x000FFFFF</limit>
+                            <access>n</access>
+                        </region>
+                        <region enabled=\"false\" name=\"NSC\">
+                            <base>0x0003FC00</base>
+                            <limit>0x0003FFFF</limit>
+                            <access>c</access>
+                        </region>
+                    </sauRegionsConfig>
+                </cpu>
+            ";
+    let region = |name: &str, base, limit, access| {
+        SauRegion::builder()
+            .name(Some(name.to_string()))
+            .base(base)
+            .limit(limit)
+            .access(access)
+    };
+    let cpu = Cpu::builder()
+        .name("CM33".to_string())
+        .revision("r0p4".to_string())
+        .endian(Endian::Little)
+        .mpu_present(true)
+        .nvic_priority_bits(3)
+        .has_vendor_systick(false)
+        .sau_num_regions(Some(2))
+        .sau_regions_config(Some(
+            SauRegionsConfig::builder()
+                .protection_when_disabled(Some(Protection::Secure))
+                .regions(vec![
+                    region("FLASH_NS", 0x40000, 0xFFFFF, SauAccess::NonSecure)
+                        .build(ValidateLevel::Strict)
+                        .unwrap(),
+                    region("NSC", 0x3FC00, 0x3FFFF, SauAccess::NonSecureCallable)
+                        .enabled(false)
+                        .build(ValidateLevel::Strict)
+                        .unwrap(),
+                ])
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        ))
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    run_test::<Cpu>(&[(cpu, xml, xml)], None, None);
+
+    let err = region("R", 0x2000, 0x1000, SauAccess::NonSecure)
+        .build(ValidateLevel::Weak)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`SauRegion error: SAU region limit 0x1000 is below its base 0x2000"
+    );
+}
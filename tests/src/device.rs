@@ -0,0 +1,1565 @@
+use crate::svd::{Access, BitRangeType, Device, RegisterProperties, ValidateLevel};
+use crate::{device_xml, device_xml_with};
+
+fn timer_device() -> String {
+    device_xml_with(
+        r#"
+  <size>32</size>
+  <access>read-only</access>"#,
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <cluster>
+          <name>CH</name>
+          <addressOffset>0x0</addressOffset>
+          <register>
+            <name>CR</name>
+            <addressOffset>0x0</addressOffset>
+            <fields>
+              <field>
+                <name>EN</name>
+                <bitRange>[31:0]</bitRange>
+              </field>
+            </fields>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>"#,
+    )
+}
+
+#[test]
+fn inherit_device_properties() {
+    let config = svd_parser::Config::default().expand_properties(true);
+    let device: Device = svd_parser::parse_with_config(&timer_device(), &config).unwrap();
+
+    let expected = RegisterProperties::new()
+        .size(Some(32))
+        .access(Some(Access::ReadOnly));
+    assert_eq!(device.default_register_properties(), &expected);
+
+    let timer = device.get_peripheral("TIMER").unwrap();
+    assert_eq!(timer.default_register_properties.size, None);
+    let cr = timer.all_registers().next().unwrap();
+    assert_eq!(cr.properties.size, Some(32));
+    let en = cr.get_field("EN").unwrap();
+    assert_eq!(en.bit_width(), 32);
+    assert_eq!(en.access, Some(Access::ReadOnly));
+}
+
+#[test]
+fn register_table() {
+    let xml = device_xml_with(
+        r#"
+  <size>32</size>
+  <resetValue>0</resetValue>"#,
+        r#"
+    <peripheral>
+      <name>UART1</name>
+      <baseAddress>0x40002000</baseAddress>
+      <access>read-write</access>
+      <registers>
+        <register>
+          <name>SR</name>
+          <description>Status register</description>
+          <addressOffset>0x0</addressOffset>
+          <access>read-only</access>
+        </register>
+        <register>
+          <dim>4</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>DR%s</name>
+          <addressOffset>0x10</addressOffset>
+          <size>16</size>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="UART1">
+      <name>UART0</name>
+      <baseAddress>0x40001000</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let table = device.register_table();
+    assert_eq!(table.len(), 10);
+
+    let first = &table[0];
+    assert_eq!(first.address, 0x40001000);
+    assert_eq!(first.peripheral, "UART0");
+    assert_eq!(first.name, "SR");
+    assert_eq!(first.size, Some(32));
+    assert_eq!(first.access, Some(Access::ReadOnly));
+    assert_eq!(first.reset_value, Some(0));
+    assert_eq!(first.description.as_deref(), Some("Status register"));
+
+    let last = &table[9];
+    assert_eq!(last.address, 0x4000201C);
+    assert_eq!(last.peripheral, "UART1");
+    assert_eq!(last.name, "DR3");
+    assert_eq!(last.size, Some(16));
+    assert_eq!(last.access, Some(Access::ReadWrite));
+}
+
+#[test]
+fn resolved_peripherals() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>GPIOA</name>
+      <groupName>GPIO</groupName>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <register>
+          <name>IDR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>ODR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="GPIOA">
+      <name>GPIOB</name>
+      <baseAddress>0x40010400</baseAddress>
+    </peripheral>
+    <peripheral derivedFrom="GPIOB">
+      <name>GPIOC</name>
+      <baseAddress>0x40010800</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let resolved: Vec<_> = device.resolved_peripherals().collect();
+    assert_eq!(resolved.len(), 3);
+
+    let base = device.get_peripheral("GPIOA").unwrap();
+    for p in &resolved {
+        assert_eq!(p.derived_from, None);
+        assert_eq!(p.group_name.as_deref(), Some("GPIO"));
+        assert_eq!(p.registers().count(), base.registers().count());
+    }
+    assert_eq!(resolved[2].name, "GPIOC");
+    assert_eq!(resolved[2].base_address, 0x40010800);
+
+    // the device itself is left untouched
+    assert!(device.get_peripheral("GPIOC").unwrap().registers.is_none());
+}
+
+#[test]
+fn field_header_name() {
+    let xml = device_xml_with(
+        r#"
+  <headerDefinitionsPrefix>DEV_</headerDefinitionsPrefix>"#,
+        r#"
+    <peripheral>
+      <name>TIM1</name>
+      <prependToName>T_</prependToName>
+      <headerStructName>TIM</headerStructName>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <register>
+          <dim>2</dim>
+          <dimIncrement>4</dimIncrement>
+          <name>CCR%s</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>VAL</name>
+              <bitRange>[15:0]</bitRange>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let p = device.get_peripheral("TIM1").unwrap();
+    let r = p.get_register("CCR%s").unwrap();
+    let f = r.get_field("VAL").unwrap();
+    assert_eq!(f.header_name(r, p, &device), "DEV_TIM_T_CCR_VAL");
+}
+
+#[test]
+fn json_round_trip() {
+    let json = r#"{
+  "name": "DEV",
+  "version": "1.0",
+  "description": "Test device",
+  "addressUnitBits": 8,
+  "width": 32,
+  "size": 32,
+  "peripherals": [
+    {
+      "name": "TIMER",
+      "baseAddress": 1073807360,
+      "registers": [
+        {
+          "register": {
+            "name": "CR",
+            "addressOffset": 4,
+            "resetValue": 1,
+            "fields": [
+              {
+                "name": "EN",
+                "bitOffset": 0,
+                "bitWidth": 1
+              }
+            ]
+          }
+        }
+      ]
+    }
+  ]
+}"#;
+    let device = Device::from_json(json).unwrap();
+    let cr = device
+        .get_peripheral("TIMER")
+        .unwrap()
+        .get_register("CR")
+        .unwrap();
+    assert_eq!(cr.address_offset, 4);
+    assert_eq!(cr.properties.reset_value, Some(1));
+
+    let xml = svd_encoder::encode(&device).unwrap();
+    assert_eq!(svd_parser::parse(&xml).unwrap(), device);
+    assert_eq!(Device::from_json(&device.to_json()).unwrap(), device);
+
+    // "name" is required
+    assert!(Device::from_json("{}").is_err());
+}
+
+#[test]
+fn remove_peripheral() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>SPI1</name>
+      <baseAddress>0x40013000</baseAddress>
+    </peripheral>
+    <peripheral derivedFrom="SPI1">
+      <name>SPI2</name>
+      <baseAddress>0x40003800</baseAddress>
+    </peripheral>
+    <peripheral>
+      <name>RTC</name>
+      <baseAddress>0x40002800</baseAddress>
+    </peripheral>"#,
+    );
+    let mut device: Device = svd_parser::parse(&xml).unwrap();
+
+    assert!(device.remove_peripheral("SPI1").is_err());
+    assert!(device.remove_peripheral("USB").is_err());
+    assert_eq!(device.peripherals.len(), 3);
+
+    assert_eq!(device.remove_peripheral("RTC").unwrap().name, "RTC");
+    assert!(device.get_peripheral("RTC").is_none());
+
+    let (spi1, dangling) = device.force_remove_peripheral("SPI1").unwrap();
+    assert_eq!(spi1.name, "SPI1");
+    assert_eq!(dangling, vec!["SPI2".to_string()]);
+    assert_eq!(device.peripherals.len(), 1);
+}
+
+#[test]
+fn registers_with_size() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>ADC</name>
+      <baseAddress>0x40012000</baseAddress>
+      <size>16</size>
+      <registers>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x4</addressOffset>
+          <size>8</size>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral>
+      <name>RTC</name>
+      <baseAddress>0x40002800</baseAddress>
+      <registers>
+        <register>
+          <name>TR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let sizes: Vec<_> = device.registers_with_size().collect();
+    assert_eq!(
+        sizes,
+        [
+            ("RTC.TR".to_string(), 32),
+            ("ADC.DR".to_string(), 16),
+            ("ADC.CR".to_string(), 8),
+        ]
+    );
+}
+
+#[test]
+fn revalidate() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>WDT</name>
+      <baseAddress>0x40003000</baseAddress>
+      <addressBlock>
+        <offset>0x0</offset>
+        <size>0x8</size>
+        <usage>registers</usage>
+      </addressBlock>
+      <registers>
+        <register>
+          <name>KR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0xC</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    device.revalidate(ValidateLevel::Weak).unwrap();
+    assert_eq!(
+        device
+            .revalidate(ValidateLevel::Strict)
+            .unwrap_err()
+            .to_string(),
+        "WDT: `Peripheral error: Register `SR` at offset 0xc is outside of address blocks"
+    );
+}
+
+#[test]
+fn normalize_field_ranges() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>PWR</name>
+      <baseAddress>0x40007000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>VOS</name>
+              <lsb>14</lsb>
+              <msb>15</msb>
+            </field>
+            <field>
+              <name>PLS</name>
+              <bitRange>[7:5]</bitRange>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let mut device = svd_parser::parse(&xml).unwrap();
+    device.normalize_field_ranges();
+    let cr = device.peripherals[0].get_register("CR").unwrap();
+    let vos = cr.get_field("VOS").unwrap();
+    assert_eq!(vos.bit_range.range_type, BitRangeType::OffsetWidth);
+    assert_eq!((vos.bit_offset(), vos.bit_width()), (14, 2));
+    let pls = cr.get_field("PLS").unwrap();
+    assert_eq!(pls.bit_range.range_type, BitRangeType::OffsetWidth);
+    assert_eq!((pls.bit_offset(), pls.bit_width()), (5, 3));
+}
+
+#[test]
+fn derived_overrides() {
+    let xml = |offset: &str| {
+        device_xml(&format!(
+            r#"
+    <peripheral>
+      <name>TIM2</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CR1</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>ARR</name>
+          <addressOffset>0x2C</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="TIM2">
+      <name>TIM5</name>
+      <baseAddress>0x40000C00</baseAddress>
+      <registers>
+        <register>
+          <name>ARR</name>
+          <description>32-bit auto-reload</description>
+          <addressOffset>{offset}</addressOffset>
+        </register>
+        <register>
+          <name>OR</name>
+          <addressOffset>0x50</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"#
+        ))
+    };
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    assert!(svd_parser::parse_with_config(&xml("0x2C"), &strict).is_ok());
+
+    let err = svd_parser::parse_with_config(&xml("0x30"), &strict).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "`Device error: Peripheral `TIM5` redefines register `ARR` of `TIM2` with different addressOffset at 2:1 (device)"
+    );
+    assert!(svd_parser::parse(&xml("0x30")).is_ok());
+}
+
+#[test]
+fn has_trustzone() {
+    let xml = |cpu: &str, peripheral: &str| {
+        device_xml_with(
+            &format!(
+                r#"
+  <cpu>
+    <name>{cpu}</name>
+    <revision>r0p0</revision>
+    <endian>little</endian>
+    <mpuPresent>false</mpuPresent>
+    <fpuPresent>false</fpuPresent>
+    <nvicPrioBits>2</nvicPrioBits>
+    <vendorSystickConfig>false</vendorSystickConfig>
+  </cpu>"#
+            ),
+            &format!(
+                r#"
+    <peripheral>
+      <name>GPIO</name>
+      <baseAddress>0x50000000</baseAddress>
+      {peripheral}
+      <registers>
+        <register>
+          <name>OUT</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"#
+            ),
+        )
+    };
+    let m0 = svd_parser::parse(&xml("CM0", "")).unwrap();
+    assert!(!m0.has_trustzone());
+
+    let m33 = svd_parser::parse(&xml("CM33", "<protection>s</protection>")).unwrap();
+    assert!(m33.has_trustzone());
+
+    let mut m33 = svd_parser::parse(&xml("CM33", "")).unwrap();
+    assert!(!m33.has_trustzone());
+    m33.cpu.as_mut().unwrap().sau_num_regions = Some(8);
+    assert!(m33.has_trustzone());
+}
+
+#[test]
+fn counts() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <dim>2</dim>
+      <dimIncrement>0x400</dimIncrement>
+      <name>DMA%s</name>
+      <baseAddress>0x40020000</baseAddress>
+      <registers>
+        <register>
+          <name>ISR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <dim>4</dim>
+              <dimIncrement>4</dimIncrement>
+              <name>TCIF%s</name>
+              <bitOffset>0</bitOffset>
+              <bitWidth>1</bitWidth>
+            </field>
+          </fields>
+        </register>
+        <cluster>
+          <dim>4</dim>
+          <dimIncrement>0x14</dimIncrement>
+          <name>CH%s</name>
+          <addressOffset>0x8</addressOffset>
+          <register>
+            <dim>2</dim>
+            <dimIncrement>0x4</dimIncrement>
+            <name>ADDR%s</name>
+            <addressOffset>0x0</addressOffset>
+            <fields>
+              <field>
+                <name>PA</name>
+                <bitRange>[31:0]</bitRange>
+              </field>
+            </fields>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="DMA%s">
+      <name>BDMA</name>
+      <baseAddress>0x58025400</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    assert_eq!(device.peripheral_count(), 2);
+    assert_eq!(device.register_count(false), 2);
+    assert_eq!(device.register_count(true), 2 * (1 + 4 * 2));
+    assert_eq!(device.field_count(false), 2);
+    assert_eq!(device.field_count(true), 2 * (4 + 4 * 2));
+}
+
+#[test]
+fn encode_validated() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>RNG</name>
+      <baseAddress>0x50060800</baseAddress>
+    </peripheral>"#,
+    );
+    let mut device = svd_parser::parse(&xml).unwrap();
+    let config = svd_encoder::Config::default();
+    let encoded = svd_encoder::encode_validated(&device, ValidateLevel::Strict, &config).unwrap();
+    assert_eq!(encoded, svd_encoder::encode(&device).unwrap());
+
+    device.peripherals.clear();
+    assert!(svd_encoder::encode_validated(&device, ValidateLevel::Weak, &config).is_err());
+    assert!(svd_encoder::encode(&device).is_ok());
+}
+
+#[test]
+fn derived_cluster_rebased() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIM</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <cluster>
+          <name>CH0</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>CR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x4</addressOffset>
+          </register>
+        </cluster>
+        <cluster derivedFrom="CH0">
+          <name>CH1</name>
+          <addressOffset>0x20</addressOffset>
+        </cluster>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let ch1 = device.peripherals[0].get_cluster("CH1").unwrap();
+    assert_eq!(ch1.derived_from.as_deref(), Some("CH0"));
+    assert!(ch1.children.is_empty());
+
+    let config = svd_parser::Config::default().expand(true);
+    let device = svd_parser::parse_with_config(&xml, &config).unwrap();
+    let registers: Vec<_> = device.peripherals[0]
+        .registers()
+        .map(|r| (r.name.as_str(), r.address_offset))
+        .collect();
+    assert_eq!(
+        registers,
+        [
+            ("CH0_CR", 0x10),
+            ("CH0_CCR", 0x14),
+            ("CH1_CR", 0x20),
+            ("CH1_CCR", 0x24)
+        ]
+    );
+}
+
+#[test]
+fn access_summary() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>USART</name>
+      <baseAddress>0x40011000</baseAddress>
+      <access>read-only</access>
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field><name>RXNE</name><bitRange>[5:5]</bitRange></field>
+            <field><name>TC</name><bitRange>[6:6]</bitRange><access>read-write</access></field>
+          </fields>
+        </register>
+        <register>
+          <dim>2</dim>
+          <dimIncrement>4</dimIncrement>
+          <name>DR%s</name>
+          <addressOffset>0x4</addressOffset>
+          <access>write-only</access>
+          <fields>
+            <field><name>DR</name><bitRange>[8:0]</bitRange></field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral>
+      <name>CRC</name>
+      <baseAddress>0x40023000</baseAddress>
+      <registers>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field><name>DR</name><bitRange>[31:0]</bitRange></field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let summary: Vec<_> = device.access_summary().into_iter().collect();
+    assert_eq!(
+        summary,
+        [
+            (Access::ReadOnly, 1),
+            (Access::ReadWrite, 2),
+            (Access::WriteOnly, 2),
+        ]
+    );
+}
+
+#[test]
+fn frozen_device() {
+    let xml = timer_device().replace(
+        "  </peripherals>",
+        r#"    <peripheral derivedFrom="TIMER">
+      <name>TIMER2</name>
+      <baseAddress>0x40020000</baseAddress>
+    </peripheral>
+  </peripherals>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let frozen = svd_parser::FrozenDevice::new(&device).unwrap();
+
+    let handles: Vec<_> = ["TIMER", "TIMER2"]
+        .into_iter()
+        .map(|name| {
+            let frozen = frozen.clone();
+            std::thread::spawn(move || {
+                let p = frozen.get_peripheral(name).unwrap();
+                assert_eq!(p.derived_from, None);
+                let cr = frozen.get_register(&format!("{name}.CH_CR")).unwrap();
+                (p.base_address, cr.properties.size, cr.properties.access)
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(
+        results,
+        [
+            (0x40010000, Some(32), Some(Access::ReadOnly)),
+            (0x40020000, Some(32), Some(Access::ReadOnly)),
+        ]
+    );
+    assert_eq!(frozen.peripherals().count(), 2);
+    assert!(frozen.get_register("TIMER.CH.CR").is_none());
+    assert!(frozen.get_peripheral("TIMER3").is_none());
+}
+
+#[test]
+fn conflicting_header_enums() {
+    let xml = |value: &str| {
+        device_xml(&format!(
+            r#"
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+              <enumeratedValues>
+                <headerEnumName>State</headerEnumName>
+                <enumeratedValue><name>Off</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>On</name><value>1</value></enumeratedValue>
+              </enumeratedValues>
+            </field>
+            <field>
+              <name>TXEN</name>
+              <bitRange>[2:1]</bitRange>
+              <enumeratedValues>
+                <headerEnumName>State</headerEnumName>
+                <enumeratedValue><name>On</name><value>{value}</value></enumeratedValue>
+                <enumeratedValue><name>Off</name><value>0</value></enumeratedValue>
+              </enumeratedValues>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"#
+        ))
+    };
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    assert!(svd_parser::parse_with_config(&xml("1"), &strict).is_ok());
+
+    let err = svd_parser::parse_with_config(&xml("0x2"), &strict).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "`Device error: Header enum `State` has different values in `UART.CR.EN` and `UART.CR.TXEN` at 2:1 (device)"
+    );
+    assert!(svd_parser::parse(&xml("0x2")).is_ok());
+}
+
+#[test]
+fn validation_path() {
+    let xml = timer_device().replace("<name>EN</name>", "<name>E-N</name>");
+    let device = svd_parser::parse(&xml).unwrap();
+    let err = device.validate_all(ValidateLevel::Strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TIMER.CH.CR.E-N: `Name check error: Name `E-N` contains unexpected symbol"
+    );
+    assert_eq!(err.path(), Some("TIMER.CH.CR.E-N"));
+    assert_eq!(err.kind(), "Name");
+    assert!(err.inner().path().is_none());
+}
+
+#[test]
+fn reg_iter() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>DMA</name>
+      <baseAddress>0x40020000</baseAddress>
+      <registers>
+        <register>
+          <name>ISR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <cluster>
+          <dim>2</dim>
+          <dimIncrement>0x20</dimIncrement>
+          <name>CH%s</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>CR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+          <cluster>
+            <name>BUF</name>
+            <addressOffset>0x8</addressOffset>
+            <register>
+              <dim>2</dim>
+              <dimIncrement>0x4</dimIncrement>
+              <name>ADDR%s</name>
+              <addressOffset>0x0</addressOffset>
+            </register>
+          </cluster>
+        </cluster>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="DMA">
+      <name>DMA2</name>
+      <baseAddress>0x40030000</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let regs: Vec<_> = device
+        .reg_iter()
+        .map(|(address, path, r)| (address, path, r.name.as_str()))
+        .collect();
+    let expected = |p: &str, base: u64| {
+        [
+            (base, format!("{p}.ISR"), "ISR"),
+            (base + 0x10, format!("{p}.CH0.CR"), "CR"),
+            (base + 0x18, format!("{p}.CH0.BUF.ADDR0"), "ADDR%s"),
+            (base + 0x1c, format!("{p}.CH0.BUF.ADDR1"), "ADDR%s"),
+            (base + 0x30, format!("{p}.CH1.CR"), "CR"),
+            (base + 0x38, format!("{p}.CH1.BUF.ADDR0"), "ADDR%s"),
+            (base + 0x3c, format!("{p}.CH1.BUF.ADDR1"), "ADDR%s"),
+        ]
+    };
+    let mut all = expected("DMA", 0x40020000).to_vec();
+    all.extend(expected("DMA2", 0x40030000));
+    assert_eq!(regs, all);
+
+    // addresses of array elements past the 32-bit offsets don't overflow
+    let xml = xml.replace(
+        "<name>ISR</name>
+          <addressOffset>0x0</addressOffset>",
+        "<dim>3</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>ISR%s</name>
+          <addressOffset>0xFFFFFFF8</addressOffset>",
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let last = device.reg_iter().nth(2).unwrap();
+    assert_eq!((last.0, last.1.as_str()), (0x1_4002_0000, "DMA.ISR2"));
+}
+
+#[test]
+fn fingerprint() {
+    let dr = "<register><name>DR</name><addressOffset>0x4</addressOffset></register>";
+    let sr = "<register><name>SR</name><addressOffset>0x0</addressOffset></register>";
+    let uart = |description: &str, registers: &str| {
+        format!(
+            "<peripheral>
+      <name>UART</name>
+      <description>{description}</description>
+      <baseAddress>0x40020000</baseAddress>
+      <registers>{registers}</registers>
+    </peripheral>"
+        )
+    };
+    let after = |uart: &str| {
+        timer_device().replace("  </peripherals>", &format!("{uart}\n  </peripherals>"))
+    };
+    let before =
+        |uart: &str| timer_device().replace("<peripherals>", &format!("<peripherals>\n{uart}"));
+    let fingerprint = |xml: &str| svd_parser::parse(xml).unwrap().fingerprint();
+
+    let original = after(&uart(
+        "Universal asynchronous   receiver",
+        &format!("{dr}{sr}"),
+    ));
+    let reformatted = before(&uart(
+        "Universal\n  asynchronous receiver",
+        &format!("{sr}{dr}"),
+    ));
+    assert_eq!(fingerprint(&original), fingerprint(&reformatted));
+
+    let edited = original.replace("0x40020000", "0x40024000");
+    assert_ne!(fingerprint(&edited), fingerprint(&original));
+
+    // number literals are only formatting
+    let decimal = original.replace("0x40020000", "1073872896");
+    assert_eq!(fingerprint(&decimal), fingerprint(&original));
+    let config = svd_parser::Config::default().keep_literals(true);
+    let kept = svd_parser::parse_with_config(&decimal, &config).unwrap();
+    assert_eq!(kept, svd_parser::parse(&original).unwrap());
+    assert_eq!(kept.fingerprint(), fingerprint(&original));
+
+    // so are comments
+    let commented = original
+        .replace("<peripheral>", "<!-- serial port --><peripheral>")
+        .replace("<register>", "<!-- register --><register>");
+    let device = svd_parser::parse(&commented).unwrap();
+    assert!(!device.peripherals[1].comments.is_empty());
+    assert_eq!(device.fingerprint(), fingerprint(&original));
+}
+
+#[test]
+fn expand_peripheral() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>TIM2</name>
+      <description>General purpose timer</description>
+      <baseAddress>0x40000000</baseAddress>
+      <addressBlock>
+        <offset>0x0</offset>
+        <size>0x400</size>
+        <usage>registers</usage>
+      </addressBlock>
+      <interrupt>
+        <name>TIM2</name>
+        <value>28</value>
+      </interrupt>
+      <registers>
+        <register>
+          <name>CR1</name>
+          <addressOffset>0x0</addressOffset>
+          <size>16</size>
+        </register>
+        <register>
+          <name>ARR</name>
+          <addressOffset>0x2C</addressOffset>
+          <size>16</size>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="TIM2">
+      <name>TIM3</name>
+      <baseAddress>0x40000400</baseAddress>
+    </peripheral>
+    <peripheral derivedFrom="TIM3">
+      <name>TIM5</name>
+      <description>32-bit timer</description>
+      <baseAddress>0x40000C00</baseAddress>
+      <registers>
+        <register>
+          <name>ARR</name>
+          <addressOffset>0x2C</addressOffset>
+          <size>32</size>
+        </register>
+        <register>
+          <name>OR</name>
+          <addressOffset>0x50</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let mut device = svd_parser::parse(&xml).unwrap();
+
+    let tim3 = device.expand_peripheral("TIM3").unwrap();
+    assert_eq!(tim3.derived_from, None);
+    assert_eq!(tim3.base_address, 0x40000400);
+    assert_eq!(tim3.description.as_deref(), Some("General purpose timer"));
+    assert_eq!(tim3.interrupt[0].value, 28);
+    assert_eq!(tim3.address_block.as_ref().unwrap()[0].size, 0x400);
+
+    let tim5 = device.expand_peripheral("TIM5").unwrap();
+    assert_eq!(tim5.name, "TIM5");
+    assert_eq!(tim5.base_address, 0x40000C00);
+    assert_eq!(tim5.description.as_deref(), Some("32-bit timer"));
+    let names: Vec<_> = tim5.registers().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["CR1", "ARR", "OR"]);
+    // own register shadows the inherited one
+    assert_eq!(tim5.get_register("ARR").unwrap().properties.size, Some(32));
+    assert_eq!(tim5.get_register("CR1").unwrap().properties.size, Some(16));
+
+    assert_eq!(
+        device.expand_peripheral("TIM4").unwrap_err().to_string(),
+        "`Device error: Peripheral `TIM4` not found"
+    );
+    device.get_mut_peripheral("TIM2").unwrap().derived_from = Some("TIM5".into());
+    assert_eq!(
+        device.expand_peripheral("TIM3").unwrap_err().to_string(),
+        "`Device error: Peripherals derive from each other in a cycle: TIM3 -> TIM2 -> TIM5 -> TIM3"
+    );
+}
+
+#[test]
+fn encode_options() {
+    use svd_encoder::{DerivableSorting, Indent, LineEnding, NumberFormat, Sorting};
+
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+    </peripheral>
+    <peripheral>
+      <name>GPIO</name>
+      <baseAddress>0x40000000</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+
+    let out = svd_encoder::encode(&device).unwrap();
+    assert!(out.contains(
+        "\n    <peripheral>\n      <name>UART</name>\n      <baseAddress>0x40004000</baseAddress>"
+    ));
+    assert!(out.find("UART") < out.find("GPIO"));
+
+    let config = svd_encoder::Config::default()
+        .indent(Indent::Tab)
+        .peripheral_base_address(NumberFormat::Dec)
+        .peripheral_sorting(DerivableSorting::Unchanged(Some(Sorting::Offset)));
+    let out = svd_encoder::encode_with_config(&device, &config).unwrap();
+    assert!(out.contains(
+        "\n\t\t<peripheral>\n\t\t\t<name>GPIO</name>\n\t\t\t<baseAddress>1073741824</baseAddress>"
+    ));
+    assert!(out.find("GPIO") < out.find("UART"));
+    assert_eq!(svd_parser::parse(&out).unwrap().peripherals.len(), 2);
+
+    let config = svd_encoder::Config::default()
+        .indent(Indent::None)
+        .peripheral_base_address(NumberFormat::LowerHex);
+    let out = svd_encoder::encode_with_config(&device, &config).unwrap();
+    assert!(!out.contains('\n'));
+    assert!(out.contains("<baseAddress>0x40004000</baseAddress>"));
+
+    let config = svd_encoder::Config::default().line_ending(LineEnding::CrLf);
+    let out = svd_encoder::encode_with_config(&device, &config).unwrap();
+    assert!(out.contains("\r\n    <peripheral>\r\n      <name>UART</name>\r\n"));
+    assert_eq!(out.matches('\n').count(), out.matches("\r\n").count());
+    assert_eq!(svd_parser::parse(&out).unwrap().peripherals.len(), 2);
+}
+
+#[test]
+fn duplicate_interrupts() {
+    let xml = |name: &str| {
+        device_xml(&format!(
+            r#"
+    <peripheral>
+      <name>TIM2</name>
+      <baseAddress>0x40000000</baseAddress>
+      <interrupt>
+        <name>TIM2</name>
+        <value>28</value>
+      </interrupt>
+      <registers>
+        <register>
+          <name>CR1</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="TIM2">
+      <name>TIM3</name>
+      <baseAddress>0x40000400</baseAddress>
+      <interrupt>
+        <name>{name}</name>
+        <value>28</value>
+      </interrupt>
+    </peripheral>"#
+        ))
+    };
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    assert!(svd_parser::parse_with_config(&xml("TIM2"), &strict).is_ok());
+    let err = svd_parser::parse_with_config(&xml("TIM3"), &strict).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "`Device error: Interrupts `TIM2` and `TIM3` have the same value 28 at 2:1 (device)"
+    );
+    assert!(svd_parser::parse(&xml("TIM3")).is_ok());
+}
+
+#[test]
+fn parse_bytes() {
+    let device = svd_parser::parse(&timer_device()).unwrap();
+    assert_eq!(
+        svd_parser::parse_bytes(timer_device().as_bytes()).unwrap(),
+        device
+    );
+
+    let with_bom = format!("\u{feff}{}", timer_device());
+    assert_eq!(svd_parser::parse(&with_bom).unwrap(), device);
+    assert_eq!(
+        svd_parser::parse_bytes(with_bom.as_bytes()).unwrap(),
+        device
+    );
+
+    let mut invalid = timer_device().as_bytes().to_vec();
+    invalid.insert(timer_device().find("Test").unwrap(), 0xff);
+    assert!(svd_parser::parse_bytes(&invalid).is_err());
+}
+
+#[test]
+fn vendor_extensions() {
+    let xml = timer_device()
+        .replace(
+            "<device ",
+            "<device xmlns:nrf=\"http://www.nordicsemi.com/svd\" ",
+        )
+        .replace(
+            "</peripherals>",
+            r#"</peripherals>
+  <vendorExtensions>
+    <nrf:tz regions="2">
+      <nrf:region id="0">flash</nrf:region>
+      <nrf:region id="1">ram</nrf:region>
+    </nrf:tz>
+    <comment>Kept &amp; untouched</comment>
+  </vendorExtensions>"#,
+        );
+    let device = svd_parser::parse(&xml).unwrap();
+    let raw = device.vendor_extensions.as_deref().unwrap();
+    assert!(raw.starts_with("<vendorExtensions xmlns:nrf=\"http://www.nordicsemi.com/svd\">"));
+    assert!(raw.contains("<comment>Kept &amp; untouched</comment>"));
+
+    let encoded = svd_encoder::encode(&device).unwrap();
+    assert!(
+        encoded.contains("<nrf:region id=\"1\">ram</nrf:region>"),
+        "{encoded}"
+    );
+    assert_eq!(svd_parser::parse(&encoded).unwrap(), device);
+
+    let plain = svd_parser::parse(&timer_device()).unwrap();
+    assert!(plain.vendor_extensions.is_none());
+    assert!(!svd_encoder::encode(&plain)
+        .unwrap()
+        .contains("vendorExtensions"));
+}
+
+#[test]
+fn peripherals_by_address() {
+    let peripheral = |name: &str, base: u32, extra: &str| {
+        format!(
+            "
+    <peripheral>
+      <name>{name}</name>
+      {extra}
+      <baseAddress>{base:#x}</baseAddress>
+      <addressBlock>
+        <offset>0x0</offset>
+        <size>0x400</size>
+        <usage>registers</usage>
+      </addressBlock>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>"
+        )
+    };
+    let xml = |peripherals: &[String]| device_xml(&peripherals.concat());
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+
+    let device = svd_parser::parse_with_config(
+        &xml(&[
+            peripheral("UART", 0x4000_4000, ""),
+            peripheral("GPIO", 0x4800_0000, ""),
+            peripheral("TIM2", 0x4000_0000, ""),
+            peripheral("TIM3", 0x4000_0400, ""),
+        ]),
+        &strict,
+    )
+    .unwrap();
+    let names: Vec<_> = device
+        .peripherals_by_address()
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(names, ["TIM2", "TIM3", "UART", "GPIO"]);
+
+    let overlapping = xml(&[
+        peripheral("TIM2", 0x4000_0000, ""),
+        peripheral("TIM3", 0x4000_0200, ""),
+    ]);
+    let err = svd_parser::parse_with_config(&overlapping, &strict).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "`Device error: Peripheral `TIM2` at 0x40000000 overlaps peripheral `TIM3` at 0x40000200 at 2:1 (device)"
+    );
+    assert!(svd_parser::parse(&overlapping).is_ok());
+
+    let alternate = xml(&[
+        peripheral("TIM2", 0x4000_0000, ""),
+        peripheral(
+            "TIM2_ALT",
+            0x4000_0000,
+            "<alternatePeripheral>TIM2</alternatePeripheral>",
+        ),
+    ]);
+    assert!(svd_parser::parse_with_config(&alternate, &strict).is_ok());
+}
+
+#[test]
+fn find_field() {
+    let device: Device = svd_parser::parse(&timer_device()).unwrap();
+
+    let cr = device.find_register("TIMER.CH.CR").unwrap();
+    assert_eq!(cr.name, "CR");
+    let en = device.find_field("TIMER.CH.CR.EN").unwrap();
+    assert_eq!(en.name, "EN");
+    assert_eq!(en.bit_width(), 32);
+
+    assert!(device.find_field("TIMER.CH2.CR.EN").is_none());
+    assert!(device.find_field("TIMER.CH.CR.DIS").is_none());
+    assert!(device.find_register("UART.CH.CR").is_none());
+    assert!(device.find_register("TIMER").is_none());
+}
+
+#[test]
+fn expand_derived_from() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>UART0</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <description>Control</description>
+          <addressOffset>0x0</addressOffset>
+          <resetValue>0x1</resetValue>
+          <fields>
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+              <enumeratedValues>
+                <name>ENABLE</name>
+                <enumeratedValue><name>DISABLED</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>ENABLED</name><value>1</value></enumeratedValue>
+              </enumeratedValues>
+            </field>
+            <field derivedFrom="EN">
+              <name>TXEN</name>
+              <bitRange>[1:1]</bitRange>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="UART0">
+      <name>UART1</name>
+      <baseAddress>0x40001000</baseAddress>
+    </peripheral>
+    <peripheral>
+      <name>SPI</name>
+      <baseAddress>0x40002000</baseAddress>
+      <registers>
+        <register derivedFrom="UART0.CR">
+          <name>CTRL</name>
+          <addressOffset>0x8</addressOffset>
+          <resetValue>0x3</resetValue>
+        </register>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let device = svd_parser::expand(&device).unwrap();
+
+    let uart1 = device.get_peripheral("UART1").unwrap();
+    assert_eq!(uart1.derived_from, None);
+    assert_eq!(uart1.base_address, 0x40001000);
+    assert!(uart1.get_register("CR").is_some());
+
+    let ctrl = device.find_register("SPI.CTRL").unwrap();
+    assert_eq!(ctrl.derived_from, None);
+    assert_eq!(ctrl.description.as_deref(), Some("Control"));
+    assert_eq!(ctrl.address_offset, 0x8);
+    assert_eq!(ctrl.properties.reset_value, Some(0x3));
+
+    let txen = ctrl.get_field("TXEN").unwrap();
+    assert_eq!(txen.derived_from, None);
+    assert_eq!(txen.bit_offset(), 1);
+    assert_eq!(txen.enumerated_values[0].values.len(), 2);
+
+    for p in &device.peripherals {
+        assert_eq!(p.derived_from, None);
+        for r in p.all_registers() {
+            assert_eq!(r.derived_from, None);
+            for f in r.fields() {
+                assert_eq!(f.derived_from, None);
+                assert!(f
+                    .enumerated_values
+                    .iter()
+                    .all(|ev| ev.derived_from.is_none()));
+            }
+        }
+    }
+}
+
+#[test]
+fn expand_arrays() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <dim>2</dim>
+      <dimIncrement>0x1000</dimIncrement>
+      <name>TIM%s</name>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <cluster>
+          <dim>2</dim>
+          <dimIncrement>0x10</dimIncrement>
+          <dimIndex>A,B</dimIndex>
+          <name>CH%s</name>
+          <addressOffset>0x20</addressOffset>
+          <register>
+            <dim>2</dim>
+            <dimIncrement>0x4</dimIncrement>
+            <name>CCR%s</name>
+            <addressOffset>0x0</addressOffset>
+            <fields>
+              <field>
+                <dim>2</dim>
+                <dimIncrement>0x4</dimIncrement>
+                <name>MODE%s</name>
+                <bitRange>[3:0]</bitRange>
+              </field>
+            </fields>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>"#,
+    );
+    let mut device = svd_parser::parse(&xml).unwrap();
+    device.expand_arrays();
+
+    let peripherals: Vec<_> = device
+        .peripherals
+        .iter()
+        .map(|p| (p.is_single(), p.name.as_str(), p.base_address))
+        .collect();
+    assert_eq!(
+        peripherals,
+        [(true, "TIM0", 0x40000000), (true, "TIM1", 0x40001000)]
+    );
+    let tim1 = &device.peripherals[1];
+    let clusters: Vec<_> = tim1
+        .clusters()
+        .map(|c| (c.is_single(), c.name.as_str(), c.address_offset))
+        .collect();
+    assert_eq!(clusters, [(true, "CHA", 0x20), (true, "CHB", 0x30)]);
+    let chb = tim1.get_cluster("CHB").unwrap();
+    let registers: Vec<_> = chb
+        .registers()
+        .map(|r| (r.is_single(), r.name.as_str(), r.address_offset))
+        .collect();
+    assert_eq!(registers, [(true, "CCR0", 0x0), (true, "CCR1", 0x4)]);
+    let fields: Vec<_> = chb
+        .registers()
+        .next()
+        .unwrap()
+        .fields()
+        .map(|f| (f.is_single(), f.name.as_str(), f.bit_offset()))
+        .collect();
+    assert_eq!(fields, [(true, "MODE0", 0), (true, "MODE1", 4)]);
+}
+
+#[test]
+fn validate_collect() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      <addressBlock>
+        <offset>0x0</offset>
+        <size>0x10</size>
+        <usage>registers</usage>
+      </addressBlock>
+      <interrupt>
+        <name>UART</name>
+        <value>1</value>
+      </interrupt>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>8</size>
+          <fields>
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+            <field>
+              <name>MODE</name>
+              <bitRange>[1:0]</bitRange>
+            </field>
+            <field>
+              <name>HIGH</name>
+              <bitRange>[8:8]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x10</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral>
+      <name>SPI</name>
+      <baseAddress>0x40008000</baseAddress>
+      <interrupt>
+        <name>SPI</name>
+        <value>1</value>
+      </interrupt>
+    </peripheral>
+    <peripheral>
+      <name>SPI</name>
+      <baseAddress>0x40009000</baseAddress>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    assert!(device.validate_collect(ValidateLevel::Weak).is_empty());
+
+    let errors: Vec<_> = device
+        .validate_collect(ValidateLevel::Strict)
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "UART.CR: `Field error: Field `HIGH` ends at bit 8, beyond the register size of 8 bits",
+            "UART.CR: `Register error: Field `EN` overlaps field `MODE`",
+            "UART: `Peripheral error: Register or cluster `SR` is defined more than once",
+            "UART: `Peripheral error: Register `DR` at offset 0x10 is outside of address blocks",
+            "UART: `Peripheral error: Register `CR` at offset 0x0 overlaps register `SR` at offset 0x0",
+            "`Device error: Interrupts `UART` and `SPI` have the same value 1",
+            "`Device error: Peripheral `SPI` is defined more than once",
+        ]
+    );
+    // the first error is the one of `validate_all`
+    assert_eq!(
+        device
+            .validate_all(ValidateLevel::Strict)
+            .unwrap_err()
+            .to_string(),
+        errors[0]
+    );
+}
+
+#[test]
+fn effective_properties() {
+    let xml = device_xml_with(
+        r#"
+  <size>32</size>
+  <access>read-write</access>
+  <resetValue>0x0</resetValue>
+  <resetMask>0xffffffff</resetMask>"#,
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40010000</baseAddress>
+      <access>read-only</access>
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <cluster>
+          <name>CH%s</name>
+          <dim>2</dim>
+          <dimIncrement>0x10</dimIncrement>
+          <addressOffset>0x10</addressOffset>
+          <size>16</size>
+          <resetMask>0xffff</resetMask>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x0</addressOffset>
+            <resetValue>0x5</resetValue>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="TIMER">
+      <name>TIMER2</name>
+      <baseAddress>0x40020000</baseAddress>
+      <access>write-only</access>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let props = |size, access, reset_value, reset_mask| {
+        RegisterProperties::new()
+            .size(Some(size))
+            .access(Some(access))
+            .reset_value(Some(reset_value))
+            .reset_mask(Some(reset_mask))
+    };
+
+    assert_eq!(
+        device.effective_properties("TIMER.SR"),
+        Some(props(32, Access::ReadOnly, 0, 0xffffffff))
+    );
+    let ccr = Some(props(16, Access::ReadOnly, 0x5, 0xffff));
+    assert_eq!(device.effective_properties("TIMER.CH%s.CCR"), ccr);
+    assert_eq!(device.effective_properties("TIMER.CH1.CCR"), ccr);
+    // derived peripheral overrides the access of its base
+    assert_eq!(
+        device.effective_properties("TIMER2.CH0.CCR"),
+        Some(props(16, Access::WriteOnly, 0x5, 0xffff))
+    );
+    assert_eq!(device.effective_properties("TIMER.CH0"), None);
+    assert_eq!(device.effective_properties("TIMER.DR"), None);
+}
+
+#[test]
+fn effective_access() {
+    // the access is only given by the device
+    let device = svd_parser::parse(&timer_device()).unwrap();
+    let props = device.effective_properties("TIMER.CH.CR").unwrap();
+    let timer = device.get_peripheral("TIMER").unwrap();
+    let cluster = timer.get_cluster("CH").unwrap();
+    let cr = device.find_register("TIMER.CH.CR").unwrap();
+    let parent = cluster
+        .default_register_properties
+        .inherit_from(&timer.default_register_properties)
+        .inherit_from(&device.default_register_properties);
+    assert_eq!(cr.effective_access(&parent), Access::ReadOnly);
+    assert_eq!(cr.effective_access(&parent), props.access.unwrap());
+    let en = cr.get_field("EN").unwrap();
+    assert_eq!(en.effective_access(&props), Access::ReadOnly);
+}
+
+#[test]
+fn missing_address_offset_device_size() {
+    let xml = device_xml_with(
+        r#"
+  <size>16</size>"#,
+        r#"
+    <peripheral>
+      <name>GPIO</name>
+      <baseAddress>0x40020000</baseAddress>
+      <registers>
+        <cluster>
+          <name>PORT</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>A</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+          <register>
+            <name>B</name>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>"#,
+    );
+    let device = svd_parser::parse(&xml).unwrap();
+    let port = device
+        .get_peripheral("GPIO")
+        .unwrap()
+        .get_cluster("PORT")
+        .unwrap();
+    assert_eq!(port.get_register("B").unwrap().address_offset, 0x2);
+}
+
+#[test]
+fn size_in_address_units() {
+    let xml = device_xml(
+        r#"
+    <peripheral>
+      <name>DSP</name>
+      <baseAddress>0x1000</baseAddress>
+      <registers>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>2</size>
+        </register>
+      </registers>
+    </peripheral>"#,
+    )
+    .replace(
+        "<addressUnitBits>8</addressUnitBits>",
+        "<addressUnitBits>16</addressUnitBits>",
+    );
+    let config = svd_parser::Config::default().fix_byte_sizes(true);
+    let device = svd_parser::parse_with_config(&xml, &config).unwrap();
+    let dr = device
+        .get_peripheral("DSP")
+        .unwrap()
+        .get_register("DR")
+        .unwrap();
+    assert_eq!(dr.properties.size, Some(32));
+}
@@ -1,9 +1,9 @@
-use super::run_test;
-use crate::svd::{DimElement, ValidateLevel};
+use super::{parse_with_config, run_test};
+use crate::svd::{DimArrayIndex, DimElement, EnumeratedValue, ValidateLevel};
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         DimElement::builder()
             .dim(2)
             .dim_increment(4)
@@ -25,7 +25,7 @@ fn decode_encode() {
     )];
     run_test::<DimElement>(&tests[..], None, None);
 
-    let tests = vec![(
+    let tests = [(
         DimElement::builder()
             .dim(3)
             .dim_increment(4)
@@ -51,7 +51,7 @@ fn decode_encode() {
     )];
     run_test::<DimElement>(&tests[..], None, None);
 
-    let tests = vec![(
+    let tests = [(
         DimElement::builder()
             .dim(3)
             .dim_increment(4)
@@ -77,7 +77,7 @@ fn decode_encode() {
     )];
     run_test::<DimElement>(&tests[..], None, None);
 
-    let tests = vec![(
+    let tests = [(
         DimElement::builder()
             .dim(1)
             .dim_increment(0)
@@ -104,7 +104,7 @@ fn decode_encode() {
     encode_config.update("dim_dim", "UpperHex");
     encode_config.update("dim_increment", "LowerHex");
 
-    let tests = vec![(
+    let tests = [(
         DimElement::builder()
             .dim(14)
             .dim_increment(15)
@@ -127,3 +127,130 @@ fn decode_encode() {
 
 #[test]
 fn decode_encode_one_element() {}
+
+#[test]
+fn dim_array_index() {
+    let names = ["UART0", "UART1", "SPI0", "SPI1"];
+    let values = |n: usize| -> Vec<_> {
+        names
+            .iter()
+            .cycle()
+            .take(n)
+            .enumerate()
+            .map(|(i, name)| {
+                EnumeratedValue::builder()
+                    .name(name.to_string())
+                    .value(Some(i as u64))
+                    .build(ValidateLevel::Strict)
+                    .unwrap()
+            })
+            .collect()
+    };
+    let dim = |n| {
+        DimElement::builder()
+            .dim(4)
+            .dim_increment(0x100)
+            .dim_array_index(Some(DimArrayIndex {
+                header_enum_name: Some("FLEXCOMM".to_string()),
+                values: values(n),
+            }))
+            .build(ValidateLevel::Strict)
+    };
+    let tests = [(
+        dim(4).unwrap(),
+        "<dimElement>
+            <dim>4</dim>
+            <dimIncrement>0x100</dimIncrement>
+            <dimArrayIndex>
+                <headerEnumName>FLEXCOMM</headerEnumName>
+                <enumeratedValue><name>UART0</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>UART1</name><value>1</value></enumeratedValue>
+                <enumeratedValue><name>SPI0</name><value>2</value></enumeratedValue>
+                <enumeratedValue><name>SPI1</name><value>3</value></enumeratedValue>
+            </dimArrayIndex>
+        </dimElement>
+        ",
+        "<dimElement>
+            <dim>4</dim>
+            <dimIncrement>0x100</dimIncrement>
+            <dimArrayIndex>
+                <headerEnumName>FLEXCOMM</headerEnumName>
+                <enumeratedValue><name>UART0</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>UART1</name><value>1</value></enumeratedValue>
+                <enumeratedValue><name>SPI0</name><value>2</value></enumeratedValue>
+                <enumeratedValue><name>SPI1</name><value>3</value></enumeratedValue>
+            </dimArrayIndex>
+        </dimElement>
+        ",
+    )];
+    run_test::<DimElement>(&tests[..], None, None);
+
+    assert_eq!(
+        dim(5).unwrap_err().to_string(),
+        "`DimElement error: dimArrayIndex has 5 values, but array has only 4 elements"
+    );
+}
+
+#[test]
+fn dim_index() {
+    assert_eq!(
+        DimElement::parse_indexes("3-6").unwrap(),
+        ["3", "4", "5", "6"]
+    );
+    assert_eq!(DimElement::parse_indexes("B-D").unwrap(), ["B", "C", "D"]);
+    assert_eq!(
+        DimElement::parse_indexes("A,B,C,D").unwrap(),
+        ["A", "B", "C", "D"]
+    );
+    for text in ["6-3", "1-", "A,,B", "a-C"] {
+        assert_eq!(DimElement::parse_indexes(text), None, "{text}");
+    }
+
+    let dim = |dim: u32, index: &str| {
+        let xml = format!(
+            "<dimElement>
+                <dim>{dim}</dim>
+                <dimIncrement>0x4</dimIncrement>
+                <dimIndex>{index}</dimIndex>
+            </dimElement>"
+        );
+        parse_with_config::<DimElement>(&xml, &Default::default())
+    };
+    let d = dim(4, "3-6").unwrap();
+    assert_eq!(d.indexes().collect::<Vec<_>>(), ["3", "4", "5", "6"]);
+    let d = dim(3, "X,Y,Z").unwrap();
+    assert_eq!(d.indexes().collect::<Vec<_>>(), ["X", "Y", "Z"]);
+
+    assert_eq!(
+        dim(4, "6-3").unwrap_err().to_string(),
+        "Failed to parse dimIndex `6-3`, expected a range like `0-3` or a list like `A,B,C`"
+    );
+    assert_eq!(
+        dim(4, "A,B,C").unwrap_err().to_string(),
+        "`DimElement error: dimIndex has 3 indexes, but dim is 4"
+    );
+}
+
+#[test]
+fn element_names() {
+    let dim = DimElement::builder()
+        .dim(3)
+        .dim_increment(4)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let names: Vec<_> = dim.element_names("GPIO%s_CTRL").collect();
+    assert_eq!(names, ["GPIO0_CTRL", "GPIO1_CTRL", "GPIO2_CTRL"]);
+    let names: Vec<_> = dim.element_names("%s_DR").collect();
+    assert_eq!(names, ["0_DR", "1_DR", "2_DR"]);
+    let names: Vec<_> = dim.element_names("DR[%s]").collect();
+    assert_eq!(names, ["DR0", "DR1", "DR2"]);
+
+    let dim = DimElement::builder()
+        .dim(2)
+        .dim_increment(4)
+        .dim_index(Some(vec!["A".to_string(), "B".to_string()]))
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let names: Vec<_> = dim.element_names("CH%s").collect();
+    assert_eq!(names, ["CHA", "CHB"]);
+}
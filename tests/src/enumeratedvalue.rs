@@ -1,9 +1,10 @@
-use super::run_test;
-use crate::svd::{EnumeratedValue, ValidateLevel};
+use super::{parse_with_config, run_test};
+use crate::svd::{EnumeratedValue, EnumeratedValues, Field, ValidateLevel};
+use svd_encoder::Encode;
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         EnumeratedValue::builder()
             .name("WS0".to_string())
             .description(Some(
@@ -35,7 +36,7 @@ fn decode_encode() {
     encode_config.update("enumerated_value_name", "Pascal");
     encode_config.update("enumerated_value_value", "Bin");
 
-    let tests = vec![(
+    let tests = [(
         EnumeratedValue::builder()
             .name("WS0".to_string())
             .description(Some(
@@ -62,3 +63,204 @@ fn decode_encode() {
 
     run_test::<EnumeratedValue>(&tests[..], Some(parse_config), Some(encode_config));
 }
+
+#[test]
+fn build_values() {
+    let evs = EnumeratedValues::builder()
+        .name(Some("MODE".to_string()))
+        .header_enum_name(Some("MODE_ENUM".to_string()))
+        .value(
+            EnumeratedValue::builder()
+                .name("OFF".to_string())
+                .value(Some(0))
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        )
+        .value(
+            EnumeratedValue::builder()
+                .name("ON".to_string())
+                .is_default(Some(true))
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        )
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    assert_eq!(evs.default_value().unwrap().name, "ON");
+
+    let tests = [(
+        evs,
+        "
+            <enumeratedValues>
+                <name>MODE</name>
+                <headerEnumName>MODE_ENUM</headerEnumName>
+                <enumeratedValue><name>OFF</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>ON</name><isDefault>true</isDefault></enumeratedValue>
+            </enumeratedValues>
+        ",
+        "
+            <enumeratedValues>
+                <name>MODE</name>
+                <headerEnumName>MODE_ENUM</headerEnumName>
+                <enumeratedValue><name>OFF</name><value>0</value></enumeratedValue>
+                <enumeratedValue><name>ON</name><isDefault>true</isDefault></enumeratedValue>
+            </enumeratedValues>
+        ",
+    )];
+    run_test::<EnumeratedValues>(&tests[..], None, None);
+
+    let value = |name: &str, value| {
+        EnumeratedValue::builder()
+            .name(name.to_string())
+            .value(Some(value))
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    assert!(EnumeratedValues::builder()
+        .values(vec![value("A", 0), value("A", 1)])
+        .build(ValidateLevel::Strict)
+        .is_err());
+    assert!(EnumeratedValues::builder()
+        .values(vec![value("A", 0), value("B", 0)])
+        .build(ValidateLevel::Strict)
+        .is_err());
+    assert!(EnumeratedValues::builder()
+        .values(vec![value("A", 0), value("B", 0)])
+        .build(ValidateLevel::Weak)
+        .is_ok());
+}
+
+#[test]
+fn semantic_equality() {
+    let value = |name: &str, value, description: &str| {
+        EnumeratedValue::builder()
+            .name(name.to_string())
+            .description(Some(description.to_string()))
+            .value(Some(value))
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    let evs = |values| {
+        EnumeratedValues::builder()
+            .values(values)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+
+    let a = evs(vec![value("OFF", 0, "Disabled"), value("ON", 1, "Enabled")]);
+    let b = evs(vec![
+        value("ON", 1, "Turned on"),
+        value("OFF", 0, "Turned off"),
+    ]);
+    let c = evs(vec![
+        value("OFF", 0, "Disabled"),
+        value("ENABLED", 1, "Enabled"),
+    ]);
+    assert!(a.semantically_eq(&b));
+    assert_ne!(a, b);
+    assert!(!a.semantically_eq(&c));
+}
+
+#[test]
+fn derived_values() {
+    let tests = [(
+        EnumeratedValues::builder()
+            .derived_from(Some("CR.MODE.MODE_R".to_string()))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "<enumeratedValues derivedFrom=\"CR.MODE.MODE_R\"></enumeratedValues>",
+        "<enumeratedValues derivedFrom=\"CR.MODE.MODE_R\"/>",
+    )];
+    run_test::<EnumeratedValues>(&tests[..], None, None);
+
+    let mut evs = EnumeratedValues::builder()
+        .name(Some("MODE_W".to_string()))
+        .values(vec![EnumeratedValue::builder()
+            .name("ON".to_string())
+            .value(Some(1))
+            .build(ValidateLevel::Strict)
+            .unwrap()])
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    evs.modify_from(
+        EnumeratedValues::builder().derived_from(Some("MODE_R".to_string())),
+        ValidateLevel::Strict,
+    )
+    .unwrap();
+    assert_eq!(evs.derived_from.as_deref(), Some("MODE_R"));
+    assert_eq!(evs.name, None);
+    assert!(evs.values.is_empty());
+}
+
+#[test]
+fn dont_care_bits() {
+    let tests = [(
+        EnumeratedValue::builder()
+            .name("MODE1".to_string())
+            .value(Some(0b1000))
+            .mask(Some(0b1100))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "
+            <enumeratedValue>
+                <name>MODE1</name>
+                <value>#10xx</value>
+            </enumeratedValue>
+        ",
+        "
+            <enumeratedValue>
+                <name>MODE1</name>
+                <value>#10xx</value>
+            </enumeratedValue>
+        ",
+    )];
+    run_test::<EnumeratedValue>(&tests[..], None, None);
+
+    let field = |range: &str| {
+        format!(
+            "<field>
+              <name>MODE</name>
+              <bitRange>{range}</bitRange>
+              <enumeratedValues>{}</enumeratedValues>
+            </field>",
+            tests[0].1
+        )
+    };
+    let config = svd_parser::Config::default();
+    assert!(parse_with_config::<Field>(&field("[3:0]"), &config).is_ok());
+    let err = parse_with_config::<Field>(&field("[2:0]"), &config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`EnumeratedValue error: Value 8 out of range [0 - 7]"
+    );
+
+    // leading don't-care digits are kept up to the width of the field
+    let field = "<field>
+          <name>MODE</name>
+          <bitRange>[3:0]</bitRange>
+          <enumeratedValues>
+            <enumeratedValue>
+              <name>LOW</name>
+              <value>#xx01</value>
+            </enumeratedValue>
+          </enumeratedValues>
+        </field>";
+    let f = parse_with_config::<Field>(field, &config).unwrap();
+    let ev = &f.enumerated_values[0].values[0];
+    assert_eq!((ev.value, ev.mask), (Some(0b01), Some(0b0011)));
+    let mut out = Vec::new();
+    f.encode().unwrap().write(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<value>#xx01</value>"), "{out}");
+    assert_eq!(parse_with_config::<Field>(&out, &config).unwrap(), f);
+
+    let err = EnumeratedValue::builder()
+        .name("MODE1".to_string())
+        .value(Some(0b1001))
+        .mask(Some(0b1100))
+        .build(ValidateLevel::Weak)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`EnumeratedValue error: Value 0b1001 has bits set outside of mask 0b1100"
+    );
+}
@@ -0,0 +1,49 @@
+use crate::device_xml;
+use crate::svd::{Interrupt, ValidateLevel};
+use serde_json::json;
+use svd_parser::SVDErrorAt;
+
+fn bad_size() -> String {
+    device_xml(
+        r#"
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>foo</size>
+        </register>
+      </registers>
+    </peripheral>"#,
+    )
+}
+
+#[test]
+fn serialize_errors() {
+    let err = svd_parser::parse(&bad_size()).unwrap_err();
+    let err = err.downcast_ref::<SVDErrorAt>().unwrap();
+    let svd_err = Interrupt::builder()
+        .name("TIM1".to_string())
+        .value(1000)
+        .build(ValidateLevel::Strict)
+        .unwrap_err();
+
+    let json = serde_json::to_value((err, svd_err)).unwrap();
+    assert_eq!(
+        json,
+        json!([
+            {
+                "kind": "ParseInt",
+                "message": "Failed to parse `invalid digit found in string`",
+                "position": { "line": 16, "column": 11 },
+                "path": "device/peripherals/peripheral/registers/register/size",
+            },
+            {
+                "kind": "Interrupt",
+                "message": "`Interrupt error: Interrupt `TIM1` value 1000 is out of range 0..=495",
+            },
+        ])
+    );
+}
@@ -1,12 +1,13 @@
-use super::run_test;
+use super::{parse, parse_with_config, run_test, warnings};
 use crate::svd::{
     Access, BitRange, BitRangeType, DimElement, EnumeratedValue, EnumeratedValues, Field,
-    FieldInfo, ValidateLevel,
+    FieldInfo, ModifiedWriteValues, Peripheral, ReadAction, RegisterInfo, Usage, ValidateLevel,
 };
+use svd_parser::Config;
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         Field::Array(
             FieldInfo::builder()
                 .name("MODE%s".to_string())
@@ -49,7 +50,7 @@ fn decode_encode() {
     encode_config.update("field_name", "Snake");
     encode_config.update("field_bit_range", "MsbLsb");
 
-    let tests = vec![(
+    let tests = [(
         FieldInfo::builder()
             .name("MODE".to_string())
             .derived_from(Some("other_field".to_string()))
@@ -81,7 +82,7 @@ fn decode_encode() {
 
 #[test]
 fn decode_encode_info() {
-    let tests = vec![
+    let tests = [
         (
             FieldInfo::builder()
                 .name("MODE".to_string())
@@ -169,3 +170,422 @@ fn decode_encode_info() {
 
     run_test::<FieldInfo>(&tests[..], None, None);
 }
+
+#[test]
+fn write_once_modified_write_values() {
+    let builder = FieldInfo::builder()
+        .name("EN".to_string())
+        .bit_range(BitRange::from_offset_width(0, 1))
+        .access(Some(Access::WriteOnce))
+        .modified_write_values(Some(ModifiedWriteValues::OneToToggle));
+
+    let err = builder.clone().build(ValidateLevel::Strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Field error: Field `EN` with `writeOnce` access can't have `oneToToggle` modifiedWriteValues"
+    );
+    assert!(builder.build(ValidateLevel::Weak).is_ok());
+
+    assert!(FieldInfo::builder()
+        .name("EN".to_string())
+        .bit_range(BitRange::from_offset_width(0, 1))
+        .access(Some(Access::ReadWriteOnce))
+        .modified_write_values(Some(ModifiedWriteValues::OneToClear))
+        .build(ValidateLevel::Strict)
+        .is_ok());
+}
+
+#[test]
+fn empty_enumerated_values() {
+    let xml = "
+        <field>
+            <name>MODE</name>
+            <bitRange>[1:0]</bitRange>
+            <enumeratedValues>
+                <name>MODE_VALUES</name>
+            </enumeratedValues>
+        </field>
+    ";
+
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+    assert!(parse_with_config::<FieldInfo>(xml, &strict).is_err());
+
+    let field = parse_with_config::<FieldInfo>(xml, &Config::default()).unwrap();
+    assert!(field.enumerated_values.is_empty());
+    assert!(warnings()
+        .iter()
+        .any(|w| w == "Skipping empty `enumeratedValues` `MODE_VALUES`"));
+}
+
+#[test]
+fn reset_contribution() {
+    let field = |offset, width| {
+        FieldInfo::builder()
+            .name("F".to_string())
+            .bit_range(BitRange::from_offset_width(offset, width))
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    let reset = 0x0000_3A00;
+    assert_eq!(field(8, 4).reset_contribution(reset), 0xA);
+    assert_eq!(field(12, 2).reset_contribution(reset), 0x3);
+    assert_eq!(field(0, 8).reset_contribution(reset), 0);
+    assert_eq!(field(0, 32).reset_contribution(reset), reset);
+}
+
+#[test]
+fn modify_from() {
+    let mut field = FieldInfo::builder()
+        .name("MODE".to_string())
+        .bit_range(BitRange::from_offset_width(4, 2))
+        .enumerated_values(vec![EnumeratedValues::builder()
+            .values(vec![EnumeratedValue::builder()
+                .name("FAST".to_string())
+                .value(Some(3))
+                .build(ValidateLevel::Strict)
+                .unwrap()])
+            .build(ValidateLevel::Strict)
+            .unwrap()])
+        .build(ValidateLevel::Strict)
+        .unwrap();
+
+    field
+        .modify_from(
+            FieldInfo::builder()
+                .bit_width(3)
+                .access(Some(Access::ReadOnly)),
+            ValidateLevel::Strict,
+        )
+        .unwrap();
+    assert_eq!(field.name, "MODE");
+    assert_eq!(field.bit_range, BitRange::from_offset_width(4, 3));
+    assert_eq!(field.access, Some(Access::ReadOnly));
+    assert_eq!(field.enumerated_values.len(), 1);
+
+    assert!(field
+        .modify_from(FieldInfo::builder().bit_width(0), ValidateLevel::Strict)
+        .is_err());
+}
+
+#[test]
+fn merge_enums() {
+    let field = |values: &[(&str, u64)]| {
+        let values = values
+            .iter()
+            .map(|&(name, value)| {
+                EnumeratedValue::builder()
+                    .name(name.to_string())
+                    .value(Some(value))
+                    .build(ValidateLevel::Strict)
+                    .unwrap()
+            })
+            .collect();
+        FieldInfo::builder()
+            .name("MODE".to_string())
+            .bit_range(BitRange::from_offset_width(0, 2))
+            .enumerated_values(vec![EnumeratedValues::builder()
+                .values(values)
+                .build(ValidateLevel::Strict)
+                .unwrap()])
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+
+    let mut f = field(&[("INPUT", 0), ("OUTPUT", 1)]);
+    f.merge_enums(&field(&[("OUTPUT", 1), ("ALT", 2)])).unwrap();
+    let names: Vec<_> = f.enumerated_values[0]
+        .values
+        .iter()
+        .map(|v| (v.name.as_str(), v.value))
+        .collect();
+    assert_eq!(
+        names,
+        [("INPUT", Some(0)), ("OUTPUT", Some(1)), ("ALT", Some(2))]
+    );
+
+    let before = f.clone();
+    let err = f.merge_enums(&field(&[("ANALOG", 2)])).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Field error: Field `MODE` has conflicting enumerated values `ALT` and `ANALOG`"
+    );
+    assert_eq!(f, before);
+}
+
+#[test]
+fn enumerated_value_range() {
+    let field = |value| {
+        FieldInfo::builder()
+            .name("MODE".to_string())
+            .bit_range(BitRange::from_offset_width(0, 2))
+            .enumerated_values(vec![EnumeratedValues::builder()
+                .values(vec![EnumeratedValue::builder()
+                    .name("V".to_string())
+                    .value(Some(value))
+                    .build(ValidateLevel::Strict)
+                    .unwrap()])
+                .build(ValidateLevel::Strict)
+                .unwrap()])
+    };
+    assert!(field(1).build(ValidateLevel::Strict).is_ok());
+    assert!(field(3).build(ValidateLevel::Strict).is_ok());
+    assert_eq!(
+        field(7)
+            .build(ValidateLevel::Strict)
+            .unwrap_err()
+            .to_string(),
+        "`EnumeratedValue error: Value 7 out of range [0 - 3]"
+    );
+    assert!(field(4).build(ValidateLevel::Weak).is_err());
+    assert!(field(7).build(ValidateLevel::Disabled).is_ok());
+}
+
+#[test]
+fn side_effects() {
+    let tests = [(
+        FieldInfo::builder()
+            .name("OVR".to_string())
+            .bit_range(BitRange::from_offset_width(5, 1))
+            .modified_write_values(Some(ModifiedWriteValues::OneToClear))
+            .read_action(Some(ReadAction::Clear))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "
+        <field>
+          <name>OVR</name>
+          <bitOffset>5</bitOffset>
+          <bitWidth>1</bitWidth>
+          <modifiedWriteValues>oneToClear</modifiedWriteValues>
+          <readAction>clear</readAction>
+        </field>
+        ",
+        "
+        <field>
+          <name>OVR</name>
+          <bitOffset>5</bitOffset>
+          <bitWidth>1</bitWidth>
+          <modifiedWriteValues>oneToClear</modifiedWriteValues>
+          <readAction>clear</readAction>
+        </field>
+        ",
+    )];
+    run_test::<FieldInfo>(&tests[..], None, None);
+
+    let config = Config::default();
+    let xml = tests[0].1.replace(">clear<", ">clearOnRead<");
+    let err = parse_with_config::<FieldInfo>(&xml, &config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid readAction variant, found clearOnRead"
+    );
+    let xml = tests[0].1.replace("oneToClear", "oneToWipe");
+    let err = parse_with_config::<FieldInfo>(&xml, &config).unwrap_err();
+    assert!(err.to_string().contains("oneToWipe"), "{err}");
+}
+
+#[test]
+fn bit_range_styles() {
+    let field = |bit_range| {
+        FieldInfo::builder()
+            .name("MODE".to_string())
+            .bit_range(bit_range)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    let tests = [
+        (
+            field(BitRange::from_bit_range("[7:4]").unwrap()),
+            "<field><name>MODE</name><bitRange>[7:4]</bitRange></field>",
+            "<field><name>MODE</name><bitRange>[7:4]</bitRange></field>",
+        ),
+        (
+            field(BitRange::from_offset_width(4, 4)),
+            "<field><name>MODE</name><bitOffset>4</bitOffset><bitWidth>4</bitWidth></field>",
+            "<field><name>MODE</name><bitOffset>4</bitOffset><bitWidth>4</bitWidth></field>",
+        ),
+        (
+            field(BitRange::from_msb_lsb(7, 4)),
+            "<field><name>MODE</name><lsb>4</lsb><msb>7</msb></field>",
+            "<field><name>MODE</name><lsb>4</lsb><msb>7</msb></field>",
+        ),
+    ];
+    run_test::<FieldInfo>(&tests[..], None, None);
+
+    let styles: Vec<_> = tests.iter().map(|t| t.0.bit_range.range_type).collect();
+    assert_eq!(
+        styles,
+        [
+            BitRangeType::BitRange,
+            BitRangeType::OffsetWidth,
+            BitRangeType::MsbLsb
+        ]
+    );
+}
+
+#[test]
+fn enumerated_values_usage() {
+    let field = |usages: [&str; 2]| {
+        let evs: String = usages
+            .iter()
+            .map(|usage| {
+                format!(
+                    "<enumeratedValues><usage>{usage}</usage><enumeratedValue><name>A</name><value>0</value></enumeratedValue></enumeratedValues>"
+                )
+            })
+            .collect();
+        format!("<field><name>MODE</name><bitRange>[1:0]</bitRange>{evs}</field>")
+    };
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+
+    let xml = field(["read", "write"]);
+    let f = parse_with_config::<FieldInfo>(&xml, &strict).unwrap();
+    assert_eq!(f.enumerated_values[0].usage, Some(Usage::Read));
+    assert_eq!(f.enumerated_values[1].usage, Some(Usage::Write));
+    assert!(f.get_enumerated_values(Usage::Write).is_some());
+    run_test::<FieldInfo>(&[(f, &xml, &xml)], Some(strict), None);
+
+    for usages in [["read", "read"], ["read-write", "read"]] {
+        let err = parse_with_config::<FieldInfo>(&field(usages), &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Field error: You can have 0, 1 or 2 enumeratedValues with different usage"
+        );
+        assert!(parse_with_config::<FieldInfo>(&field(usages), &Config::default()).is_ok());
+    }
+}
+
+#[test]
+fn effective_access() {
+    let xml = |peripheral_access: &str| {
+        format!(
+            "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      {peripheral_access}
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+          <access>read-only</access>
+          <fields>
+            <field>
+              <name>CLR</name>
+              <bitRange>[0:0]</bitRange>
+              <access>write-only</access>
+            </field>
+            <field>
+              <name>BUSY</name>
+              <bitRange>[1:1]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x4</addressOffset>
+          <fields>
+            <field>
+              <name>DATA</name>
+              <bitRange>[7:0]</bitRange>
+            </field>
+          </fields>
+        </register>
+      </registers>
+    </peripheral>"
+        )
+    };
+    let access = |p: &Peripheral, reg: &str, field: &str| {
+        let r = p.get_register(reg).unwrap();
+        let f = r.get_field(field).unwrap();
+        let parent = &p.default_register_properties;
+        let props = r.properties.inherit_from(parent);
+        (r.effective_access(parent), f.effective_access(&props))
+    };
+
+    let p = parse::<Peripheral>(&xml("<access>write-only</access>"));
+    assert_eq!(p.effective_access(), Access::WriteOnly);
+    assert_eq!(
+        access(&p, "SR", "CLR"),
+        (Access::ReadOnly, Access::WriteOnly)
+    );
+    assert_eq!(
+        access(&p, "SR", "BUSY"),
+        (Access::ReadOnly, Access::ReadOnly)
+    );
+    assert_eq!(
+        access(&p, "DR", "DATA"),
+        (Access::WriteOnly, Access::WriteOnly)
+    );
+
+    let p = parse::<Peripheral>(&xml(""));
+    assert_eq!(p.effective_access(), Access::ReadWrite);
+    assert_eq!(
+        access(&p, "DR", "DATA"),
+        (Access::ReadWrite, Access::ReadWrite)
+    );
+}
+
+#[test]
+fn reset_value() {
+    let register = parse::<RegisterInfo>(
+        "
+        <register>
+          <name>CTRL</name>
+          <addressOffset>0x0</addressOffset>
+          <resetValue>0x12345678</resetValue>
+          <resetMask>0x0000FFFF</resetMask>
+          <fields>
+            <field>
+              <name>LOW</name>
+              <bitRange>[11:4]</bitRange>
+            </field>
+            <field>
+              <name>MID</name>
+              <bitRange>[19:12]</bitRange>
+            </field>
+          </fields>
+        </register>
+        ",
+    );
+    let low = register.get_field("LOW").unwrap();
+    let mid = register.get_field("MID").unwrap();
+    assert_eq!(low.reset_value(&register), Some(0x67));
+    assert_eq!(mid.reset_value(&register), None);
+
+    let mut unmasked = register.clone();
+    unmasked.properties.reset_mask = None;
+    assert_eq!(mid.reset_value(&unmasked), Some(0x45));
+
+    let mut no_reset = register.clone();
+    no_reset.properties.reset_value = None;
+    assert_eq!(low.reset_value(&no_reset), None);
+}
+
+#[test]
+fn enumerated_value_pattern_range() {
+    let xml = |width, value| {
+        format!(
+            "
+            <field>
+              <name>MODE</name>
+              <bitOffset>0</bitOffset>
+              <bitWidth>{width}</bitWidth>
+              <enumeratedValues>
+                <enumeratedValue>
+                  <name>V</name>
+                  <value>{value}</value>
+                </enumeratedValue>
+              </enumeratedValues>
+            </field>
+            "
+        )
+    };
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+    assert!(parse_with_config::<FieldInfo>(&xml(3, "0b1x1"), &strict).is_ok());
+    let err = parse_with_config::<FieldInfo>(&xml(3, "0b1xx1"), &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`EnumeratedValue error: Value 9 out of range [0 - 7]"
+    );
+    assert!(parse_with_config::<FieldInfo>(&xml(64, "0xffffffffffffffff"), &strict).is_ok());
+}
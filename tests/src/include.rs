@@ -0,0 +1,59 @@
+use crate::device_xml;
+use std::fs;
+use std::path::PathBuf;
+
+fn device() -> String {
+    device_xml(
+        r#"
+    <xi:include href="periph/uart.xml"/>"#,
+    )
+    .replace(
+        r#"schemaVersion="1.3""#,
+        r#"schemaVersion="1.3" xmlns:xi="http://www.w3.org/2001/XInclude""#,
+    )
+}
+
+const UART: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<peripheral>
+  <name>UART</name>
+  <baseAddress>0x40002000</baseAddress>
+</peripheral>
+"#;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("svd-include-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("periph")).unwrap();
+    dir
+}
+
+#[test]
+fn include_peripheral() {
+    let dir = temp_dir("ok");
+    fs::write(dir.join("dev.svd"), device()).unwrap();
+    fs::write(dir.join("periph/uart.xml"), UART).unwrap();
+
+    let device =
+        svd_parser::parse_file_with_includes(&dir.join("dev.svd"), &Default::default()).unwrap();
+    let uart = device.get_peripheral("UART").unwrap();
+    assert_eq!(uart.base_address, 0x40002000);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn include_errors() {
+    let dir = temp_dir("err");
+    let err = svd_parser::resolve_includes(&device(), &dir).unwrap_err();
+    assert!(format!("{:#}", err).contains("uart.xml` not found"));
+
+    fs::write(dir.join("dev.svd"), device()).unwrap();
+    fs::write(
+        dir.join("periph/uart.xml"),
+        r#"<xi:include xmlns:xi="http://www.w3.org/2001/XInclude" href="../dev.svd"/>"#,
+    )
+    .unwrap();
+    let err = svd_parser::parse_file_with_includes(&dir.join("dev.svd"), &Default::default())
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains("Include cycle"));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -1,9 +1,9 @@
-use super::run_test;
+use super::{parse, run_test, warnings};
 use crate::svd::{Interrupt, ValidateLevel};
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         Interrupt::builder()
             .name("test".to_string())
             .description(Some("description".to_string()))
@@ -30,7 +30,7 @@ fn decode_encode() {
     let mut encode_config = svd_encoder::Config::default();
     encode_config.update("interrupt_name", "Constant");
 
-    let tests = vec![(
+    let tests = [(
         Interrupt::builder()
             .name("test".to_string())
             .description(Some("description".to_string()))
@@ -53,3 +53,32 @@ fn decode_encode() {
 
     run_test::<Interrupt>(&tests[..], Some(parse_config), Some(encode_config));
 }
+
+#[test]
+fn value_range() {
+    let builder = |value| Interrupt::builder().name("TIM1".to_string()).value(value);
+
+    assert!(builder(495).build(ValidateLevel::Strict).is_ok());
+
+    let err = builder(0x4000_0000)
+        .build(ValidateLevel::Strict)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Interrupt error: Interrupt `TIM1` value 1073741824 is out of range 0..=495"
+    );
+    assert!(builder(0x4000_0000).build(ValidateLevel::Weak).is_ok());
+
+    let irq = builder(100).build(ValidateLevel::Strict).unwrap();
+    assert!(irq.validate_max(99, ValidateLevel::Strict).is_err());
+    assert!(irq.validate_max(100, ValidateLevel::Strict).is_ok());
+
+    let xml = "
+        <interrupt>
+            <name>HUGE</name>
+            <value>0x40000000</value>
+        </interrupt>";
+    let irq: Interrupt = parse(xml);
+    assert_eq!(irq.value, 0x4000_0000);
+    assert!(warnings().iter().any(|w| w.contains("`HUGE`")));
+}
@@ -2,6 +2,7 @@
 
 use core::str;
 use roxmltree::Document;
+use std::sync::{Mutex, Once};
 use svd_encoder::{Encode, EncodeError};
 use svd_parser::{Config, Parse, SVDErrorAt};
 use svd_rs as svd;
@@ -27,7 +28,7 @@ pub fn run_test<
         let rotree = Document::parse(t.1).unwrap();
         let elem = T::parse(
             &rotree.root().first_element_child().unwrap(),
-            &parser_config.unwrap_or(Config::default()),
+            &parser_config.unwrap_or_default(),
         )
         .unwrap();
         assert_eq!(
@@ -37,7 +38,7 @@ pub fn run_test<
 
         let tree1 = Element::parse(t.2.as_bytes()).unwrap();
         let tree2 = elem
-            .encode_with_config(&encoder_config.unwrap_or(svd_encoder::Config::default()))
+            .encode_with_config(&encoder_config.unwrap_or_default())
             .unwrap();
         assert_eq!(
             tree1, tree2,
@@ -46,18 +47,91 @@ pub fn run_test<
     }
 }
 
+struct WarningLogger;
+
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl log::Log for WarningLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            WARNINGS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+    fn flush(&self) {}
+}
+
+/// Start capturing warnings logged by the parser
+pub fn init_logger() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&WarningLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+    });
+}
+
+/// Returns all warnings logged so far (by any test)
+pub fn warnings() -> Vec<String> {
+    init_logger();
+    WARNINGS.lock().unwrap().clone()
+}
+
+/// Parse a single element from xml using the default parser config
+pub fn parse<T: Parse<Error = SVDErrorAt, Object = T, Config = Config>>(xml: &str) -> T {
+    parse_with_config::<T>(xml, &Config::default()).unwrap()
+}
+
+/// Parse a single element from xml
+pub fn parse_with_config<T: Parse<Error = SVDErrorAt, Config = Config>>(
+    xml: &str,
+    config: &Config,
+) -> Result<T::Object, SVDErrorAt> {
+    init_logger();
+    let rotree = Document::parse(xml).unwrap();
+    T::parse(&rotree.root().first_element_child().unwrap(), config)
+}
+
+/// Wraps `peripherals` in a minimal device
+pub fn device_xml(peripherals: &str) -> String {
+    device_xml_with("", peripherals)
+}
+
+/// Like [`device_xml`] with extra device-level `properties` after `<width>`
+pub fn device_xml_with(properties: &str, peripherals: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<device schemaVersion="1.3">
+  <name>DEV</name>
+  <version>1.0</version>
+  <description>Test device</description>
+  <addressUnitBits>8</addressUnitBits>
+  <width>32</width>{properties}
+  <peripherals>{peripherals}
+  </peripherals>
+</device>
+"#
+    )
+}
+
 mod access;
 mod addressblock;
 //mod bitrange;
 mod cpu;
+mod device;
 mod dimelement;
 mod endian;
 mod enumeratedvalue;
+mod error;
 //mod enumeratedvalues;
 mod field;
+mod include;
 mod interrupt;
 mod modifiedwritevalues;
+mod peripheral;
 mod register;
 //mod registerproperties;
+mod types;
 mod usage;
 mod writeconstraint;
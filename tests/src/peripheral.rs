@@ -0,0 +1,1036 @@
+use super::{parse, parse_with_config, warnings};
+use crate::svd::{
+    layout::{ArrayDim, LayoutKind},
+    AddressBlock, AddressBlockUsage, NameMangler, Peripheral, PeripheralInfo, ValidateLevel,
+};
+use core::ops::Range;
+use core::str;
+use svd_encoder::Encode;
+use xmltree::{Element, XMLNode};
+
+const PERIPHERAL: &str = "
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <dim>16</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>CCR%s</name>
+          <addressOffset>0x10</addressOffset>
+        </register>
+        <cluster>
+          <name>CH</name>
+          <addressOffset>0x50</addressOffset>
+          <register>
+            <name>DR</name>
+            <addressOffset>0x0</addressOffset>
+            <size>16</size>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+";
+
+#[test]
+fn generate_address_block() {
+    let mut p = parse::<Peripheral>(PERIPHERAL);
+
+    let block = |size| {
+        AddressBlock::builder()
+            .offset(0)
+            .size(size)
+            .usage(AddressBlockUsage::Registers)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    // last register ends at 0x52
+    assert_eq!(
+        p.generate_address_block(AddressBlockUsage::Registers),
+        block(0x80)
+    );
+    assert_eq!(
+        p.generate_address_block_aligned(AddressBlockUsage::Registers, 0x20),
+        block(0x60)
+    );
+
+    p.ensure_address_block();
+    assert_eq!(p.address_block, Some(vec![block(0x80)]));
+
+    p.address_block = Some(vec![block(0x400)]);
+    p.ensure_address_block();
+    assert_eq!(p.address_block, Some(vec![block(0x400)]));
+}
+
+#[test]
+fn prune_padding() {
+    let mut p = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40002000</baseAddress>
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>RESERVED0</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+        <register>
+          <name>RESERVED_DATA</name>
+          <addressOffset>0x8</addressOffset>
+        </register>
+        <cluster>
+          <name>CH</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>reserved%s</name>
+            <dim>2</dim>
+            <dimIncrement>4</dimIncrement>
+            <addressOffset>0x0</addressOffset>
+          </register>
+          <register>
+            <name>DR</name>
+            <addressOffset>0x8</addressOffset>
+          </register>
+        </cluster>
+        <register>
+          <name>GAP1</name>
+          <addressOffset>0x20</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+",
+    );
+
+    assert!(p.get_register("RESERVED0").unwrap().is_padding());
+    assert!(!p.get_register("RESERVED_DATA").unwrap().is_padding());
+
+    p.prune_padding();
+    let names: Vec<_> = p.all_registers().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["SR", "RESERVED_DATA", "DR", "GAP1"]);
+
+    p.prune_padding_with(&["GAP"]);
+    let names: Vec<_> = p.all_registers().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["SR", "RESERVED_DATA", "DR"]);
+}
+
+#[test]
+fn array_out_of_address_block() {
+    let xml = "
+    <peripheral>
+      <name>DMA</name>
+      <baseAddress>0x40020000</baseAddress>
+      <addressBlock>
+        <offset>0x0</offset>
+        <size>0x20</size>
+        <usage>registers</usage>
+      </addressBlock>
+      <registers>
+        <register>
+          <dim>16</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>DR%s</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ";
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    let err = parse_with_config::<Peripheral>(xml, &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Peripheral error: Register `DR8` at offset 0x20 is outside of address blocks"
+    );
+
+    // fits when the block is large enough
+    let xml = xml.replace("<size>0x20</size>", "<size>0x40</size>");
+    assert!(parse_with_config::<Peripheral>(&xml, &strict).is_ok());
+}
+
+#[test]
+fn expand_array_with_padding() {
+    let gpio = |increment: &str| {
+        format!(
+            "
+    <peripheral>
+      <dim>4</dim>
+      <dimIncrement>{increment}</dimIncrement>
+      <name>GPIO%s</name>
+      <baseAddress>0x48000000</baseAddress>
+      <registers>
+        <register>
+          <dim>64</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>R%s</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+"
+        )
+    };
+
+    let p = parse::<Peripheral>(&gpio("0x400"));
+    p.validate_all(ValidateLevel::Strict).unwrap();
+    let instances = p.expanded();
+    assert_eq!(instances.len(), 4);
+    for (i, info) in instances.iter().enumerate() {
+        assert_eq!(info.name, format!("GPIO{i}"));
+        assert_eq!(info.base_address, 0x48000000 + i as u64 * 0x400);
+    }
+
+    let p = parse::<Peripheral>(&gpio("0x80"));
+    assert!(p.validate_all(ValidateLevel::Strict).is_err());
+    assert!(p.validate_all(ValidateLevel::Weak).is_ok());
+}
+
+#[test]
+fn sort_registers() {
+    let xml = "
+    <peripheral>
+      <name>I2C</name>
+      <baseAddress>0x40005400</baseAddress>
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x14</addressOffset>
+        </register>
+        <cluster>
+          <name>BUF</name>
+          <addressOffset>0x20</addressOffset>
+          <register>
+            <name>TX</name>
+            <addressOffset>0x4</addressOffset>
+          </register>
+          <register>
+            <name>RX</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+        <register>
+          <dim>2</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>OAR%s</name>
+          <addressOffset>0x8</addressOffset>
+        </register>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+";
+    let names = |p: &Peripheral| {
+        let mut names: Vec<_> = p
+            .registers
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|rc| rc.name().clone())
+            .collect();
+        names.extend(
+            p.clusters()
+                .flat_map(|c| c.children.iter().map(|rc| rc.name().clone())),
+        );
+        names
+    };
+
+    let mut p = parse::<Peripheral>(xml);
+    p.sort_registers_by_offset();
+    assert_eq!(names(&p), ["CR", "OAR%s", "SR", "BUF", "RX", "TX"]);
+
+    p.sort_registers_by_name();
+    assert_eq!(names(&p), ["BUF", "CR", "OAR%s", "SR", "RX", "TX"]);
+    assert_eq!(p.registers().count(), 3);
+}
+
+#[test]
+fn address_gaps() {
+    let mut p = parse::<Peripheral>(PERIPHERAL);
+    // CR ends at 0x4, CCR0 starts at 0x10, CH.DR ends at 0x52
+    assert_eq!(
+        p.address_gaps(),
+        [Range {
+            start: 4,
+            end: 0x10
+        }]
+    );
+
+    let block = AddressBlock::builder()
+        .offset(0)
+        .size(0x100)
+        .usage(AddressBlockUsage::Registers)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    p.address_block = Some(vec![block]);
+    assert_eq!(p.address_gaps(), [0x4..0x10, 0x52..0x100]);
+}
+
+#[test]
+fn layout() {
+    let layout = parse::<Peripheral>(PERIPHERAL).layout();
+    assert_eq!(layout.size, 0x52);
+    let items: Vec<_> = layout
+        .items
+        .iter()
+        .map(|i| (i.offset, i.size, &i.kind))
+        .collect();
+    assert!(matches!(
+        items[..],
+        [
+            (0, 4, LayoutKind::Register { .. }),
+            (0x4, 0xc, LayoutKind::Reserved),
+            (
+                0x10,
+                0x40,
+                LayoutKind::Register {
+                    dim: Some(ArrayDim {
+                        count: 16,
+                        stride: 4
+                    }),
+                    ..
+                }
+            ),
+            (0x50, 2, LayoutKind::Cluster { .. }),
+        ]
+    ));
+    match items[3].2 {
+        LayoutKind::Cluster { name, layout, dim } => {
+            assert_eq!(name, "CH");
+            assert_eq!(*dim, None);
+            assert_eq!(layout.size, 2);
+            assert_eq!(layout.items.len(), 1);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn interrupts() {
+    let xml = "
+    <peripheral>
+      <name>DMA1</name>
+      <baseAddress>0x40026000</baseAddress>
+      <interrupt>
+        <name>DMA1_CH0</name>
+        <description>DMA1 channel 0 global interrupt</description>
+        <value>11</value>
+      </interrupt>
+      <interrupt>
+        <name>DMA1_CH1</name>
+        <value>12</value>
+      </interrupt>
+    </peripheral>
+";
+    let p = parse::<Peripheral>(xml);
+    let ch0 = p.get_interrupt("DMA1_CH0").unwrap();
+    assert_eq!(ch0.value, 11);
+    assert_eq!(
+        ch0.description.as_deref(),
+        Some("DMA1 channel 0 global interrupt")
+    );
+    assert_eq!(p.get_interrupt("DMA1_CH1").unwrap().description, None);
+    assert!(p.get_interrupt("DMA1_CH2").is_none());
+
+    let xml = xml.replace("DMA1_CH1", "DMA1_CH0");
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    let err = parse_with_config::<Peripheral>(&xml, &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Peripheral error: Interrupt `DMA1_CH0` is defined more than once"
+    );
+    assert!(parse_with_config::<Peripheral>(&xml, &Default::default()).is_ok());
+}
+
+#[test]
+fn annotate_addresses() {
+    let p = parse::<Peripheral>(PERIPHERAL);
+    let config = svd_encoder::Config::default().annotate_addresses(true);
+    let elem = p.encode_with_config(&config).unwrap();
+
+    let registers = elem.get_child("registers").unwrap();
+    let comments = |e: &Element| -> Vec<String> {
+        e.children
+            .iter()
+            .filter_map(|n| match n {
+                XMLNode::Comment(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(comments(registers), [" @ 0x40010000 ", " @ 0x40010010 "]);
+    let cluster = registers.get_child("cluster").unwrap();
+    assert_eq!(comments(cluster), [" @ 0x40010050 "]);
+
+    let mut xml = Vec::new();
+    elem.write(&mut xml).unwrap();
+    assert_eq!(parse::<Peripheral>(str::from_utf8(&xml).unwrap()), p);
+
+    let plain = p.encode().unwrap();
+    assert!(comments(plain.get_child("registers").unwrap()).is_empty());
+
+    // addresses are taken from the model, not from the offsets as written
+    let xml = PERIPHERAL.replace(
+        "<addressOffset>0x10</addressOffset>",
+        "<addressOffset>#10000</addressOffset>",
+    );
+    let literals = svd_parser::Config::default().keep_literals(true);
+    let p = parse_with_config::<Peripheral>(&xml, &literals).unwrap();
+    let elem = p.encode_with_config(&config).unwrap();
+    let registers = elem.get_child("registers").unwrap();
+    assert_eq!(comments(registers), [" @ 0x40010000 ", " @ 0x40010010 "]);
+}
+
+#[test]
+fn missing_address_offset() {
+    let xml = "
+    <peripheral>
+      <name>CRC</name>
+      <baseAddress>0x40023000</baseAddress>
+      <registers>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>IDR</name>
+          <size>8</size>
+        </register>
+        <register>
+          <name>CR</name>
+        </register>
+      </registers>
+    </peripheral>
+";
+    let p = parse::<Peripheral>(xml);
+    assert_eq!(p.get_register("IDR").unwrap().address_offset, 0x4);
+    assert_eq!(p.get_register("CR").unwrap().address_offset, 0x5);
+    assert!(warnings()
+        .iter()
+        .any(|w| w == "Register `IDR` has no `addressOffset`, placing it at 0x4"));
+
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    assert!(parse_with_config::<Peripheral>(xml, &strict).is_err());
+
+    // registers at the end of the address space don't overflow the next offset
+    let xml = "
+    <peripheral>
+      <name>CRC</name>
+      <baseAddress>0x0</baseAddress>
+      <registers>
+        <register>
+          <name>DR</name>
+          <addressOffset>0xFFFFFFFC</addressOffset>
+        </register>
+        <register>
+          <dim>0x10001</dim>
+          <dimIncrement>0x10000</dimIncrement>
+          <name>CR%s</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x8</addressOffset>
+        </register>
+        <register>
+          <name>IDR</name>
+        </register>
+      </registers>
+    </peripheral>
+";
+    let p = parse::<Peripheral>(xml);
+    assert_eq!(p.get_register("SR").unwrap().address_offset, 0x8);
+    assert_eq!(p.get_register("IDR").unwrap().address_offset, 0xc);
+    let xml = xml.replace("<addressOffset>0x8</addressOffset>", "");
+    assert!(parse_with_config::<Peripheral>(&xml, &svd_parser::Config::default()).is_err());
+}
+
+#[test]
+fn custom_name_mangler() {
+    struct Snake;
+    impl NameMangler for Snake {
+        fn array_element(&self, base: &str, index: &str) -> String {
+            format!("{}_{}", self.sanitize(base), index).to_lowercase()
+        }
+    }
+
+    let p = parse::<Peripheral>(
+        "
+    <peripheral>
+      <dim>2</dim>
+      <dimIncrement>0x400</dimIncrement>
+      <dimIndex>A,B</dimIndex>
+      <name>GPIO%s</name>
+      <baseAddress>0x48000000</baseAddress>
+    </peripheral>
+",
+    );
+    let names = |ps: Vec<PeripheralInfo>| ps.into_iter().map(|p| p.name).collect::<Vec<_>>();
+    assert_eq!(names(p.expanded()), ["GPIOA", "GPIOB"]);
+    assert_eq!(names(p.expanded_with(&Snake)), ["gpio_a", "gpio_b"]);
+}
+
+#[test]
+fn comments() {
+    let xml = "
+    <peripheral>
+      <name>TIMER</name>
+      <baseAddress>0x40010000</baseAddress>
+      <registers>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+        <!-- control register -->
+        <!-- write only when disabled -->
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <!-- enable -->
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <!-- channel -->
+        <cluster>
+          <name>CH</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    ";
+    let p = parse::<Peripheral>(xml);
+    assert!(p.get_register("SR").unwrap().comments.is_empty());
+    assert_eq!(p.get_cluster("CH").unwrap().comments, [" channel "]);
+    let cr = p.get_register("CR").unwrap();
+    assert_eq!(
+        cr.comments,
+        [" control register ", " write only when disabled "]
+    );
+    assert_eq!(cr.get_field("EN").unwrap().comments, [" enable "]);
+
+    let elem = p.encode().unwrap();
+    let registers = elem.get_child("registers").unwrap();
+    let nodes: Vec<_> = registers
+        .children
+        .iter()
+        .map(|n| match n {
+            XMLNode::Comment(c) => c.clone(),
+            XMLNode::Element(e) => e.get_child("name").unwrap().get_text().unwrap().into(),
+            _ => panic!("unexpected node"),
+        })
+        .collect();
+    assert_eq!(
+        nodes,
+        [
+            "SR",
+            " control register ",
+            " write only when disabled ",
+            "CR",
+            " channel ",
+            "CH"
+        ]
+    );
+
+    let mut out = Vec::new();
+    elem.write(&mut out).unwrap();
+    assert_eq!(parse::<Peripheral>(str::from_utf8(&out).unwrap()), p);
+}
+
+#[test]
+fn get_register() {
+    let xml = "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <dim>4</dim>
+              <dimIncrement>1</dimIncrement>
+              <name>EN%s</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <register>
+          <dim>4</dim>
+          <dimIncrement>4</dimIncrement>
+          <name>DR[%s]</name>
+          <addressOffset>0x10</addressOffset>
+        </register>
+        <cluster>
+          <dim>2</dim>
+          <dimIncrement>0x10</dimIncrement>
+          <name>CH%s</name>
+          <addressOffset>0x20</addressOffset>
+          <register>
+            <name>CFG</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    ";
+    let mut p = parse::<Peripheral>(xml);
+
+    let cr = p.get_register("CR").unwrap();
+    assert_eq!(cr.address_offset, 0);
+    assert_eq!(cr.get_field("EN2").unwrap().name, "EN%s");
+    assert!(cr.get_field("EN4").is_none());
+
+    assert_eq!(p.get_register("DR[%s]").unwrap().address_offset, 0x10);
+    assert_eq!(p.get_register("DR[3]").unwrap().name, "DR[%s]");
+    assert_eq!(p.get_register("DR3").unwrap().name, "DR[%s]");
+    assert!(p.get_register("DR[4]").is_none());
+
+    assert_eq!(p.get_cluster("CH1").unwrap().name, "CH%s");
+    assert_eq!(p.get_register("CH1.CFG").unwrap().name, "CFG");
+    assert!(p.get_register("CFG").is_none());
+    assert!(p.get_register("CR.CFG").is_none());
+
+    p.get_mut_register("CH%s.CFG").unwrap().address_offset = 4;
+    assert_eq!(p.get_register("CH0.CFG").unwrap().address_offset, 4);
+}
+
+#[test]
+fn overlapping_registers() {
+    let peripheral = |second: &str| {
+        format!(
+            "<peripheral>
+              <name>UART</name>
+              <baseAddress>0x40004000</baseAddress>
+              <registers>
+                <register>
+                  <dim>2</dim>
+                  <dimIncrement>4</dimIncrement>
+                  <name>DR%s</name>
+                  <addressOffset>0x0</addressOffset>
+                </register>
+                {second}
+              </registers>
+            </peripheral>"
+        )
+    };
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+
+    let adjacent = peripheral(
+        "<register><name>SR</name><addressOffset>0x8</addressOffset><size>16</size></register>",
+    );
+    assert!(parse_with_config::<Peripheral>(&adjacent, &strict).is_ok());
+
+    let overlap = peripheral(
+        "<register><name>SR</name><addressOffset>0x6</addressOffset><size>16</size></register>",
+    );
+    let err = parse_with_config::<Peripheral>(&overlap, &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Peripheral error: Register `DR1` at offset 0x4 overlaps register `SR` at offset 0x6"
+    );
+    let weak = svd_parser::Config::default().validate_level(ValidateLevel::Weak);
+    assert!(parse_with_config::<Peripheral>(&overlap, &weak).is_ok());
+
+    let alternate = peripheral(
+        "<register>
+          <name>DR0_ALT</name>
+          <alternateGroup>ALT</alternateGroup>
+          <addressOffset>0x0</addressOffset>
+        </register>",
+    );
+    assert!(parse_with_config::<Peripheral>(&alternate, &strict).is_ok());
+}
+
+#[test]
+fn alternates() {
+    let xml = "
+    <peripheral>
+      <name>TIM2_ALT</name>
+      <alternatePeripheral>TIM2</alternatePeripheral>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CCMR1_Output</name>
+          <addressOffset>0x18</addressOffset>
+        </register>
+        <register>
+          <name>CCMR1_Input</name>
+          <alternateGroup>Input</alternateGroup>
+          <addressOffset>0x18</addressOffset>
+        </register>
+        <register>
+          <name>CCMR2_Input</name>
+          <alternateRegister>CCMR2_Output</alternateRegister>
+          <addressOffset>0x1C</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ";
+    let p = parse::<Peripheral>(xml);
+    assert_eq!(p.alternate_peripheral.as_deref(), Some("TIM2"));
+    let input = p.get_register("CCMR1_Input").unwrap();
+    assert_eq!(input.alternate_group.as_deref(), Some("Input"));
+    let input = p.get_register("CCMR2_Input").unwrap();
+    assert_eq!(input.alternate_register.as_deref(), Some("CCMR2_Output"));
+
+    let elem = p.encode().unwrap();
+    assert_eq!(
+        elem.get_child("alternatePeripheral")
+            .unwrap()
+            .get_text()
+            .unwrap(),
+        "TIM2"
+    );
+    let mut out = Vec::new();
+    elem.write(&mut out).unwrap();
+    let out = str::from_utf8(&out).unwrap();
+    assert!(out.contains("<alternateGroup>Input</alternateGroup>"));
+    assert_eq!(parse::<Peripheral>(out), p);
+}
+
+#[test]
+fn merge() {
+    let mut p = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <description>UART</description>
+      <baseAddress>0x40004000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <resetValue>0x1</resetValue>
+        </register>
+        <register>
+          <name>SR</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ",
+    );
+    let patch = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <description>Universal asynchronous receiver transmitter</description>
+      <baseAddress>0x40004000</baseAddress>
+      <interrupt>
+        <name>UART</name>
+        <value>5</value>
+      </interrupt>
+      <registers>
+        <register>
+          <name>CR</name>
+          <description>Control register</description>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>DR</name>
+          <addressOffset>0x8</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ",
+    );
+    p.merge(&patch, ValidateLevel::Strict).unwrap();
+
+    assert_eq!(
+        p.description.as_deref(),
+        Some("Universal asynchronous receiver transmitter")
+    );
+    assert_eq!(p.interrupt.len(), 1);
+    let names: Vec<_> = p.registers().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["CR", "SR", "DR"]);
+    let cr = p.get_register("CR").unwrap();
+    assert_eq!(cr.description.as_deref(), Some("Control register"));
+    assert_eq!(cr.properties.reset_value, Some(1));
+
+    // merged registers are validated
+    let overlap = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      <registers>
+        <register>
+          <name>CR2</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ",
+    );
+    assert!(p.clone().merge(&overlap, ValidateLevel::Strict).is_err());
+    p.merge(&overlap, ValidateLevel::Weak).unwrap();
+    assert_eq!(p.registers().count(), 4);
+}
+
+#[test]
+fn merge_keeps_comments_and_fields() {
+    let mut p = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      <registers>
+        <!-- control register -->
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <!-- enable -->
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+            <field>
+              <name>MODE</name>
+              <bitRange>[2:1]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <!-- channel -->
+        <cluster>
+          <name>CH</name>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    ",
+    );
+    let patch = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0x40004000</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>EN</name>
+              <description>Enable</description>
+              <bitRange>[0:0]</bitRange>
+            </field>
+            <field>
+              <name>IE</name>
+              <bitRange>[3:3]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <cluster>
+          <name>CH</name>
+          <description>Channel</description>
+          <addressOffset>0x10</addressOffset>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    ",
+    );
+    p.merge(&patch, ValidateLevel::Strict).unwrap();
+
+    let cr = p.get_register("CR").unwrap();
+    assert_eq!(cr.comments, [" control register "]);
+    let names: Vec<_> = cr.fields().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["EN", "MODE", "IE"]);
+    let en = cr.get_field("EN").unwrap();
+    assert_eq!(en.description.as_deref(), Some("Enable"));
+    assert_eq!(en.comments, [" enable "]);
+    let ch = p.get_cluster("CH").unwrap();
+    assert_eq!(ch.description.as_deref(), Some("Channel"));
+    assert_eq!(ch.comments, [" channel "]);
+}
+
+#[test]
+fn merge_derived_from() {
+    let mut p = parse::<Peripheral>(
+        "
+    <peripheral>
+      <name>UART2</name>
+      <baseAddress>0x40004400</baseAddress>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ",
+    );
+    let patch = parse::<Peripheral>(
+        "
+    <peripheral derivedFrom=\"UART1\">
+      <name>UART2</name>
+      <baseAddress>0x0</baseAddress>
+      <registers>
+        <register derivedFrom=\"CR\">
+          <name>CR2</name>
+          <addressOffset>0x4</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    ",
+    );
+    p.merge(&patch, ValidateLevel::Strict).unwrap();
+
+    assert_eq!(p.derived_from.as_deref(), Some("UART1"));
+    assert_eq!(p.base_address, 0x40004400);
+    let names: Vec<_> = p.registers().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["CR", "CR2"]);
+    let cr2 = p.get_register("CR2").unwrap();
+    assert_eq!(cr2.derived_from.as_deref(), Some("CR"));
+}
+
+#[test]
+fn header_struct_name() {
+    let xml = PERIPHERAL
+        .replace(
+            "<name>TIMER</name>",
+            "<name>TIMER</name><headerStructName>TIM_Type</headerStructName>",
+        )
+        .replace(
+            "<name>CH</name>",
+            "<name>CH</name><headerStructName>TIM_CH_Type</headerStructName>",
+        );
+    let strict = svd_parser::Config::default().validate_level(ValidateLevel::Strict);
+    let p = parse_with_config::<Peripheral>(&xml, &strict).unwrap();
+    assert_eq!(p.header_struct_name.as_deref(), Some("TIM_Type"));
+    assert_eq!(
+        p.get_cluster("CH").unwrap().header_struct_name.as_deref(),
+        Some("TIM_CH_Type")
+    );
+
+    let elem = p.encode().unwrap();
+    assert_eq!(
+        elem.get_child("headerStructName")
+            .unwrap()
+            .get_text()
+            .unwrap(),
+        "TIM_Type"
+    );
+    let mut encoded = Vec::new();
+    elem.write(&mut encoded).unwrap();
+    assert_eq!(parse::<Peripheral>(str::from_utf8(&encoded).unwrap()), p);
+
+    let xml = xml.replace("TIM_CH_Type", "TIM-CH");
+    let err = parse_with_config::<Peripheral>(&xml, &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Name check error: Name `TIM-CH` contains unexpected symbol"
+    );
+    assert!(parse::<Peripheral>(&xml).header_struct_name.is_some());
+}
+
+#[test]
+fn keep_literals() {
+    let xml = "
+    <peripheral>
+      <name>UART</name>
+      <baseAddress>0X40004000</baseAddress>
+      <size>32</size>
+      <registers>
+        <register>
+          <name>CR</name>
+          <addressOffset>#1000</addressOffset>
+          <resetValue>0x0000ffff</resetValue>
+          <resetMask>0xffffffff</resetMask>
+        </register>
+        <cluster>
+          <name>CH</name>
+          <addressOffset>16</addressOffset>
+          <register>
+            <name>CCR</name>
+            <addressOffset>0x0</addressOffset>
+          </register>
+        </cluster>
+      </registers>
+    </peripheral>
+    ";
+    let text = |e: &Element, tag: &str| e.get_child(tag).unwrap().get_text().unwrap().to_string();
+    let register = |e: &Element, name: &str| {
+        e.get_child("registers")
+            .unwrap()
+            .children
+            .iter()
+            .filter_map(XMLNode::as_element)
+            .find(|r| text(r, "name") == name)
+            .unwrap()
+            .clone()
+    };
+
+    // numbers are normalized by default
+    let elem = parse::<Peripheral>(xml).encode().unwrap();
+    assert_eq!(text(&elem, "baseAddress"), "0x40004000");
+    assert_eq!(text(&register(&elem, "CR"), "resetValue"), "0x0000FFFF");
+
+    let config = svd_parser::Config::default().keep_literals(true);
+    let mut p = parse_with_config::<Peripheral>(xml, &config).unwrap();
+    // literals don't take part in comparisons
+    assert_eq!(p, parse::<Peripheral>(xml));
+    let elem = p.encode().unwrap();
+    assert_eq!(text(&elem, "baseAddress"), "0X40004000");
+    assert_eq!(text(&elem, "size"), "32");
+    let cr = register(&elem, "CR");
+    assert_eq!(text(&cr, "addressOffset"), "#1000");
+    assert_eq!(text(&cr, "resetValue"), "0x0000ffff");
+    assert_eq!(text(&cr, "resetMask"), "0xffffffff");
+    assert_eq!(text(&register(&elem, "CH"), "addressOffset"), "16");
+
+    // modified values are formatted by the encoder
+    let cr = p.get_mut_register("CR").unwrap();
+    cr.properties.reset_value = Some(0x1);
+    cr.address_offset = 0x4;
+    let cr = register(&p.encode().unwrap(), "CR");
+    assert_eq!(text(&cr, "addressOffset"), "0x4");
+    assert_eq!(text(&cr, "resetValue"), "0x00000001");
+    assert_eq!(text(&cr, "resetMask"), "0xffffffff");
+}
+
+#[test]
+fn missing_address_offset_inherited_size() {
+    let xml = "
+    <peripheral>
+      <name>GPIO</name>
+      <baseAddress>0x40020000</baseAddress>
+      <size>8</size>
+      <registers>
+        <register>
+          <name>A</name>
+          <addressOffset>0x0</addressOffset>
+        </register>
+        <register>
+          <name>B</name>
+        </register>
+      </registers>
+    </peripheral>
+";
+    let p = parse::<Peripheral>(xml);
+    assert_eq!(p.get_register("B").unwrap().address_offset, 0x1);
+}
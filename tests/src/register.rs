@@ -1,12 +1,15 @@
-use super::run_test;
+use super::{parse, parse_with_config, run_test, warnings};
 use crate::svd::{
-    Access, BitRange, BitRangeType, DimElement, Field, FieldInfo, ModifiedWriteValues, Register,
-    RegisterInfo, ValidateLevel,
+    register, Access, BitRange, BitRangeType, Cluster, DataType, DimElement, Field, FieldInfo,
+    ModifiedWriteValues, ReadAction, Register, RegisterInfo, ValidateLevel,
 };
+use svd_encoder::Encode;
+use svd_parser::Config;
+use xmltree::Element;
 
 #[test]
 fn decode_encode() {
-    let tests = vec![(
+    let tests = [(
         Register::Array(
             RegisterInfo::builder()
                 .name("MODE%s".to_string())
@@ -44,7 +47,7 @@ fn decode_encode() {
 
 #[test]
 fn decode_encode_info() {
-    let tests = vec![(
+    let tests = [(
         RegisterInfo::builder()
             .name("WRITECTRL".to_string())
             .alternate_group(Some("alternate_group".to_string()))
@@ -131,7 +134,7 @@ fn decode_encode_info() {
     encode_config.update("register_reset_mask", "UpperHex16");
     encode_config.update("field_bit_range", "BitRange");
 
-    let tests = vec![(
+    let tests = [(
         RegisterInfo::builder()
             .name("WRITECTRL".to_string())
             .alternate_group(Some("alternate_group".to_string()))
@@ -208,3 +211,691 @@ fn decode_encode_info() {
 
     run_test::<RegisterInfo>(&tests[..], Some(parse_config), Some(encode_config));
 }
+
+#[test]
+fn attach_register_enums() {
+    let xml = "
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>MODE</name>
+              <bitRange>[1:0]</bitRange>
+            </field>
+          </fields>
+          <enumeratedValues>
+            <enumeratedValue>
+              <name>FAST</name>
+              <value>1</value>
+            </enumeratedValue>
+          </enumeratedValues>
+        </register>
+    ";
+    let r: RegisterInfo = parse(xml);
+    assert!(r.get_field("MODE").unwrap().enumerated_values.is_empty());
+
+    let config = Config::default().attach_register_enums(true);
+    let r: RegisterInfo = parse_with_config::<RegisterInfo>(xml, &config).unwrap();
+    let evs = &r.get_field("MODE").unwrap().enumerated_values;
+    assert_eq!(evs.len(), 1);
+    assert_eq!(evs[0].values[0].name, "FAST");
+
+    let ambiguous = "
+        <register>
+          <name>AMBIGUOUS</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>A</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+            <field>
+              <name>B</name>
+              <bitRange>[1:1]</bitRange>
+            </field>
+          </fields>
+          <enumeratedValues>
+            <enumeratedValue>
+              <name>ON</name>
+              <value>1</value>
+            </enumeratedValue>
+          </enumeratedValues>
+        </register>
+    ";
+    let r: RegisterInfo = parse_with_config::<RegisterInfo>(ambiguous, &config).unwrap();
+    assert!(r.fields().all(|f| f.enumerated_values.is_empty()));
+    assert!(warnings()
+        .iter()
+        .any(|w| w.contains("register `AMBIGUOUS`")));
+}
+
+#[test]
+fn mixed_bit_range_styles() {
+    let xml = "
+        <register>
+          <name>CR</name>
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>A</name>
+              <bitRange>[3:0]</bitRange>
+            </field>
+            <field>
+              <name>B</name>
+              <lsb>4</lsb>
+              <msb>7</msb>
+            </field>
+            <field>
+              <name>C</name>
+              <bitOffset>8</bitOffset>
+              <bitWidth>8</bitWidth>
+            </field>
+          </fields>
+        </register>
+    ";
+    let r: RegisterInfo = parse(xml);
+    let styles: Vec<_> = r.fields().map(|f| f.bit_range.range_type).collect();
+    assert_eq!(
+        styles,
+        [
+            BitRangeType::BitRange,
+            BitRangeType::MsbLsb,
+            BitRangeType::OffsetWidth
+        ]
+    );
+
+    // each field keeps its notation when no global style is forced
+    let encoded = r.encode().unwrap();
+    assert_eq!(encoded, Element::parse(xml.as_bytes()).unwrap());
+
+    // global style overrides the per-field one
+    let mut config = svd_encoder::Config::default();
+    config.update("field_bit_range", "OffsetWidth");
+    let encoded = r.encode_with_config(&config).unwrap();
+    for f in encoded.get_child("fields").unwrap().children.iter() {
+        let f = f.as_element().unwrap();
+        assert!(f.get_child("bitOffset").is_some() && f.get_child("bitRange").is_none());
+    }
+}
+
+#[test]
+fn inferred_access() {
+    let field = |name: &str, offset, access| {
+        Field::Single(
+            FieldInfo::builder()
+                .name(name.to_string())
+                .bit_range(BitRange::from_offset_width(offset, 1))
+                .access(access)
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        )
+    };
+    let register = |access, fields| {
+        RegisterInfo::builder()
+            .name("CR".to_string())
+            .address_offset(0)
+            .access(access)
+            .fields(fields)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+
+    assert_eq!(register(None, None).inferred_access(), Access::ReadWrite);
+    assert_eq!(
+        register(Some(Access::ReadOnly), None).inferred_access(),
+        Access::ReadOnly
+    );
+
+    let uniform = vec![
+        field("A", 0, Some(Access::ReadOnly)),
+        field("B", 1, Some(Access::ReadOnly)),
+        field("C", 2, None),
+    ];
+    assert_eq!(
+        register(None, Some(uniform)).inferred_access(),
+        Access::ReadOnly
+    );
+
+    let mixed = vec![
+        field("A", 0, Some(Access::ReadOnly)),
+        field("B", 1, Some(Access::WriteOnly)),
+    ];
+    assert_eq!(
+        register(None, Some(mixed)).inferred_access(),
+        Access::ReadWrite
+    );
+
+    let write = vec![
+        field("A", 0, Some(Access::WriteOnce)),
+        field("B", 1, Some(Access::WriteOnly)),
+    ];
+    assert_eq!(
+        register(None, Some(write)).inferred_access(),
+        Access::WriteOnly
+    );
+
+    let explicit = vec![field("A", 0, Some(Access::ReadOnly))];
+    assert_eq!(
+        register(Some(Access::ReadWrite), Some(explicit)).inferred_access(),
+        Access::ReadWrite
+    );
+}
+
+#[test]
+fn apply_write() {
+    let r: RegisterInfo = parse(
+        "
+        <register>
+          <name>SR</name>
+          <addressOffset>0x0</addressOffset>
+          <size>16</size>
+          <fields>
+            <field>
+              <name>FLAGS</name>
+              <bitRange>[3:0]</bitRange>
+              <modifiedWriteValues>oneToClear</modifiedWriteValues>
+            </field>
+            <field>
+              <name>EN</name>
+              <bitRange>[7:4]</bitRange>
+              <modifiedWriteValues>zeroToSet</modifiedWriteValues>
+            </field>
+            <field>
+              <name>STATE</name>
+              <bitRange>[11:8]</bitRange>
+              <access>read-only</access>
+            </field>
+          </fields>
+        </register>
+    ",
+    );
+
+    let flags = r.get_field("FLAGS").unwrap();
+    assert_eq!(flags.apply_write(0b1111, 0b0101), 0b1010);
+    let en = r.get_field("EN").unwrap();
+    assert_eq!(en.apply_write(0b0000, 0b0101), 0b1010);
+    assert_eq!(en.apply_write(0b0001, 0b1111), 0b0001);
+    let state = r.get_field("STATE").unwrap();
+    assert_eq!(state.apply_write(0b0110, 0b1001), 0b0110);
+
+    // bits 12..16 are not described by fields and are simply written
+    assert_eq!(r.apply_write(0x06F1, 0xF1F3), 0xF6F0);
+}
+
+#[test]
+fn register_level_read_action() {
+    let tests = [(
+        RegisterInfo::builder()
+            .name("ISR".to_string())
+            .address_offset(0x10)
+            .access(Some(Access::ReadWrite))
+            .modified_write_values(Some(ModifiedWriteValues::OneToClear))
+            .read_action(Some(ReadAction::Clear))
+            .fields(Some(vec![Field::Single(
+                FieldInfo::builder()
+                    .name("OVF".to_string())
+                    .bit_range(BitRange::from_offset_width(0, 1))
+                    .build(ValidateLevel::Strict)
+                    .unwrap(),
+            )]))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "
+        <register>
+            <name>ISR</name>
+            <addressOffset>0x10</addressOffset>
+            <access>read-write</access>
+            <modifiedWriteValues>oneToClear</modifiedWriteValues>
+            <readAction>clear</readAction>
+            <fields>
+                <field>
+                    <name>OVF</name>
+                    <bitOffset>0</bitOffset>
+                    <bitWidth>1</bitWidth>
+                </field>
+            </fields>
+        </register>
+        ",
+        "
+        <register>
+            <name>ISR</name>
+            <addressOffset>0x10</addressOffset>
+            <access>read-write</access>
+            <modifiedWriteValues>oneToClear</modifiedWriteValues>
+            <readAction>clear</readAction>
+            <fields>
+                <field>
+                    <name>OVF</name>
+                    <bitOffset>0</bitOffset>
+                    <bitWidth>1</bitWidth>
+                </field>
+            </fields>
+        </register>
+        ",
+    )];
+    run_test::<RegisterInfo>(&tests[..], None, None);
+    let field = tests[0].0.get_field("OVF").unwrap();
+    assert_eq!(field.read_action, None);
+    assert_eq!(field.modified_write_values, None);
+}
+
+#[test]
+fn size_in_bytes() {
+    let xml = "
+        <register>
+            <name>DR</name>
+            <addressOffset>0x0</addressOffset>
+            <size>4</size>
+        </register>
+    ";
+    let config = Config::default().fix_byte_sizes(true);
+    let r = parse_with_config::<RegisterInfo>(xml, &config).unwrap();
+    assert_eq!(r.properties.size, Some(32));
+    assert!(warnings()
+        .iter()
+        .any(|w| w == "Size 4 of `DR` looks like a number of bytes, using 32 bits"));
+
+    let r = parse_with_config::<RegisterInfo>(xml, &Config::default()).unwrap();
+    assert_eq!(r.properties.size, Some(4));
+
+    let strict = config.validate_level(ValidateLevel::Strict);
+    let r = parse_with_config::<RegisterInfo>(xml, &strict).unwrap();
+    assert_eq!(r.properties.size, Some(4));
+}
+
+#[test]
+fn modify_from() {
+    let mut register = RegisterInfo::builder()
+        .name("CR".to_string())
+        .address_offset(0x10)
+        .size(Some(32))
+        .reset_value(Some(0x1234))
+        .build(ValidateLevel::Strict)
+        .unwrap();
+
+    register
+        .modify_from(
+            RegisterInfo::builder().access(Some(Access::WriteOnly)),
+            ValidateLevel::Strict,
+        )
+        .unwrap();
+    assert_eq!(register.name, "CR");
+    assert_eq!(register.address_offset, 0x10);
+    assert_eq!(register.properties.size, Some(32));
+    assert_eq!(register.properties.reset_value, Some(0x1234));
+    assert_eq!(register.properties.access, Some(Access::WriteOnly));
+}
+
+#[test]
+fn expanded_lazy() {
+    let info = RegisterInfo::builder()
+        .name("CCR%s".to_string())
+        .display_name(Some("CCR[%s]".to_string()))
+        .description(Some("Capture/compare %s".to_string()))
+        .address_offset(0x10)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let dim = DimElement::builder()
+        .dim(1024)
+        .dim_increment(4)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let eager: Vec<_> = register::expand(&info, &dim).collect();
+    let array = Register::Array(info.clone(), dim);
+    let lazy: Vec<_> = array.expanded_lazy().collect();
+    assert_eq!(lazy.len(), 1024);
+    assert_eq!(lazy[1023].name(), "CCR1023");
+    assert_eq!(lazy[1023].index(), Some("1023"));
+    assert_eq!(lazy[1023].address_offset(), 0x10 + 1023 * 4);
+    assert_eq!(lazy[1023].description().unwrap(), "Capture/compare 1023");
+    for (l, e) in lazy.iter().zip(&eager) {
+        assert_eq!(&l.cloned(), e);
+    }
+
+    let single = Register::Single(info.clone());
+    let lazy: Vec<_> = single.expanded_lazy().map(|r| r.cloned()).collect();
+    assert_eq!(lazy, [info]);
+}
+
+#[test]
+fn array_placeholders() {
+    let xml = |name: &str, dim: u32| {
+        format!(
+            "
+        <register>
+            <dim>{dim}</dim>
+            <dimIncrement>0x4</dimIncrement>
+            <name>{name}</name>
+            <addressOffset>0x0</addressOffset>
+        </register>"
+        )
+    };
+    for name in ["CH%s", "CH[%s]"] {
+        let r = parse::<Register>(&xml(name, 2));
+        let names: Vec<_> = r.expanded_lazy().map(|r| r.name().into_owned()).collect();
+        assert_eq!(names, ["CH0", "CH1"]);
+    }
+
+    let err = parse_with_config::<Register>(&xml("CH", 2), &Config::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Name `CH` in tag `register` is missing a %s placeholder"
+    );
+    // a single element doesn't need a placeholder
+    let r = parse::<Register>(&xml("CH", 1));
+    assert_eq!(r.expanded_lazy().next().unwrap().name(), "CH");
+
+    let dim = DimElement::builder()
+        .dim(2)
+        .dim_increment(4)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let info = RegisterInfo::builder()
+        .name("CH".to_string())
+        .address_offset(0)
+        .build(ValidateLevel::Strict)
+        .unwrap();
+    let err = Register::Array(info, dim)
+        .validate_all(ValidateLevel::Weak)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Name check error: Array name `CH` is missing a %s placeholder"
+    );
+}
+
+#[test]
+fn expanded() {
+    let xml = "
+    <register>
+      <dim>4</dim>
+      <dimIncrement>4</dimIncrement>
+      <dimIndex>0-3</dimIndex>
+      <name>CCR%s</name>
+      <displayName>CCR_%s</displayName>
+      <description>Capture/compare %s</description>
+      <addressOffset>0x34</addressOffset>
+    </register>";
+    let regs = parse::<Register>(xml).expanded();
+    let names: Vec<_> = regs.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["CCR0", "CCR1", "CCR2", "CCR3"]);
+    assert_eq!(regs[3].display_name.as_deref(), Some("CCR_3"));
+    assert_eq!(regs[3].description.as_deref(), Some("Capture/compare 3"));
+    assert_eq!(regs[3].address_offset, 0x40);
+
+    let xml = "
+    <register>
+      <dim>3</dim>
+      <dimIncrement>0x10</dimIncrement>
+      <dimIndex>A,B,C</dimIndex>
+      <name>GPIO%s_MODE</name>
+      <description>Mode of port %s</description>
+      <addressOffset>0x0</addressOffset>
+    </register>";
+    let regs = parse::<Register>(xml).expanded();
+    let names: Vec<_> = regs.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["GPIOA_MODE", "GPIOB_MODE", "GPIOC_MODE"]);
+    assert_eq!(regs[1].description.as_deref(), Some("Mode of port B"));
+    assert_eq!(regs[2].address_offset, 0x20);
+
+    let single = Register::Single(regs[0].clone());
+    assert_eq!(single.expanded(), [regs[0].clone()]);
+
+    let xml = "
+    <cluster>
+      <dim>2</dim>
+      <dimIncrement>0x20</dimIncrement>
+      <name>CH%s</name>
+      <description>Channel %s</description>
+      <addressOffset>0x100</addressOffset>
+      <register>
+        <name>CR</name>
+        <addressOffset>0x0</addressOffset>
+      </register>
+    </cluster>";
+    let clusters = parse::<Cluster>(xml).expanded();
+    assert_eq!(clusters[1].name, "CH1");
+    assert_eq!(clusters[1].description.as_deref(), Some("Channel 1"));
+    assert_eq!(clusters[1].address_offset, 0x120);
+    assert!(clusters[1].get_register("CR").is_some());
+}
+
+#[test]
+fn field_coverage() {
+    let field = |name: &str, offset, width| {
+        Field::Single(
+            FieldInfo::builder()
+                .name(name.to_string())
+                .bit_range(BitRange::from_offset_width(offset, width))
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        )
+    };
+    let register = |fields| {
+        RegisterInfo::builder()
+            .name("CR".to_string())
+            .address_offset(0)
+            .size(Some(8))
+            .fields(Some(fields))
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+
+    let full = register(vec![
+        field("EN", 0, 1),
+        field("MODE", 1, 3),
+        field("DIV", 4, 4),
+    ]);
+    assert_eq!(full.field_coverage(), 0xff);
+    assert!(full.overlapping_fields().is_empty());
+
+    let gap = register(vec![
+        field("EN", 0, 1),
+        Field::Array(
+            FieldInfo::builder()
+                .name("IE%s".to_string())
+                .bit_range(BitRange::from_offset_width(4, 1))
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+            DimElement::builder()
+                .dim(2)
+                .dim_increment(2)
+                .build(ValidateLevel::Strict)
+                .unwrap(),
+        ),
+    ]);
+    assert_eq!(gap.field_coverage(), 0b0101_0001);
+    assert!(gap.overlapping_fields().is_empty());
+
+    let overlap = register(vec![
+        field("EN", 0, 1),
+        field("MODE", 1, 3),
+        field("FAST", 3, 2),
+    ]);
+    assert_eq!(overlap.field_coverage(), 0x1f);
+    let names: Vec<_> = overlap
+        .overlapping_fields()
+        .into_iter()
+        .map(|(f1, f2)| (f1.name.as_str(), f2.name.as_str()))
+        .collect();
+    assert_eq!(names, [("MODE", "FAST")]);
+}
+
+#[test]
+fn data_type() {
+    let tests = [(
+        RegisterInfo::builder()
+            .name("DATA".to_string())
+            .address_offset(0)
+            .size(Some(8))
+            .data_type(Some(DataType::U8))
+            .fields(Some(vec![FieldInfo::builder()
+                .name("PTR".to_string())
+                .bit_range(BitRange::from_offset_width(0, 8))
+                .data_type(Some(DataType::U8Ptr))
+                .build(ValidateLevel::Strict)
+                .unwrap()
+                .single()]))
+            .build(ValidateLevel::Strict)
+            .unwrap(),
+        "
+        <register>
+          <name>DATA</name>
+          <addressOffset>0x0</addressOffset>
+          <size>8</size>
+          <dataType>uint8_t</dataType>
+          <fields>
+            <field>
+              <name>PTR</name>
+              <bitOffset>0</bitOffset>
+              <bitWidth>8</bitWidth>
+              <dataType>uint8_t *</dataType>
+            </field>
+          </fields>
+        </register>
+        ",
+        "
+        <register>
+          <name>DATA</name>
+          <addressOffset>0x0</addressOffset>
+          <size>0x8</size>
+          <dataType>uint8_t</dataType>
+          <fields>
+            <field>
+              <name>PTR</name>
+              <bitOffset>0</bitOffset>
+              <bitWidth>8</bitWidth>
+              <dataType>uint8_t *</dataType>
+            </field>
+          </fields>
+        </register>
+        ",
+    )];
+    run_test::<RegisterInfo>(&tests[..], None, None);
+
+    let err = parse_with_config::<RegisterInfo>(
+        "
+        <register>
+          <name>DATA</name>
+          <addressOffset>0x0</addressOffset>
+          <dataType>char</dataType>
+        </register>
+        ",
+        &Config::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Invalid dataType variant, found char");
+}
+
+#[test]
+fn stray_text() {
+    let xml = "
+        <register>
+          <name>DATA</name>
+          garbage
+          <addressOffset>0x0</addressOffset>
+          <fields>
+            <field>
+              <name>EN</name>
+              <bitRange>[0:0]</bitRange>
+            </field>
+          </fields>
+        </register>
+    ";
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+    let err = parse_with_config::<Register>(xml, &strict).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected text `garbage` in <register>");
+    assert_eq!(parse::<Register>(xml).name, "DATA");
+
+    let xml = xml
+        .replace("garbage", "")
+        .replace("<field>", "<field> garbage");
+    let err = parse_with_config::<Register>(&xml, &strict).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected text `garbage` in <field>");
+    let weak = Config::default().validate_level(ValidateLevel::Weak);
+    assert!(parse_with_config::<Register>(&xml, &weak).is_ok());
+}
+
+#[test]
+fn promote_collapse_array() {
+    let dim = |increment| {
+        DimElement::builder()
+            .dim(2)
+            .dim_increment(increment)
+            .build(ValidateLevel::Strict)
+            .unwrap()
+    };
+    let mut reg = RegisterInfo::builder()
+        .name("CH%s".to_string())
+        .description(Some("Channel %s".to_string()))
+        .address_offset(0x10)
+        .size(Some(32))
+        .build_register(ValidateLevel::Strict)
+        .unwrap();
+    assert!(reg.is_single());
+
+    reg.modify_from(
+        RegisterInfo::builder().dim(Some(dim(4))),
+        ValidateLevel::Strict,
+    )
+    .unwrap();
+    let names: Vec<_> = reg.expanded().into_iter().map(|r| r.name).collect();
+    assert_eq!(names, ["CH0", "CH1"]);
+
+    let err = reg
+        .modify_from(
+            RegisterInfo::builder().dim(Some(dim(2))),
+            ValidateLevel::Strict,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Register error: dimIncrement 0x2 is smaller than the register size of 32 bits"
+    );
+
+    let single = reg.into_single();
+    assert!(single.is_single());
+    assert_eq!(single.name, "CH0");
+    assert_eq!(single.description.as_deref(), Some("Channel 0"));
+    assert_eq!(single.address_offset, 0x10);
+
+    let err = RegisterInfo::builder()
+        .name("CTRL".to_string())
+        .address_offset(0)
+        .dim(Some(dim(4)))
+        .build_register(ValidateLevel::Strict)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Name check error: Array name `CTRL` is missing a %s placeholder"
+    );
+}
+
+#[test]
+fn field_exceeds_register_size() {
+    let register = |size: Option<u32>, offset, width| {
+        RegisterInfo::builder()
+            .name("CTRL".to_string())
+            .address_offset(0)
+            .size(size)
+            .fields(Some(vec![FieldInfo::builder()
+                .name("VAL".to_string())
+                .bit_range(BitRange::from_offset_width(offset, width))
+                .build(ValidateLevel::Strict)
+                .unwrap()
+                .single()]))
+    };
+    assert!(register(Some(32), 24, 8)
+        .build(ValidateLevel::Strict)
+        .is_ok());
+    let err = register(Some(32), 25, 8)
+        .build(ValidateLevel::Strict)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Field error: Field `VAL` ends at bit 32, beyond the register size of 32 bits"
+    );
+    assert!(register(Some(32), 25, 8).build(ValidateLevel::Weak).is_ok());
+    assert!(register(None, 60, 8).build(ValidateLevel::Strict).is_ok());
+}
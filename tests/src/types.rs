@@ -0,0 +1,90 @@
+use super::{parse_with_config, warnings};
+use crate::svd::ValidateLevel;
+use roxmltree::Document;
+use svd_parser::types::NumberParse;
+use svd_parser::{Config, Parse};
+
+#[test]
+fn annotated_numbers() {
+    let lenient = Config::default();
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+
+    // comments split the text, only the number is read
+    let xml = "<size>32 <!-- bits --></size>";
+    assert_eq!(
+        parse_with_config::<NumberParse<u32>>(xml, &lenient).unwrap(),
+        32
+    );
+    assert_eq!(
+        parse_with_config::<NumberParse<u32>>(xml, &strict).unwrap(),
+        32
+    );
+
+    let xml = "<baseAddress>0x40000000 ; timer</baseAddress>";
+    assert_eq!(
+        parse_with_config::<NumberParse<u64>>(xml, &lenient).unwrap(),
+        0x4000_0000
+    );
+    assert!(warnings()
+        .iter()
+        .any(|w| w == "Ignoring `; timer` after number `0x40000000` in <baseAddress>"));
+    assert!(parse_with_config::<NumberParse<u64>>(xml, &strict).is_err());
+
+    let xml = "<size>32 bits</size>";
+    assert_eq!(
+        parse_with_config::<NumberParse<u32>>(xml, &lenient).unwrap(),
+        32
+    );
+    assert!(parse_with_config::<NumberParse<u32>>(xml, &strict).is_err());
+
+    let xml = "<size>32;16 bits</size>";
+    assert_eq!(
+        parse_with_config::<NumberParse<u32>>(xml, &lenient).unwrap(),
+        32
+    );
+
+    // garbage is still rejected
+    for xml in [
+        "<baseAddress>0x10 0x20</baseAddress>",
+        "<size>32 16</size>",
+        "<size>4 8</size>",
+        "<size>32bits</size>",
+        "<size>bits 32</size>",
+        "<size>foo</size>",
+    ] {
+        assert!(parse_with_config::<NumberParse<u32>>(xml, &lenient).is_err());
+    }
+
+    // plain `u32` doesn't take a config and stays strict
+    let doc = Document::parse("<size>32 bits</size>").unwrap();
+    assert!(u32::parse(&doc.root_element(), &()).is_err());
+}
+
+#[test]
+fn digit_separators() {
+    let lenient = Config::default();
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+
+    for config in [&lenient, &strict] {
+        let xml = "<baseAddress>0x4000_0000</baseAddress>";
+        assert_eq!(
+            parse_with_config::<NumberParse<u64>>(xml, config).unwrap(),
+            0x4000_0000
+        );
+        let xml = "<size>4_096</size>";
+        assert_eq!(
+            parse_with_config::<NumberParse<u32>>(xml, config).unwrap(),
+            4096
+        );
+    }
+
+    let xml = "<baseAddress>0x4000 0000</baseAddress>";
+    assert_eq!(
+        parse_with_config::<NumberParse<u64>>(xml, &lenient).unwrap(),
+        0x4000_0000
+    );
+    assert!(warnings()
+        .iter()
+        .any(|w| w == "Ignoring spaces in number `0x4000 0000` in <baseAddress>"));
+    assert!(parse_with_config::<NumberParse<u64>>(xml, &strict).is_err());
+}
@@ -1,9 +1,10 @@
-use super::run_test;
-use crate::svd::{WriteConstraint, WriteConstraintRange};
+use super::{parse_with_config, run_test};
+use crate::svd::{Field, Register, ValidateLevel, WriteConstraint, WriteConstraintRange};
+use svd_parser::Config;
 
 #[test]
 fn decode_encode() {
-    let tests = vec![
+    let tests = [
         (
             WriteConstraint::WriteAsRead(true),
             "<writeConstraint><writeAsRead>true</writeAsRead></writeConstraint>",
@@ -23,3 +24,39 @@ fn decode_encode() {
 
     run_test::<WriteConstraint>(&tests[..], None, None);
 }
+
+#[test]
+fn validate_range() {
+    let field = |min: u32, max: u32| {
+        format!(
+            "<field>
+              <name>MODE</name>
+              <bitRange>[3:0]</bitRange>
+              <writeConstraint><range><minimum>{min}</minimum><maximum>{max}</maximum></range></writeConstraint>
+            </field>"
+        )
+    };
+    let strict = Config::default().validate_level(ValidateLevel::Strict);
+    let weak = Config::default().validate_level(ValidateLevel::Weak);
+
+    assert!(parse_with_config::<Field>(&field(1, 15), &strict).is_ok());
+    let err = parse_with_config::<Field>(&field(10, 1), &strict).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`WriteConstraint error: Range minimum 10 is greater than maximum 1"
+    );
+    assert!(parse_with_config::<Field>(&field(10, 1), &weak).is_ok());
+    let err = parse_with_config::<Field>(&field(1, 16), &weak).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`WriteConstraint error: Value 16 out of range 0..16"
+    );
+
+    let register = "<register>
+      <name>CR</name>
+      <addressOffset>0x0</addressOffset>
+      <writeConstraint><range><minimum>3</minimum><maximum>2</maximum></range></writeConstraint>
+    </register>";
+    assert!(parse_with_config::<Register>(register, &strict).is_err());
+    assert!(parse_with_config::<Register>(register, &weak).is_ok());
+}